@@ -0,0 +1,164 @@
+use std::hint::black_box;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use bytes::{Buf, Bytes};
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use hyper::body::{Body, Frame};
+use ruxy_core::server::response::body::ResponseBody;
+use ruxy_core::server::response::chunk::ResponseChunk;
+
+/// The body used before `ResponseBuffer`, a growable vector sending one frame per chunk.
+struct VecBody {
+  bytes: Vec<Bytes>,
+  tail_cursor: usize,
+}
+
+impl VecBody {
+  fn new() -> Self {
+    Self { bytes: Vec::new(), tail_cursor: 0 }
+  }
+
+  fn push(&mut self, bytes: Bytes) {
+    self.bytes.push(bytes);
+  }
+}
+
+impl Body for VecBody {
+  type Data = Bytes;
+  type Error = std::convert::Infallible;
+
+  fn poll_frame(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+    let index = self.tail_cursor;
+
+    let Some(bytes) = self.bytes.get_mut(index) else {
+      return Poll::Ready(None);
+    };
+
+    let bytes = std::mem::take(bytes);
+    self.tail_cursor += 1;
+    Poll::Ready(Some(Ok(Frame::data(bytes))))
+  }
+}
+
+/// Polls all the frames of the body, the way the connection would, without copying their data.
+/// The writes themselves aren't measured – one per frame for the baseline, a single vectored one
+/// for `ResponseBody` – so this only compares the cost of assembling and framing the chunks.
+fn drain<B: Body + Unpin>(mut body: B) -> usize {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut written = 0;
+
+  while let Poll::Ready(Some(Ok(frame))) = Pin::new(&mut body).poll_frame(&mut cx) {
+    let Ok(mut data) = frame.into_data() else {
+      continue;
+    };
+
+    while data.has_remaining() {
+      let len = black_box(data.chunk()).len();
+      data.advance(len);
+      written += len;
+    }
+  }
+
+  written
+}
+
+/// Chunk counts of a small page, a page with a long list, and a page with a very long list.
+const CHUNK_COUNTS: [usize; 3] = [16, 256, 4096];
+
+/// Assembles a body of `count` chunks sized upfront, the way the generated responders do.
+fn assemble_sized(count: usize) -> ResponseBody {
+  let mut body = ResponseBody::with_capacity(count);
+
+  for _ in 0..count {
+    body.push(Bytes::from_static(b"<div>chunk</div>"));
+  }
+
+  body
+}
+
+fn bench_assemble(c: &mut Criterion) {
+  let mut group = c.benchmark_group("assemble");
+
+  for count in CHUNK_COUNTS {
+    group.bench_with_input(BenchmarkId::new("vec", count), &count, |b, &count| {
+      b.iter(|| {
+        let mut body = VecBody::new();
+
+        for _ in 0..count {
+          body.push(Bytes::from_static(b"<div>chunk</div>"));
+        }
+
+        drain(body)
+      })
+    });
+
+    group.bench_with_input(BenchmarkId::new("response_buffer", count), &count, |b, &count| {
+      b.iter(|| drain(assemble_sized(count)))
+    });
+  }
+
+  group.finish();
+}
+
+/// The chunks of a list of `count` items, three per item.
+fn list_items(count: usize) -> Vec<Bytes> {
+  let mut items = Vec::with_capacity(count * 3);
+
+  for i in 0..count {
+    items.push(Bytes::from_static(b"<li>"));
+    items.push(Bytes::from(i.to_string()));
+    items.push(Bytes::from_static(b"</li>"));
+  }
+
+  items
+}
+
+/// The same chunks nested the way a rendered list is, one chunk per item wrapped in a chunk for the list.
+fn nested_list_items(count: usize) -> ResponseChunk {
+  let items = list_items(count);
+  let items = items.chunks(3).map(|item| item.iter().cloned().map(ResponseChunk::from).collect::<Vec<_>>());
+
+  ResponseChunk::from(items.map(ResponseChunk::from).collect::<Vec<_>>())
+}
+
+fn bench_nested(c: &mut Criterion) {
+  let mut group = c.benchmark_group("nested");
+
+  // The chunks are produced outside of the measurement, only assembling and polling the bodies is measured
+  for count in CHUNK_COUNTS {
+    group.bench_with_input(BenchmarkId::new("vec", count), &count, |b, &count| {
+      b.iter_batched(
+        || list_items(count),
+        |items| {
+          let mut body = VecBody::new();
+
+          for bytes in items {
+            body.push(bytes);
+          }
+
+          drain(body)
+        },
+        BatchSize::SmallInput,
+      )
+    });
+
+    group.bench_with_input(BenchmarkId::new("response_buffer", count), &count, |b, &count| {
+      b.iter_batched(
+        || nested_list_items(count),
+        |list| {
+          let mut body = ResponseBody::new();
+          body.push_chunk(list);
+
+          drain(body)
+        },
+        BatchSize::SmallInput,
+      )
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, bench_assemble, bench_nested);
+criterion_main!(benches);
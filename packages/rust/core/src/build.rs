@@ -1,17 +1,18 @@
-mod app;
+pub(crate) mod app;
 mod build_config;
 pub mod client;
+pub mod manifest;
 mod routes_hints;
 
 use std::path::MAIN_SEPARATOR;
 
-use crate::constants::{APP_DIR, CONFIG_FILE, DOT_RUXY_DIR, GENERATED_APP_RS_FILE, ROUTES_DIR};
+use crate::constants::{APP_DIR, CONFIG_FILE, DOT_RUXY_DIR, GENERATED_APP_RS_FILE, PUBLIC_DIR, ROUTES_DIR};
 use crate::routing::routary::Routary;
 use crate::util::fs::{get_project_dir, get_ruxy_out_dir};
 
 use build_config::BuildMode;
 
-pub use build_config::BuildConfig;
+pub use build_config::{BuildConfig, ENV_BUILD_MODE};
 
 pub fn build(config: BuildConfig) {
   // `get_app_config()` is usable here too (initialized by `build!` macro)
@@ -42,6 +43,7 @@ pub fn build(config: BuildConfig) {
   // Emit watch hints for routes and special files
 
   println!("[ruxy] emitting watch hints");
+  println!("cargo::rerun-if-env-changed={ENV_BUILD_MODE}");
 
   let config_path = project_dir.join(APP_DIR).join(CONFIG_FILE);
 
@@ -50,4 +52,13 @@ pub fn build(config: BuildConfig) {
   }
 
   routes_hints::emit_watch_hints_for_dir(&routes_dir);
+
+  // Cargo scans the whole directory for modifications, so the index is regenerated when public files change.
+  // The hint is emitted even when the directory doesn't exist (the index is empty then), Cargo reruns
+  // the build script until it's created, so that a newly added `public/` is picked up.
+  let public_dir = project_dir.join(PUBLIC_DIR);
+
+  if let Some(public_dir) = public_dir.to_str() {
+    println!("cargo::rerun-if-changed={public_dir}");
+  }
 }
@@ -1,8 +1,9 @@
 mod context;
-mod errors;
+pub(crate) mod errors;
 mod handler;
 mod input;
 mod main;
+mod public;
 mod routes;
 
 use proc_macro2::{Ident, Span, TokenStream};
@@ -14,6 +15,7 @@ use crate::routing::routary::Routary;
 
 use context::GenContext;
 use errors::render_errors;
+use public::gen_public_index;
 use routes::gen_route_modules;
 
 pub fn ruxy_app(routary: &Routary, build_config: &BuildConfig) -> Result<TokenStream, TokenStream> {
@@ -26,7 +28,13 @@ pub fn ruxy_app(routary: &Routary, build_config: &BuildConfig) -> Result<TokenSt
   let handler_functions = handler::gen_handler_functions(&ctx);
   let main_function = main::gen_main_function();
 
-  let errors = routary.get_compile_errors();
+  // Public files that can't be embedded are reported along with the errors of the routes
+  let mut errors = routary.get_compile_errors();
+  let public_index = gen_public_index(&mut errors);
+
+  let mut route_ids: Vec<&str> = routary.segment_map.values().map(|segment| segment.identifier.as_str()).collect();
+  route_ids.sort_unstable();
+
   let errors = render_errors(errors);
 
   let main_fn_ident = Ident::new("main", Span::call_site());
@@ -38,7 +46,11 @@ pub fn ruxy_app(routary: &Routary, build_config: &BuildConfig) -> Result<TokenSt
     use ::ruxy::__ruxy_macro_internal as internal;
 
     pub(super) fn #main_fn_ident() {
+      #public_index
+
       internal::register_app_config(config::config());
+      internal::register_public_files(&PUBLIC_FILES);
+      internal::register_routes(&[#(#route_ids),*]);
 
       struct App;
 
@@ -1,13 +1,17 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+/// The beginning of the message of the `compile_error!` listing the errors, see `render_errors`.
+/// The CLI recognizes the message by it, so that it can print the errors without the compiler's noise.
+pub const ERRORS_HEAD: &str = "Ruxy cannot compile your application";
+
 pub fn render_errors(errors: Vec<String>) -> TokenStream {
   if errors.is_empty() {
     return TokenStream::new();
   }
 
   let errors_head = format!(
-    "Ruxy cannot compile your application due to the following {count}error{plural}:",
+    "{ERRORS_HEAD} due to the following {count}error{plural}:",
     count = if errors.len() == 1 { "".to_owned() } else { format!("{} ", errors.len()) },
     plural = if errors.len() == 1 { "" } else { "s" }
   );
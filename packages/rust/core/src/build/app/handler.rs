@@ -1,4 +1,5 @@
 mod global_404;
+mod precompress;
 mod responder;
 mod matcher;
 mod trailing_slash;
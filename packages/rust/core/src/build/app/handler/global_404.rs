@@ -13,7 +13,8 @@ pub fn gen_global_404() -> TokenStream {
     body.push(internal::Bytes::from("Not Found"));
 
     internal::HandlerResult {
-      response: response.body(body)
+      response: response.body(body),
+      route_id: None,
     }
   }
 }
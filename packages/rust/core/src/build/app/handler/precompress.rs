@@ -0,0 +1,31 @@
+use bytes::Bytes;
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+use crate::config::{CompressionLevel, ContentEncoding, get_app_config};
+use crate::server::compression::compress;
+
+/// Generates an `internal::Precompressed` expression holding the content along with its variants
+/// compressed with the best compression, for each of the encodings enabled in the app config.
+pub fn gen_precompressed(content: &[u8]) -> TokenStream {
+  let config = &get_app_config().compression;
+  let chunks = [Bytes::copy_from_slice(content)];
+
+  let variant = |encoding: ContentEncoding| {
+    let compressed = match config.enabled && config.encodings.contains(&encoding) {
+      true => compress(encoding, CompressionLevel::Best, &chunks).unwrap_or_default(),
+      false => Vec::new(),
+    };
+
+    Literal::byte_string(&compressed)
+  };
+
+  let identity = Literal::byte_string(content);
+  let gzip = variant(ContentEncoding::Gzip);
+  let brotli = variant(ContentEncoding::Brotli);
+  let zstd = variant(ContentEncoding::Zstd);
+
+  quote! {
+    internal::Precompressed { identity: #identity, gzip: #gzip, brotli: #brotli, zstd: #zstd }
+  }
+}
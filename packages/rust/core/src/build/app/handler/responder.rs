@@ -1,14 +1,25 @@
+#[cfg(test)]
+mod test;
+
 mod loader_call;
+mod method_guard;
 
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 
 use crate::routing::routary::Routary;
-use crate::routing::segment::{DynamicSequence, EitherTarget, HandlerTarget, RenderTarget, RouteSegment, SegmentEffect, TypedSequence};
+use crate::routing::segment::{
+  DynamicSequence, EitherTarget, HandlerTarget, RenderTarget, RouteSegment, RouteSegmentRsModule, SegmentEffect,
+  TypedSequence,
+};
+use crate::server::conditional::ETag;
 
 use crate::build::app::context::GenContext;
+use crate::build::app::handler::precompress::gen_precompressed;
 use crate::build::app::handler::responder::loader_call::gen_loader_call;
+use crate::build::app::handler::responder::method_guard::gen_method_guard;
 use crate::build::build_config::BuildMode;
+use crate::config::get_app_config;
 
 pub fn gen_segment_responder(ctx: &GenContext, segment: &RouteSegment) -> TokenStream {
   let identifier = &segment.identifier;
@@ -21,78 +32,345 @@ pub fn gen_segment_responder(ctx: &GenContext, segment: &RouteSegment) -> TokenS
   //       all the params and returned Props (if page.rs exists), and instructions on how to
   //       create the client page.
 
+  let method_guard = gen_method_guard(segment);
+
   let responder = match (&segment.route_target, &ctx.build_config.mode) {
+    (Some(EitherTarget::Render(target)), BuildMode::Production)
+      if target.rs_module.is_none() && path_params.is_empty() =>
+    {
+      let responder = gen_static_page_responder(segment);
+      let responder = wrap_with_middleware(ctx, segment, quote! { #method_guard #responder });
+      let responder = wrap_with_timeout(ctx, segment, responder);
+      let responder = wrap_with_rate_limit(ctx, segment, responder);
+
+      return wrap_with_cors(ctx, segment, responder);
+    }
+    (Some(EitherTarget::Handler(target)), _) => {
+      let responder = gen_handler_responder(ctx, segment, target);
+      let responder = wrap_with_middleware(ctx, segment, responder);
+      let responder = wrap_with_timeout(ctx, segment, responder);
+      let responder = wrap_with_rate_limit(ctx, segment, responder);
+
+      return wrap_with_cors(ctx, segment, responder);
+    }
     (Some(EitherTarget::Render(target)), BuildMode::Development) => gen_page_responder_dev(ctx, segment, target),
     (Some(EitherTarget::Render(target)), BuildMode::Production) => gen_page_responder_prod(ctx, segment, target),
-    (Some(EitherTarget::Handler(target)), _) => gen_handler_responder(ctx, segment, target),
     _ => unreachable!("responder generator called for segment without a route target")
   };
 
-  quote! {
+  // The loader call binds the `etag`, the `last_modified` time and the `loaded` output of the loader
+  let (validators, hydration) = match &segment.route_target {
+    Some(EitherTarget::Render(RenderTarget { rs_module: Some(_), .. })) => (
+      quote! { response = internal::with_validators(response, etag, last_modified); },
+      quote! {
+        body.push(internal::script_open_tag());
+        body.push(internal::Bytes::from(loaded.__internal_hydration_script()));
+        body.push(internal::Bytes::from_static(b"</script>"));
+      },
+    ),
+    _ => (quote! {}, quote! {}),
+  };
+
+  let hydration_chunks = if hydration.is_empty() { 0 } else { HYDRATION_CHUNKS };
+
+  let page_head = page_head_chunks(identifier);
+  let (params_close, page_tail) = PAGE_TAIL_CHUNKS.split_first().unwrap();
+
+  // Known at build time, so the body is sized for the page and never spills while it's assembled
+  let chunk_count = page_head.len() + path_params.len() * PATH_PARAM_CHUNKS + hydration_chunks + PAGE_TAIL_CHUNKS.len();
+
+  let responder = quote! {
+    #method_guard
     #responder
 
     let mut response = hyper::Response::builder();
 
     response = response.status(200);
     response = response.header("Content-Type", "text/html");
+    #validators
+
+    let mut body = internal::ResponseBody::with_capacity(#chunk_count);
 
-    let mut body = internal::ResponseBody::new();
-
-    body.push(internal::Bytes::from("<!DOCTYPE html>"));
-    body.push(internal::Bytes::from("<html>"));
-    body.push(internal::Bytes::from("<head>"));
-    body.push(internal::Bytes::from("<meta charset=\"utf-8\" />"));
-    body.push(internal::Bytes::from("</head>"));
-    body.push(internal::Bytes::from("<body>"));
-    body.push(internal::Bytes::from("<div>Matched handler:</div>"));
-    body.push(internal::Bytes::from("<div style=\"color: red;\">"));
-    body.push(internal::Bytes::from(#identifier));
-    body.push(internal::Bytes::from("</div>"));
-    body.push(internal::Bytes::from("<div style=\"margin-top: 16px;\">Path params:</div>"));
-    body.push(internal::Bytes::from("<div style=\"color: darkgreen;\">"));
+    #(body.push(internal::Bytes::from(#page_head));)*
     #(#path_params)*
-    body.push(internal::Bytes::from("</div>"));
-    body.push(internal::Bytes::from("</body>"));
-    body.push(internal::Bytes::from("</html>"));
+    body.push(internal::Bytes::from(#params_close));
+    #hydration
+    #(body.push(internal::Bytes::from(#page_tail));)*
 
     return internal::HandlerResult {
-      response: response.body(body)
+      response: response.body(body),
+      route_id: Some(#identifier),
+    };
+  };
+
+  let responder = wrap_with_middleware(ctx, segment, responder);
+  let responder = wrap_with_timeout(ctx, segment, responder);
+  let responder = wrap_with_rate_limit(ctx, segment, responder);
+
+  wrap_with_cors(ctx, segment, responder)
+}
+
+/// The static chunks of the page preceding the path params.
+fn page_head_chunks(identifier: &str) -> [&str; 12] {
+  [
+    "<!DOCTYPE html>",
+    "<html>",
+    "<head>",
+    "<meta charset=\"utf-8\" />",
+    "</head>",
+    "<body>",
+    "<div>Matched handler:</div>",
+    "<div style=\"color: red;\">",
+    identifier,
+    "</div>",
+    "<div style=\"margin-top: 16px;\">Path params:</div>",
+    "<div style=\"color: darkgreen;\">",
+  ]
+}
+
+/// The static chunks of the page following the path params.
+const PAGE_TAIL_CHUNKS: [&str; 3] = ["</div>", "</body>", "</html>"];
+
+/// The number of chunks pushed for each path param, see `extract_path_params`.
+const PATH_PARAM_CHUNKS: usize = 3;
+
+/// The number of chunks of the script passing the Props to the page – the opening tag
+/// carrying the CSP nonce, the data and the closing tag.
+const HYDRATION_CHUNKS: usize = 3;
+
+/// Generates a responder for a page that doesn't depend on the request at all. The whole page is
+/// rendered and compressed at build time, so serving it doesn't require any rendering or compression.
+/// Its strong entity tag is derived from the rendered page at build time too. No `Last-Modified` is sent,
+/// the build time would change with every build even though the page didn't.
+fn gen_static_page_responder(segment: &RouteSegment) -> TokenStream {
+  let identifier = &segment.identifier;
+
+  let html = [page_head_chunks(identifier).as_slice(), PAGE_TAIL_CHUNKS.as_slice()].concat().concat();
+  let precompressed = gen_precompressed(html.as_bytes());
+
+  let etag = ETag::from_content(html.as_bytes());
+  let etag = etag.tag();
+
+  quote! {
+    static PAGE: internal::Precompressed = #precompressed;
+
+    let etag = internal::ETag::strong(#etag);
+
+    if internal::is_not_modified(request.method(), request.headers(), Some(&etag), None) {
+      return internal::HandlerResult { response: internal::not_modified(Some(&etag), None), route_id: Some(#identifier) };
+    }
+
+    let response = internal::with_validators(hyper::Response::builder(), Some(&etag), None)
+      .status(200)
+      .header("Content-Type", "text/html")
+      .body(internal::ResponseBody::from_precompressed(&PAGE));
+
+    return internal::HandlerResult { response, route_id: Some(#identifier) };
+  }
+}
+
+/// Wraps the responder with the middleware of the segment and all its ancestors, the root-most
+/// middleware being the outermost one. Segments without any middleware are left untouched.
+fn wrap_with_middleware(ctx: &GenContext, segment: &RouteSegment, responder: TokenStream) -> TokenStream {
+  let middleware = collect_middleware(segment, ctx.routary);
+
+  if middleware.is_empty() {
+    return responder;
+  }
+
+  let identifier = &segment.identifier;
+
+  // Each middleware receives a `next` continuation running the rest of the chain. The chain is
+  // built from the innermost continuation (the responder itself) outward, so that nothing is boxed.
+  let mut chain = quote! {
+    internal::NextFn(move || async move {
+      let result: internal::HandlerResult = async move { #responder }.await;
+      internal::into_response(result.response)
+    })
+  };
+
+  for module in middleware.iter().rev() {
+    let module_name = Ident::new(&module.name, Span::mixed_site());
+
+    chain = quote! {
+      internal::NextFn(move || #module_name::inner::middleware(request, #chain))
     };
   }
+
+  quote! {
+    let request = &request;
+    let response = internal::Next::run(#chain).await;
+
+    return internal::HandlerResult { response: Ok(response), route_id: Some(#identifier) };
+  }
 }
 
-fn extract_path_params(segment: &RouteSegment, routes: &Routary) -> Vec<TokenStream> {
-  let mut v = Vec::new();
+/// Wraps the responder, including its middleware, with the CORS policy of the nearest segment defining one,
+/// or with the global policy. Preflight requests are answered before any middleware or Route Target runs.
+fn wrap_with_cors(ctx: &GenContext, segment: &RouteSegment, responder: TokenStream) -> TokenStream {
+  let identifier = &segment.identifier;
 
-  if let SegmentEffect::UrlMatcher { sequences } = &segment.effect {
-    let param_names = sequences.iter().filter_map(|s| {
-      let TypedSequence::Dynamic(DynamicSequence { param_name, .. }) = &s.typed else {
-        return None;
-      };
+  let policy = match find_nearest_module(segment, ctx.routary, |segment| segment.cors_target.as_ref()) {
+    Some(module) => {
+      let module_name = Ident::new(&module.name, Span::mixed_site());
+
+      quote! {
+        static CORS: std::sync::LazyLock<internal::CorsConfig> = std::sync::LazyLock::new(#module_name::inner::cors);
+        let cors: &internal::CorsConfig = &CORS;
+      }
+    }
+    None if get_app_config().cors.enabled => quote! { let cors = &internal::get_app_config().cors; },
+    None => return responder,
+  };
+
+  quote! {
+    #policy
+
+    if cors.enabled && internal::cors::is_preflight(request.method(), request.headers()) {
+      let response = internal::cors::preflight_response(cors, request.headers());
+      return internal::HandlerResult { response, route_id: Some(#identifier) };
+    }
+
+    let origin = request.headers().get(hyper::header::ORIGIN).cloned();
+    let mut result: internal::HandlerResult = async { #responder }.await;
+
+    if cors.enabled && let Ok(response) = &mut result.response {
+      internal::cors::apply(cors, origin.as_ref(), response);
+    }
 
-      Some(param_name)
-    });
+    return result;
+  }
+}
+
+/// Wraps the responder, including its middleware, with the rate limit of the nearest segment defining one.
+/// Requests over the limit are answered with `429 Too Many Requests` before any middleware runs.
+fn wrap_with_rate_limit(ctx: &GenContext, segment: &RouteSegment, responder: TokenStream) -> TokenStream {
+  let Some(module) = find_nearest_module(segment, ctx.routary, |segment| segment.rate_limit_target.as_ref()) else {
+    return responder;
+  };
+
+  let identifier = &segment.identifier;
+  let module_name = Ident::new(&module.name, Span::mixed_site());
+
+  // The module name is unique to the segment defining the limit, so the whole subtree shares the budget
+  let subtree_id = &module.name;
+
+  quote! {
+    static RATE_LIMIT: std::sync::LazyLock<internal::RateLimit> =
+      std::sync::LazyLock::new(#module_name::inner::rate_limit);
+
+    if let Some(response) = internal::rate_limit::check(&RATE_LIMIT, #subtree_id, &request).await {
+      return internal::HandlerResult { response, route_id: Some(#identifier) };
+    }
+
+    #responder
+  }
+}
+
+/// Wraps the responder, including its middleware, with the timeouts of the nearest segment defining them,
+/// or with the global ones. Binds the `route_timeout` used for the deadlines of the loader calls.
+fn wrap_with_timeout(ctx: &GenContext, segment: &RouteSegment, responder: TokenStream) -> TokenStream {
+  let identifier = &segment.identifier;
+
+  let route_timeout = match find_nearest_module(segment, ctx.routary, |segment| segment.timeout_target.as_ref()) {
+    Some(module) => {
+      let module_name = Ident::new(&module.name, Span::mixed_site());
+
+      quote! {
+        static TIMEOUT: std::sync::LazyLock<internal::RouteTimeout> =
+          std::sync::LazyLock::new(#module_name::inner::timeout);
+        let route_timeout = *TIMEOUT;
+      }
+    }
+    None => quote! { let route_timeout = internal::RouteTimeout::default(); },
+  };
+
+  quote! {
+    #route_timeout
+
+    return internal::timeout::request(route_timeout.request, #identifier, async { #responder }).await;
+  }
+}
+
+/// Returns the module of the segment or its nearest ancestor defining the target selected by `select`.
+fn find_nearest_module<'a>(
+  segment: &'a RouteSegment,
+  routes: &'a Routary,
+  select: impl Fn(&'a RouteSegment) -> Option<&'a HandlerTarget>,
+) -> Option<&'a RouteSegmentRsModule> {
+  let mut current = Some(segment);
+
+  while let Some(segment) = current {
+    if let Some(target) = select(segment) {
+      return Some(&target.rs_module);
+    }
+
+    current = segment.parent.as_ref().and_then(|parent| routes.segment_map.get(parent));
+  }
+
+  None
+}
 
-    for param_name in param_names {
-      let dyn_var_ident = format!("path_param_{param_name}");
-      let dyn_var_ident = Ident::new(&dyn_var_ident, Span::mixed_site());
+/// Returns the middleware modules applying to the segment, from the root segment down to the segment itself.
+fn collect_middleware<'a>(segment: &'a RouteSegment, routes: &'a Routary) -> Vec<&'a RouteSegmentRsModule> {
+  let mut middleware = Vec::new();
+  let mut current = Some(segment);
 
-      v.push(quote! {
+  while let Some(segment) = current {
+    if let Some(target) = &segment.middleware_target {
+      middleware.push(&target.rs_module);
+    }
+
+    current = segment.parent.as_ref().and_then(|parent| routes.segment_map.get(parent));
+  }
+
+  middleware.reverse();
+  middleware
+}
+
+fn extract_path_params(segment: &RouteSegment, routes: &Routary) -> Vec<TokenStream> {
+  let param_names = collect_path_param_names(segment, routes);
+
+  param_names
+    .into_iter()
+    .map(|param_name| {
+      let dyn_var_ident = path_param_ident(param_name);
+
+      quote! {
         body.push(internal::Bytes::from("<div>"));
         let formatted = format!("{}: {:?}", #param_name, #dyn_var_ident);
         body.push(internal::Bytes::from(formatted));
         body.push(internal::Bytes::from("</div>"));
-      });
-    }
+      }
+    })
+    .collect()
+}
+
+/// Returns the names of the path params bound by the matcher for the segment, from the segment up to the root.
+fn collect_path_param_names<'a>(segment: &'a RouteSegment, routes: &'a Routary) -> Vec<&'a str> {
+  let mut v = Vec::new();
+
+  if let SegmentEffect::UrlMatcher { sequences } = &segment.effect {
+    v.extend(sequences.iter().filter_map(|s| {
+      let TypedSequence::Dynamic(DynamicSequence { param_name, .. }) = &s.typed else {
+        return None;
+      };
+
+      Some(param_name.as_str())
+    }));
   }
 
   if let Some(parent) = &segment.parent && let Some(parent) = routes.segment_map.get(parent) {
-    v.extend(extract_path_params(parent, routes))
+    v.extend(collect_path_param_names(parent, routes))
   }
 
   v
 }
 
+fn path_param_ident(param_name: &str) -> Ident {
+  Ident::new(&format!("path_param_{param_name}"), Span::mixed_site())
+}
+
 fn gen_page_responder_dev(ctx: &GenContext, segment: &RouteSegment, target: &RenderTarget) -> TokenStream {
   // TODO: Layout loader calls
   let loader_call = gen_loader_call(ctx, segment, target);
@@ -103,11 +381,28 @@ fn gen_page_responder_dev(ctx: &GenContext, segment: &RouteSegment, target: &Ren
 }
 
 fn gen_page_responder_prod(ctx: &GenContext, segment: &RouteSegment, target: &RenderTarget) -> TokenStream {
+  // TODO: Layout loader calls
   // TODO: Call pre-generated function on App::segment_blahblah_page();
   // TODO: Pre-generate that function.
-  quote! {}
+  let loader_call = gen_loader_call(ctx, segment, target);
+
+  quote! {
+    #loader_call
+  }
 }
 
+/// Calls the `handler` function of the `handler.rs` file, which produces the whole response.
 fn gen_handler_responder(ctx: &GenContext, segment: &RouteSegment, target: &HandlerTarget) -> TokenStream {
-  quote! {}
+  let identifier = &segment.identifier;
+  let module_name = Ident::new(&target.rs_module.name, Span::mixed_site());
+
+  // TODO: Pass the path params to the handler, they're bound by the matcher but unused so far.
+  let path_params = collect_path_param_names(segment, ctx.routary).into_iter().map(path_param_ident);
+
+  quote! {
+    #(let _ = #path_params;)*
+    let response = #module_name::inner::handler(&request).await;
+
+    return internal::HandlerResult { response: Ok(response), route_id: Some(#identifier) };
+  }
 }
@@ -2,7 +2,8 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote};
 
 use crate::build::app::context::GenContext;
-use crate::routing::segment::{RenderTarget, RouteSegment};
+use crate::routing::routary::Routary;
+use crate::routing::segment::{RenderTarget, RouteSegment, RouteSegmentRsModule};
 
 pub fn gen_loader_call(ctx: &GenContext, segment: &RouteSegment, target: &RenderTarget) -> TokenStream {
   let Some(rs_module) = &target.rs_module else {
@@ -10,8 +11,81 @@ pub fn gen_loader_call(ctx: &GenContext, segment: &RouteSegment, target: &Render
   };
 
   let module_name = Ident::new(&rs_module.name, Span::mixed_site());
+  let route_id = &segment.identifier;
 
+  let catch = gen_catch(route_id, find_nearest_error_loader(segment, ctx.routary));
+
+  // The `route_timeout` is bound by the responder, see `wrap_with_timeout`
   quote! {
-    let loaded = #module_name::inner::loader().await;
+    let thrown_by = internal::ThrownBy { route_id: #route_id, loader_kind: internal::ThrownByLoaderKind::Page };
+
+    // A loader past its deadline is cancelled, its `LoaderTimeout` is then caught like any other error
+    let result = internal::timeout::deadline(route_timeout.loader, #module_name::inner::loader()).await;
+
+    let loaded = match result.map(internal::Loadable::__internal_into_result) {
+      Ok(Ok(output)) => output,
+      Ok(Err(error)) => { #catch }
+      Err(error) => { #catch }
+    };
+
+    // Answering conditional requests before the page is rendered
+    let etag = loaded.__internal_etag();
+    let last_modified = loaded.__internal_last_modified();
+
+    if internal::is_not_modified(request.method(), request.headers(), etag, last_modified) {
+      let response = internal::not_modified(etag, last_modified);
+      return internal::HandlerResult { response, route_id: Some(#route_id) };
+    }
   }
 }
+
+/// Generates the handling of an `error` thrown by a loader, passing it to the nearest error loader.
+/// Errors without any error loader, or returned from the error loader itself, are uncaught.
+fn gen_catch(route_id: &str, error_loader: Option<(&str, &RouteSegmentRsModule)>) -> TokenStream {
+  let Some((error_page_id, module)) = error_loader else {
+    return quote! {
+      let status = internal::timeout::error_status(&error);
+      return Self::uncaught_error(status, &error, thrown_by);
+    };
+  };
+
+  let module_name = Ident::new(&module.name, Span::mixed_site());
+
+  quote! {
+    let status = internal::timeout::error_status(&error);
+    let mut error = Some(error);
+    let caught = internal::Downcastable::new(&mut error, thrown_by);
+
+    match internal::Loadable::__internal_into_result(#module_name::inner::error_loader(caught).await) {
+      // TODO: Pass the Props to the error page and follow the redirects returned from the error loader
+      Ok(_output) => return Self::caught_error(status, #error_page_id, #route_id),
+      Err(error) => {
+        let thrown_by =
+          internal::ThrownBy { route_id: #error_page_id, loader_kind: internal::ThrownByLoaderKind::ErrorPage };
+
+        return Self::uncaught_error(internal::StatusCode::INTERNAL_SERVER_ERROR, &error, thrown_by);
+      }
+    }
+  }
+}
+
+/// Returns the ID and module of the error page with an error loader nearest to the segment,
+/// which may be the segment itself.
+fn find_nearest_error_loader<'a>(
+  segment: &'a RouteSegment,
+  routes: &'a Routary,
+) -> Option<(&'a str, &'a RouteSegmentRsModule)> {
+  let mut current = Some(segment);
+
+  while let Some(segment) = current {
+    let render = segment.error_target.as_ref().and_then(|target| target.render.as_ref());
+
+    if let Some(rs_module) = render.and_then(|render| render.rs_module.as_ref()) {
+      return Some((segment.identifier.as_str(), rs_module));
+    }
+
+    current = segment.parent.as_ref().and_then(|parent| routes.segment_map.get(parent));
+  }
+
+  None
+}
@@ -0,0 +1,30 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::routing::segment::{EitherTarget, RouteSegment};
+
+/// The methods supported by pages.
+// TODO: Allow `POST` for pages defining a form `action` once actions are dispatched,
+//       until then a `POST` would only run the loader and render the page.
+const PAGE_ALLOW: &str = "GET, HEAD, OPTIONS";
+
+/// Generates the check of the request method for the Route Target of the segment. `OPTIONS` is answered
+/// with the `Allow` header derived from the target, before any loader runs.
+///
+/// Pages support `GET` and `HEAD`, other methods are answered with `405 Method Not Allowed`.
+/// Handlers receive any method, including `OPTIONS`, and answer them themselves, so they aren't guarded.
+pub fn gen_method_guard(segment: &RouteSegment) -> TokenStream {
+  let identifier = &segment.identifier;
+
+  let Some(EitherTarget::Render(_)) = &segment.route_target else {
+    return quote! {};
+  };
+
+  quote! {
+    match request.method() {
+      &hyper::Method::GET | &hyper::Method::HEAD => {}
+      &hyper::Method::OPTIONS => return Self::allowed_methods(#PAGE_ALLOW, #identifier),
+      _ => return Self::method_not_allowed(#PAGE_ALLOW, #identifier),
+    }
+  }
+}
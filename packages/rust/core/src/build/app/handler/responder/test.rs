@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::PathBuf;
+
+use quote::quote;
+
+use crate::build::BuildConfig;
+use crate::build::app::context::GenContext;
+use crate::build::build_config::BuildMode;
+use crate::config::{AppConfig, register_app_config};
+use crate::routing::routary::Routary;
+
+use super::method_guard::gen_method_guard;
+use super::{gen_segment_responder, wrap_with_middleware};
+
+/// A routes directory created for a single test, removed when dropped.
+struct TestRoutes {
+  dir: PathBuf,
+  routary: Routary,
+}
+
+impl TestRoutes {
+  fn new(name: &str, files: &[&str]) -> Self {
+    // Parsing the routes needs the app config (e.g. for the trailing slash handling)
+    register_app_config(AppConfig::default());
+
+    let dir = std::env::temp_dir().join(format!("ruxy-responder-test-{name}-{}", std::process::id()));
+
+    for file in files {
+      let path = dir.join(file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, "").unwrap();
+    }
+
+    let routary = Routary::parse(&dir);
+
+    Self { dir, routary }
+  }
+
+  fn middleware_module(&self, identifier: &str) -> String {
+    let segment = &self.routary.segment_map[identifier];
+    segment.middleware_target.as_ref().unwrap().rs_module.name.clone()
+  }
+}
+
+impl Drop for TestRoutes {
+  fn drop(&mut self) {
+    let _ = fs::remove_dir_all(&self.dir);
+  }
+}
+
+#[test]
+fn test_middleware_nesting() {
+  let routes = TestRoutes::new(
+    "middleware",
+    &["blog/middleware.rs", "blog/post/middleware.rs", "blog/post/page.rs", "about/page.rs"],
+  );
+
+  let build_config = BuildConfig::parse();
+  let ctx = GenContext { build_config: &build_config, routary: &routes.routary };
+
+  let responder = quote! { respond() };
+  let post = &routes.routary.segment_map["blog/post"];
+  let wrapped = wrap_with_middleware(&ctx, post, responder.clone()).to_string();
+
+  // The parent's middleware wraps the child's, which wraps the responder
+  let outer = wrapped.find(&format!("{} :: inner :: middleware", routes.middleware_module("blog"))).unwrap();
+  let inner = wrapped.find(&format!("{} :: inner :: middleware", routes.middleware_module("blog/post"))).unwrap();
+  let responder_at = wrapped.find("respond ()").unwrap();
+
+  assert!(outer < inner && inner < responder_at);
+  assert_eq!(wrapped.matches("NextFn").count(), 3);
+
+  // The continuations are nested closures, nothing is boxed
+  assert!(!wrapped.contains("Box"));
+
+  let about = &routes.routary.segment_map["about"];
+  let unwrapped = wrap_with_middleware(&ctx, about, responder.clone());
+
+  assert_eq!(unwrapped.to_string(), responder.to_string());
+}
+
+#[test]
+fn test_page_validators() {
+  let routes = TestRoutes::new("validators", &["blog/page.rs", "blog/page.tsx", "about/page.tsx"]);
+
+  for mode in [BuildMode::Development, BuildMode::Production] {
+    let build_config = BuildConfig { mode };
+    let ctx = GenContext { build_config: &build_config, routary: &routes.routary };
+
+    // The entity tag and modification time of the loader's output validate pages with a loader in both modes
+    let blog = gen_segment_responder(&ctx, &routes.routary.segment_map["blog"]).to_string();
+
+    assert!(blog.contains(":: inner :: loader ()"));
+    assert!(blog.contains("request . headers () , etag , last_modified)"));
+    assert!(blog.contains("internal :: with_validators (response , etag , last_modified)"));
+
+    // The Props are passed to the page in a script opened with the CSP nonce
+    assert!(blog.contains("body . push (internal :: script_open_tag ())"));
+    assert!(blog.contains("loaded . __internal_hydration_script ()"));
+  }
+
+  let build_config = BuildConfig { mode: BuildMode::Production };
+  let ctx = GenContext { build_config: &build_config, routary: &routes.routary };
+
+  // Static pages are validated by the strong entity tag only, the build time isn't a modification time
+  let about = gen_segment_responder(&ctx, &routes.routary.segment_map["about"]).to_string();
+
+  assert!(about.contains("internal :: ETag :: strong"));
+  assert!(!about.contains("UNIX_EPOCH"));
+}
+
+#[test]
+fn test_method_guard() {
+  let routes = TestRoutes::new("method-guard", &["about/page.rs", "api/handler.rs"]);
+
+  // Pages answer OPTIONS and reject anything but GET and HEAD, POST included until actions are dispatched
+  let about = gen_method_guard(&routes.routary.segment_map["about"]).to_string();
+
+  assert!(about.contains("& hyper :: Method :: GET | & hyper :: Method :: HEAD =>"));
+  assert!(about.contains("Self :: allowed_methods (\"GET, HEAD, OPTIONS\" , \"about\")"));
+  assert!(about.contains("Self :: method_not_allowed (\"GET, HEAD, OPTIONS\" , \"about\")"));
+  assert!(!about.contains("POST"));
+
+  // Handlers receive any method, including OPTIONS
+  let api = gen_method_guard(&routes.routary.segment_map["api"]);
+  assert!(api.is_empty());
+}
@@ -0,0 +1,115 @@
+mod mime;
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::config::get_app_config;
+use crate::constants::PUBLIC_DIR;
+use crate::server::conditional::ETag;
+use crate::util::fs::get_project_dir;
+
+use mime::content_type_for;
+
+/// Generates the index of the `public/` directory, registered in the generated `main` function.
+/// The content types are resolved at build time, as well as the entity tags and modification times
+/// of the embedded files. The files that aren't embedded are read along with their metadata at runtime.
+///
+/// Files that should be embedded but can't be read are reported in `errors`, see `render_errors`.
+pub fn gen_public_index(errors: &mut Vec<String>) -> TokenStream {
+  let config = &get_app_config().static_files;
+  let public_dir = get_project_dir().join(PUBLIC_DIR);
+
+  let mut files = Vec::new();
+
+  if config.enabled {
+    collect_files(&public_dir, &mut String::new(), &mut files);
+  }
+
+  files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+  let files = files.iter().filter_map(|(url_path, path)| {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let content_type = content_type_for(extension);
+
+    let embedded = match config.embed {
+      true => match gen_embedded_file(path) {
+        Ok(embedded) => quote! { Some(#embedded) },
+        Err(e) => {
+          errors.push(e);
+          return None;
+        }
+      },
+      false => quote! { None },
+    };
+
+    Some(quote! {
+      internal::PublicFile {
+        path: #url_path,
+        content_type: #content_type,
+        embedded: #embedded,
+      }
+    })
+  });
+
+  let files: Vec<TokenStream> = files.collect();
+
+  quote! {
+    static PUBLIC_FILES: internal::PublicIndex = internal::PublicIndex {
+      files: &[#(#files),*],
+    };
+  }
+}
+
+/// Generates the embedded contents of the file along with its validators.
+fn gen_embedded_file(path: &Path) -> Result<TokenStream, String> {
+  let contents = std::fs::read(path).map_err(|e| {
+    format!("Public files are embedded into the binary, but one of them can't be read:\n{}\n{e}", path.display())
+  })?;
+
+  let modified = std::fs::metadata(path).and_then(|meta| meta.modified()).unwrap_or(UNIX_EPOCH);
+  let modified = modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+  let etag = ETag::from_content(&contents);
+  let etag = etag.tag();
+
+  // Embedding via `include_bytes!` makes Cargo rebuild the app when the file changes
+  let Some(path) = path.to_str() else {
+    return Err(format!("Public files can only be embedded from UTF-8 paths, this one is not:\n{}", path.display()));
+  };
+
+  Ok(quote! {
+    internal::EmbeddedFile { contents: include_bytes!(#path), etag: #etag, modified: #modified }
+  })
+}
+
+/// Recursively collects the files of the directory, along with their URL paths.
+fn collect_files(dir: &Path, url_prefix: &mut String, files: &mut Vec<(String, PathBuf)>) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+
+  for entry in entries.flatten() {
+    let path = entry.path();
+
+    // URL paths are decoded to UTF-8, such a file couldn't be requested
+    let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+      println!("cargo::warning=skipping public file with a non-UTF-8 name: {}", path.display());
+      continue;
+    };
+
+    let prefix_len = url_prefix.len();
+    url_prefix.push('/');
+    url_prefix.push_str(&name);
+
+    if path.is_dir() {
+      collect_files(&path, url_prefix, files);
+    } else if path.is_file() {
+      files.push((url_prefix.clone(), path));
+    }
+
+    url_prefix.truncate(prefix_len);
+  }
+}
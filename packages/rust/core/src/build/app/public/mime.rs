@@ -0,0 +1,47 @@
+/// Guesses the content type of a file from its extension. Unknown extensions are served as `application/octet-stream`.
+pub fn content_type_for(extension: &str) -> &'static str {
+  match extension.to_ascii_lowercase().as_str() {
+    // Text
+    "html" | "htm" => "text/html; charset=utf-8",
+    "css" => "text/css; charset=utf-8",
+    "js" | "mjs" => "text/javascript; charset=utf-8",
+    "txt" => "text/plain; charset=utf-8",
+    "csv" => "text/csv; charset=utf-8",
+    "md" => "text/markdown; charset=utf-8",
+    "xml" => "application/xml; charset=utf-8",
+    "json" | "map" => "application/json",
+    "webmanifest" => "application/manifest+json",
+
+    // Images
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "svg" => "image/svg+xml",
+    "ico" => "image/x-icon",
+    "bmp" => "image/bmp",
+
+    // Fonts
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+
+    // Audio and video
+    "mp3" => "audio/mpeg",
+    "ogg" => "audio/ogg",
+    "wav" => "audio/wav",
+    "mp4" => "video/mp4",
+    "webm" => "video/webm",
+
+    // Other
+    "wasm" => "application/wasm",
+    "pdf" => "application/pdf",
+    "zip" => "application/zip",
+    "gz" => "application/gzip",
+    "tar" => "application/x-tar",
+
+    _ => "application/octet-stream",
+  }
+}
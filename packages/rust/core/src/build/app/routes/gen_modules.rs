@@ -42,6 +42,22 @@ pub fn gen_route_modules_for_segment(segment: &RouteSegment) -> TokenStream {
     modules.push(module);
   };
 
+  if let Some(HandlerTarget { rs_module }) = &segment.middleware_target {
+    modules.push(rs_module);
+  };
+
+  if let Some(HandlerTarget { rs_module }) = &segment.cors_target {
+    modules.push(rs_module);
+  };
+
+  if let Some(HandlerTarget { rs_module }) = &segment.rate_limit_target {
+    modules.push(rs_module);
+  };
+
+  if let Some(HandlerTarget { rs_module }) = &segment.timeout_target {
+    modules.push(rs_module);
+  };
+
   let project_dir = get_project_dir();
   
   let declarations = modules.iter().map(|module| {
@@ -9,10 +9,19 @@ pub enum BuildMode {
   Production,
 }
 
+/// Overrides the build mode (`production` or `development`), which otherwise follows the Cargo profile.
+/// Set by `ruxy build`, so that production builds don't depend on the profile's settings.
+pub const ENV_BUILD_MODE: &str = "RUXY_BUILD_MODE";
+
 impl BuildConfig {
   pub fn parse() -> Self {
-    Self {
-      mode: if cfg!(debug_assertions) { BuildMode::Development } else { BuildMode::Production },
-    }
+    let mode = match std::env::var(ENV_BUILD_MODE).as_deref() {
+      Ok("production") => BuildMode::Production,
+      Ok("development") => BuildMode::Development,
+      _ if cfg!(debug_assertions) => BuildMode::Development,
+      _ => BuildMode::Production,
+    };
+
+    Self { mode }
   }
 }
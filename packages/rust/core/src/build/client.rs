@@ -1,16 +1,101 @@
+use std::path::Path;
+
 use crate::build::BuildConfig;
+use crate::build::manifest::{BuildAsset, BuildFile, content_hash, write_client_assets};
+use crate::constants::{APP_DIR, CLIENT_ASSETS_FILE, CLIENT_OUT_DIR, ROUTES_DIR};
 use crate::routing::routary::Routary;
-use crate::routing::segment::RouteSegmentClientEntry;
+use crate::routing::segment::{EitherTarget, RouteSegment, RouteSegmentClientEntry};
+use crate::util::fs::{get_project_dir, get_ruxy_out_dir};
 
 // TODO: emit watch hints for all client files discovered during the build process
 
+// NOTE: The client asset pipeline is a placeholder until the compiler is in place (see `compiler::compile_route`).
+//       The entries are emitted as their uncompiled sources under content-hashed names, they aren't bundled,
+//       and the server doesn't serve them yet. Only the layout of the build (hashed names, the manifest,
+//       the `dist` directory) is final.
+
+/// How many characters of the content hash are used in the names of the assets.
+const ASSET_HASH_LEN: usize = 16;
+
 /// Builds the whole client application (all pages, layouts, etc.) for Production.
-pub(crate) fn build_all(build_config: &BuildConfig, routary: &Routary) {
-  // TODO: Walk over the Routary and call `build_route_segment_entries` with all the entries.
-}
+/// For now, the entries are emitted uncompiled, see the note above.
+///
+/// The assets are written to `<out>/.ruxy/client`, listed in its `assets.json`, from where
+/// `ruxy build` collects them into the production build.
+pub(crate) fn build_all(_build_config: &BuildConfig, routary: &Routary) {
+  let client_dir = get_ruxy_out_dir().join(CLIENT_OUT_DIR);
+  let routes_dir = get_project_dir().join(APP_DIR).join(ROUTES_DIR);
+
+  // Removing the assets of the previous build, so that they don't pile up
+  if client_dir.exists() && std::fs::remove_dir_all(&client_dir).is_err() {
+    panic!("couldn't clear the `.ruxy/{CLIENT_OUT_DIR}` directory");
+  }
+
+  if let Err(e) = std::fs::create_dir_all(&client_dir) {
+    panic!("couldn't create the `.ruxy/{CLIENT_OUT_DIR}` directory: {e}");
+  }
 
+  let mut segments: Vec<&RouteSegment> = routary.segment_map.values().collect();
+  segments.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+
+  let mut assets = Vec::new();
+
+  for segment in segments {
+    for entry in get_client_entries(segment) {
+      let file = build_entry(&routes_dir, &client_dir, entry);
+      assets.push(BuildAsset { route: segment.identifier.clone(), file });
+    }
+  }
+
+  if let Err(e) = write_client_assets(&client_dir.join(CLIENT_ASSETS_FILE), &assets) {
+    panic!("couldn't write `.ruxy/{CLIENT_OUT_DIR}/{CLIENT_ASSETS_FILE}`: {e}");
+  }
+}
 
 /// Builds the requested route segment entry (Development mode).
+// TODO: Compile the entries once the compiler is in place, nothing is built in Development mode until then.
 pub fn build_route_segment_entries(build_config: &BuildConfig, entries: Vec<&RouteSegmentClientEntry>) {
 
 }
+
+/// Emits the entry as an asset named by the hash of its contents, so that it can be cached forever.
+// TODO: Compile the entry (see `compiler::compile_route`) once the compiler is in place,
+//       the source of the entry is emitted as it is until then.
+fn build_entry(routes_dir: &Path, client_dir: &Path, entry: &RouteSegmentClientEntry) -> BuildFile {
+  let source = routes_dir.join(&entry.path);
+
+  let contents = std::fs::read(&source).unwrap_or_else(|e| panic!("couldn't read {}: {e}", source.display()));
+  let hash = content_hash(&contents);
+
+  let stem = entry.path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("entry");
+  let ext = entry.path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+  let name = format!("{stem}.{}.{ext}", &hash[..ASSET_HASH_LEN]);
+
+  if let Err(e) = std::fs::write(client_dir.join(&name), &contents) {
+    panic!("couldn't write `.ruxy/{CLIENT_OUT_DIR}/{name}`: {e}");
+  }
+
+  BuildFile { path: name, size: contents.len() as u64, hash }
+}
+
+/// Returns all client entries of the segment – its page, layout, not-found page and error page.
+fn get_client_entries(segment: &RouteSegment) -> Vec<&RouteSegmentClientEntry> {
+  let error_render = segment.error_target.as_ref().and_then(|target| target.render.as_ref());
+
+  [
+    get_render_entry(segment.route_target.as_ref()),
+    segment.layout_target.as_ref().and_then(|layout| layout.client_entry.as_ref()),
+    get_render_entry(segment.not_found_target.as_ref()),
+    error_render.and_then(|render| render.client_entry.as_ref()),
+  ]
+  .into_iter()
+  .flatten()
+  .collect()
+}
+
+fn get_render_entry(target: Option<&EitherTarget>) -> Option<&RouteSegmentClientEntry> {
+  match target {
+    Some(EitherTarget::Render(render)) => render.client_entry.as_ref(),
+    _ => None,
+  }
+}
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod test;
+
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::constants::BUILD_MANIFEST_FILE;
+use crate::server::conditional::ETag;
+
+/// The manifest of a production build, written by `ruxy build` next to the binary, the client assets
+/// and the public files. It pins the exact contents of every file of the build, so that `ruxy serve`
+/// can verify that the files are the ones the binary was built with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildManifest {
+  pub binary: BuildFile,
+  pub assets: Vec<BuildAsset>,
+  /// The files of the `public/` directory, served by the binary from the copy in the build directory.
+  pub public: Vec<BuildFile>,
+}
+
+/// A file of the build, with its path relative to the build directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildFile {
+  pub path: String,
+  pub size: u64,
+  pub hash: String,
+}
+
+/// A client asset of the build, built from an entry of a route segment.
+/// Until the client compiler is in place, it's the uncompiled source of the entry, see `client::build_all`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildAsset {
+  /// The identifier of the route segment the asset belongs to.
+  pub route: String,
+  pub file: BuildFile,
+}
+
+impl BuildFile {
+  /// Describes the file at `path` (relative to `build_dir`) by its current contents.
+  pub fn read(build_dir: &Path, path: &str) -> Result<Self, String> {
+    let contents = std::fs::read(build_dir.join(path)).map_err(|e| format!("cannot read {path}: {e}"))?;
+
+    Ok(Self { path: path.to_string(), size: contents.len() as u64, hash: content_hash(&contents) })
+  }
+
+  /// Verifies that the file in `build_dir` still has the contents it was built with.
+  fn verify(&self, build_dir: &Path) -> Result<(), String> {
+    let actual = Self::read(build_dir, &self.path)?;
+
+    if actual.size != self.size || actual.hash != self.hash {
+      return Err(format!("{} differs from the one the build was made with", self.path));
+    }
+
+    Ok(())
+  }
+}
+
+impl BuildManifest {
+  pub fn read(build_dir: &Path) -> Result<Self, String> {
+    let path = build_dir.join(BUILD_MANIFEST_FILE);
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("cannot read {BUILD_MANIFEST_FILE}: {e}"))?;
+
+    Self::parse(&contents).ok_or_else(|| format!("{BUILD_MANIFEST_FILE} is malformed"))
+  }
+
+  pub fn write(&self, build_dir: &Path) -> std::io::Result<()> {
+    std::fs::write(build_dir.join(BUILD_MANIFEST_FILE), self.to_json())
+  }
+
+  /// Verifies the binary, all the assets and the public files against the manifest, returning every mismatch found.
+  pub fn verify(&self, build_dir: &Path) -> Vec<String> {
+    let files = std::iter::once(&self.binary).chain(self.assets.iter().map(|asset| &asset.file)).chain(&self.public);
+
+    files.filter_map(|file| file.verify(build_dir).err()).collect()
+  }
+
+  fn parse(contents: &str) -> Option<Self> {
+    let manifest: Value = serde_json::from_str(contents).ok()?;
+
+    Some(Self {
+      binary: parse_file(&manifest["binary"])?,
+      assets: parse_assets(&manifest["assets"])?,
+      public: manifest["public"].as_array()?.iter().map(parse_file).collect::<Option<_>>()?,
+    })
+  }
+
+  fn to_json(&self) -> String {
+    let manifest = json!({
+      "binary": file_to_json(&self.binary),
+      "assets": assets_to_json(&self.assets),
+      "public": self.public.iter().map(file_to_json).collect::<Value>(),
+    });
+
+    // Serializing a `Value` can't fail
+    serde_json::to_string_pretty(&manifest).unwrap_or_default()
+  }
+}
+
+/// Writes the list of client assets built by the build script, see `client::build_all`.
+pub fn write_client_assets(path: &Path, assets: &[BuildAsset]) -> std::io::Result<()> {
+  // Serializing a `Value` can't fail
+  std::fs::write(path, serde_json::to_string_pretty(&assets_to_json(assets)).unwrap_or_default())
+}
+
+/// Reads the list of client assets written by `write_client_assets`.
+pub fn read_client_assets(path: &Path) -> Result<Vec<BuildAsset>, String> {
+  let contents = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+  let assets: Value = serde_json::from_str(&contents).map_err(|e| format!("{} is malformed: {e}", path.display()))?;
+
+  parse_assets(&assets).ok_or_else(|| format!("{} is malformed", path.display()))
+}
+
+/// The hash identifying the contents of a build file, also used in the names of hashed assets.
+pub fn content_hash(contents: &[u8]) -> String {
+  ETag::from_content(contents).tag().to_string()
+}
+
+fn parse_assets(assets: &Value) -> Option<Vec<BuildAsset>> {
+  assets.as_array()?.iter().map(parse_asset).collect()
+}
+
+fn parse_asset(asset: &Value) -> Option<BuildAsset> {
+  Some(BuildAsset { route: asset["route"].as_str()?.to_string(), file: parse_file(&asset["file"])? })
+}
+
+fn parse_file(file: &Value) -> Option<BuildFile> {
+  Some(BuildFile {
+    path: file["path"].as_str()?.to_string(),
+    size: file["size"].as_u64()?,
+    hash: file["hash"].as_str()?.to_string(),
+  })
+}
+
+fn assets_to_json(assets: &[BuildAsset]) -> Value {
+  assets.iter().map(|asset| json!({ "route": asset.route, "file": file_to_json(&asset.file) })).collect()
+}
+
+fn file_to_json(file: &BuildFile) -> Value {
+  json!({ "path": file.path, "size": file.size, "hash": file.hash })
+}
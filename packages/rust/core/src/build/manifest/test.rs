@@ -0,0 +1,44 @@
+use std::fs;
+
+use super::{BuildAsset, BuildFile, BuildManifest, read_client_assets, write_client_assets};
+
+#[test]
+fn test_manifest_roundtrip_and_verify() {
+  let dir = std::env::temp_dir().join(format!("ruxy-manifest-test-{}", std::process::id()));
+  fs::create_dir_all(dir.join("assets")).unwrap();
+
+  fs::write(dir.join("app"), b"binary").unwrap();
+  fs::write(dir.join("assets/page.js"), b"export default 1;").unwrap();
+  fs::create_dir_all(dir.join("public")).unwrap();
+  fs::write(dir.join("public/robots.txt"), b"User-agent: *").unwrap();
+
+  let manifest = BuildManifest {
+    binary: BuildFile::read(&dir, "app").unwrap(),
+    assets: vec![BuildAsset { route: "blog".into(), file: BuildFile::read(&dir, "assets/page.js").unwrap() }],
+    public: vec![BuildFile::read(&dir, "public/robots.txt").unwrap()],
+  };
+
+  manifest.write(&dir).unwrap();
+
+  let read = BuildManifest::read(&dir).unwrap();
+  assert_eq!(read, manifest);
+  assert!(read.verify(&dir).is_empty());
+
+  // Same size, different contents
+  fs::write(dir.join("assets/page.js"), b"export default 2;").unwrap();
+  assert_eq!(read.verify(&dir), ["assets/page.js differs from the one the build was made with"]);
+
+  fs::remove_file(dir.join("app")).unwrap();
+  assert_eq!(read.verify(&dir).len(), 2);
+
+  fs::write(dir.join("public/robots.txt"), b"Disallow: /").unwrap();
+  assert_eq!(read.verify(&dir).len(), 3);
+
+  fs::write(dir.join("manifest.json"), "{\"binary\": {}}").unwrap();
+  assert!(BuildManifest::read(&dir).is_err());
+
+  write_client_assets(&dir.join("assets.json"), &manifest.assets).unwrap();
+  assert_eq!(read_client_assets(&dir.join("assets.json")).unwrap(), manifest.assets);
+
+  fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,6 +1,11 @@
 use std::path::Path;
 
 pub fn emit_watch_hints_for_dir(path: &Path) {
+  // The directory itself is watched too, so that newly created routes are picked up
+  if let Some(path) = path.to_str() {
+    println!("cargo::rerun-if-changed={path}");
+  }
+
   let mut file_names = Vec::new();
 
   if let Ok(entries) = std::fs::read_dir(path) {
@@ -17,10 +22,6 @@ pub fn emit_watch_hints_for_dir(path: &Path) {
         continue;
       }
 
-      if let Some(path) = path.to_str() {
-        println!("cargo::rerun-if-changed={path}");
-      }
-
       emit_watch_hints_for_dir(&path);
     }
   }
@@ -3,9 +3,13 @@ mod helpers;
 
 use clap::{ArgAction, Parser};
 
+use crate::log::{self, LogLevel};
+
 use commands::Commands;
 use commands::init::{cmd_init, CmdInitOptions};
+use commands::serve::{cmd_serve, CmdServeOptions};
 use commands::build::{cmd_build, CmdBuildOptions};
+use commands::dev::{cmd_dev, CmdDevOptions};
 use commands::update::{cmd_update, CmdUpdateOptions};
 
 const ABOUT: &str = "Welcome to Ruxy CLI! Use it to initialize, build, and run your project.";
@@ -29,7 +33,8 @@ pub fn cli() {
   let cli = Cli::parse();
 
   if cli.debug {
-    println!("Debug mode is on");
+    log::set_max_level(LogLevel::Debug);
+    log::debug("debug_enabled", "debug logging enabled", &[]);
   }
 
   match cli.command {
@@ -42,7 +47,11 @@ pub fn cli() {
     Commands::Build { directory } => {
       cmd_build(CmdBuildOptions { directory })
     }
-    Commands::Dev { .. } => {}
-    Commands::Serve { .. } => {}
+    Commands::Dev { directory, port } => {
+      cmd_dev(CmdDevOptions { directory, port, debug: cli.debug });
+    }
+    Commands::Serve { directory, port } => {
+      cmd_serve(CmdServeOptions { directory, port, debug: cli.debug });
+    }
   }
 }
@@ -1,5 +1,7 @@
 pub mod build;
+pub mod dev;
 pub mod init;
+pub mod serve;
 pub mod update;
 
 use std::path::PathBuf;
@@ -36,12 +38,19 @@ pub enum Commands {
   Dev {
     #[arg(short, long, alias = "dir", help = "A relative or absolute path to your project's directory")]
     directory: Option<PathBuf>,
-    #[arg(short, long)]
+    #[arg(short, long, help = "The port to serve your application on, 3000 by default")]
     port: Option<usize>,
   },
   #[command(about = "Serve your production build")]
   Serve {
-    #[arg(short, long)]
+    #[arg(
+      short,
+      long,
+      alias = "dir",
+      help = "A relative or absolute path to your project's directory or to its production build"
+    )]
+    directory: Option<PathBuf>,
+    #[arg(short, long, help = "The port to serve your application on, overriding the configured one")]
     port: Option<usize>,
   },
 }
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-use crate::routing::routary::Routary;
+use crate::build::manifest::{BuildAsset, BuildFile, BuildManifest, read_client_assets};
+use crate::constants::{
+  BUILD_MANIFEST_FILE, CLIENT_ASSETS_FILE, CLIENT_OUT_DIR, DIST_ASSETS_DIR, DIST_DIR, DOT_RUXY_DIR, PUBLIC_DIR,
+};
 
-use crate::cli::helpers::resolve_project_directory;
+use crate::cli::helpers::{CargoBuild, resolve_project_directory, run_cargo_build};
 
 pub struct CmdBuildOptions {
   pub directory: Option<PathBuf>,
@@ -10,9 +14,156 @@ pub struct CmdBuildOptions {
 
 pub fn cmd_build(mut options: CmdBuildOptions) {
   let project_dir = resolve_project_directory(options.directory.take());
-  let routes_dir = project_dir.join("app/routes");
-  let routes = Routary::parse(&routes_dir);
-  
-  println!("Routes:");
-  println!("{:#?}", routes);
+
+  // The routes are parsed and the client is built by the build script (see `ruxy::build!`)
+  let Some(build) = run_cargo_build(&project_dir, true) else {
+    eprintln!("Build failed, please fix the errors above.");
+    std::process::exit(1);
+  };
+
+  let dist_dir = project_dir.join(DIST_DIR);
+
+  prepare_dist_dir(&dist_dir);
+
+  let manifest = collect_build(&build, &project_dir, &dist_dir).unwrap_or_else(|e| {
+    eprintln!("Failed to collect the production build into {}: {e}", dist_dir.display());
+    std::process::exit(1);
+  });
+
+  print_size_report(&manifest);
+
+  if !manifest.assets.is_empty() {
+    println!(
+      "\nNote: the client compiler isn't available yet, the assets are the uncompiled sources of the client entries. \
+      They're listed for the manifest only, the server doesn't serve them."
+    );
+  }
+
+  println!("\nProduction build is ready in {}, run it with `ruxy serve`.", dist_dir.display());
+}
+
+/// Empties the `dist` directory, making sure it contains a previous build rather than the user's files.
+fn prepare_dist_dir(dist_dir: &Path) {
+  let is_empty = dist_dir.read_dir().map(|mut entries| entries.next().is_none()).unwrap_or(true);
+
+  if !is_empty && !dist_dir.join(BUILD_MANIFEST_FILE).is_file() {
+    eprintln!(
+      "The {} directory exists and doesn't contain a previous build. \
+      Please move its contents elsewhere, Ruxy won't overwrite them.",
+      dist_dir.display()
+    );
+
+    std::process::exit(1);
+  }
+
+  let result = match dist_dir.exists() {
+    true => std::fs::remove_dir_all(dist_dir).and_then(|_| std::fs::create_dir_all(dist_dir.join(DIST_ASSETS_DIR))),
+    false => std::fs::create_dir_all(dist_dir.join(DIST_ASSETS_DIR)),
+  };
+
+  if let Err(e) = result {
+    eprintln!("Failed to prepare the {} directory: {e}", dist_dir.display());
+    std::process::exit(1);
+  }
+}
+
+/// Copies the binary, the client assets and the public files into the `dist` directory and writes their manifest.
+fn collect_build(build: &CargoBuild, project_dir: &Path, dist_dir: &Path) -> Result<BuildManifest, String> {
+  let Some(out_dir) = &build.out_dir else {
+    return Err("the output directory of the build script is unknown, is `ruxy::build!` used in build.rs?".into());
+  };
+
+  let client_dir = out_dir.join(DOT_RUXY_DIR).join(CLIENT_OUT_DIR);
+  let mut assets = read_client_assets(&client_dir.join(CLIENT_ASSETS_FILE))?;
+
+  for asset in &mut assets {
+    let path = format!("{DIST_ASSETS_DIR}/{}", asset.file.path);
+
+    std::fs::copy(client_dir.join(&asset.file.path), dist_dir.join(&path))
+      .map_err(|e| format!("cannot copy {}: {e}", asset.file.path))?;
+
+    asset.file.path = path;
+  }
+
+  let Some(binary_name) = build.executable.file_name().and_then(|name| name.to_str()) else {
+    return Err(format!("invalid binary path {}", build.executable.display()));
+  };
+
+  std::fs::copy(&build.executable, dist_dir.join(binary_name)).map_err(|e| format!("cannot copy the binary: {e}"))?;
+
+  // `ruxy serve` runs the binary in the build directory, where it reads the public files that aren't embedded
+  let mut public = Vec::new();
+  copy_public_files(&project_dir.join(PUBLIC_DIR), dist_dir, PUBLIC_DIR, &mut public)?;
+
+  let manifest = BuildManifest { binary: BuildFile::read(dist_dir, binary_name)?, assets, public };
+  manifest.write(dist_dir).map_err(|e| format!("cannot write {BUILD_MANIFEST_FILE}: {e}"))?;
+
+  Ok(manifest)
+}
+
+/// Recursively copies the files of the public directory `dir` to `path` (relative to `dist_dir`).
+fn copy_public_files(dir: &Path, dist_dir: &Path, path: &str, files: &mut Vec<BuildFile>) -> Result<(), String> {
+  // The public directory is optional
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Ok(());
+  };
+
+  std::fs::create_dir_all(dist_dir.join(path)).map_err(|e| format!("cannot create {path}: {e}"))?;
+
+  for entry in entries.flatten() {
+    // Such a file is skipped by the server as well, it couldn't be requested
+    let Some(name) = entry.file_name().to_str().map(|name| format!("{path}/{name}")) else {
+      eprintln!("Skipping public file with a non-UTF-8 name: {}", entry.path().display());
+      continue;
+    };
+
+    let source = entry.path();
+
+    if source.is_dir() {
+      copy_public_files(&source, dist_dir, &name, files)?;
+    } else if source.is_file() {
+      std::fs::copy(&source, dist_dir.join(&name)).map_err(|e| format!("cannot copy {name}: {e}"))?;
+      files.push(BuildFile::read(dist_dir, &name)?);
+    }
+  }
+
+  Ok(())
+}
+
+fn print_size_report(manifest: &BuildManifest) {
+  let mut routes: BTreeMap<&str, Vec<&BuildAsset>> = BTreeMap::new();
+
+  for asset in &manifest.assets {
+    routes.entry(&asset.route).or_default().push(asset);
+  }
+
+  let rows: Vec<(String, usize, u64)> = routes
+    .into_iter()
+    .map(|(route, assets)| (format!("/{route}"), assets.len(), assets.iter().map(|asset| asset.file.size).sum()))
+    .collect();
+
+  let width = rows.iter().map(|(route, ..)| route.len()).max().unwrap_or(0).max("Route".len());
+
+  // The assets aren't compiled yet (see `client::build_all`), so these are the sizes of their sources
+  println!("\n{:width$}  {:>7}  {:>11}", "Route", "Sources", "Source Size");
+
+  for (route, count, size) in &rows {
+    println!("{route:width$}  {count:>7}  {:>11}", format_size(*size));
+  }
+
+  let assets_size: u64 = manifest.assets.iter().map(|asset| asset.file.size).sum();
+
+  println!("\nClient sources (uncompiled): {} in {} files", format_size(assets_size), manifest.assets.len());
+  let public_size: u64 = manifest.public.iter().map(|file| file.size).sum();
+
+  println!("Public files: {} in {} files", format_size(public_size), manifest.public.len());
+  println!("Binary ({}): {}", manifest.binary.path, format_size(manifest.binary.size));
+}
+
+fn format_size(bytes: u64) -> String {
+  match bytes {
+    0..1_000 => format!("{bytes} B"),
+    1_000..1_000_000 => format!("{:.1} kB", bytes as f64 / 1_000.0),
+    _ => format!("{:.1} MB", bytes as f64 / 1_000_000.0),
+  }
 }
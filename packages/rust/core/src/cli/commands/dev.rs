@@ -0,0 +1,117 @@
+#[cfg(unix)]
+mod server_process;
+mod watcher;
+
+#[cfg(test)]
+mod test;
+
+use std::path::PathBuf;
+
+#[cfg(unix)]
+use crate::cli::helpers::resolve_project_directory;
+
+pub struct CmdDevOptions {
+  pub directory: Option<PathBuf>,
+  pub port: Option<usize>,
+  /// Whether the server logs debug events, see `--debug`.
+  pub debug: bool,
+}
+
+#[cfg(unix)]
+const DEFAULT_PORT: usize = 3000;
+
+#[cfg(unix)]
+pub fn cmd_dev(mut options: CmdDevOptions) {
+  use std::net::TcpListener;
+  use std::path::Path;
+  use std::time::Duration;
+
+  use crate::cli::helpers::run_cargo_build;
+  use crate::constants::{APP_DIR, PUBLIC_DIR};
+  use crate::log;
+
+  use server_process::ServerProcess;
+  use watcher::Watcher;
+
+  /// How often the project files are checked for changes.
+  const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+  /// How long to wait after a change before rebuilding, editors often write files in several steps.
+  const DEBOUNCE: Duration = Duration::from_millis(150);
+
+  let project_dir = resolve_project_directory(options.directory.take());
+  let port = options.port.unwrap_or(DEFAULT_PORT);
+
+  let Ok(port) = u16::try_from(port) else {
+    eprintln!("The port must be between 0 and 65535, got {port}");
+    std::process::exit(1);
+  };
+
+  // The socket is owned by us rather than by the server, so it stays open across restarts
+  let listener = match TcpListener::bind(("127.0.0.1", port)) {
+    Ok(listener) => listener,
+    Err(e) => {
+      eprintln!("Failed to listen on port {port}: {e}");
+      std::process::exit(1);
+    }
+  };
+
+  let watched = [APP_DIR, PUBLIC_DIR, "Cargo.toml"].iter().map(|path| project_dir.join(path)).collect();
+  let mut watcher = Watcher::new(watched);
+
+  let start = |executable: &Path| match ServerProcess::spawn(executable, &project_dir, &listener, options.debug) {
+    Ok(server) => {
+      log::info("dev_server_started", &format!("serving on http://127.0.0.1:{port}"), &[]);
+      Some(server)
+    }
+    Err(e) => {
+      log::error("dev_server_failed", "failed to start the server", &[("error", (&e).into())]);
+      None
+    }
+  };
+
+  let mut server = match run_cargo_build(&project_dir, false) {
+    Some(build) => start(&build.executable),
+    None => {
+      log::warn("dev_build_failed", "build failed, fix the errors above to continue", &[]);
+      None
+    }
+  };
+
+  loop {
+    std::thread::sleep(POLL_INTERVAL);
+
+    if server.as_mut().is_some_and(|server| server.has_exited()) {
+      log::warn("dev_server_exited", "the server exited, waiting for changes to restart it", &[]);
+      server = None;
+    }
+
+    if !watcher.changed() {
+      continue;
+    }
+
+    // Letting the burst of writes settle, so we don't build a half-saved project
+    std::thread::sleep(DEBOUNCE);
+    watcher.changed();
+
+    log::info("dev_rebuilding", "files changed, rebuilding", &[]);
+
+    // The old server keeps serving while building, and also when the build fails
+    let Some(build) = run_cargo_build(&project_dir, false) else {
+      log::warn("dev_build_failed", "build failed, fix the errors above to continue", &[]);
+      continue;
+    };
+
+    if let Some(server) = server.take() {
+      server.stop();
+    }
+
+    server = start(&build.executable);
+  }
+}
+
+#[cfg(not(unix))]
+pub fn cmd_dev(_options: CmdDevOptions) {
+  eprintln!("`ruxy dev` is not supported on this platform yet");
+  std::process::exit(1);
+}
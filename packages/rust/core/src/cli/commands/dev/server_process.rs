@@ -0,0 +1,92 @@
+use std::io;
+use std::net::TcpListener;
+use std::os::fd::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+use crate::log::{ENV_LOG, LogLevel};
+use crate::server::listener::{ENV_LISTEN, ENV_LISTEN_FDNAMES, ENV_LISTEN_FDS, ENV_LISTEN_PID, SD_LISTEN_FDS_START};
+
+/// How long a server may take to finish its in-flight requests after `SIGTERM`, before it's killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A running server of the application. It listens on the socket owned by `ruxy dev`, which
+/// is passed to it using the `LISTEN_FDS` protocol. The socket outlives the server, so
+/// connections made while the server restarts wait in the backlog instead of being refused.
+pub struct ServerProcess {
+  child: Child,
+}
+
+impl ServerProcess {
+  /// Spawns the server, listening on the socket of `listener`. It logs debug events when `debug` is set.
+  pub fn spawn(executable: &Path, project_dir: &Path, listener: &TcpListener, debug: bool) -> io::Result<Self> {
+    let fd = listener.as_raw_fd();
+
+    let mut command = Command::new(executable);
+    command.env(ENV_LISTEN, "fd:0").env(ENV_LISTEN_FDS, "1");
+
+    // Files of `public/` that aren't embedded are read relative to the working directory
+    command.current_dir(project_dir);
+
+    if debug {
+      command.env(ENV_LOG, LogLevel::Debug.as_str());
+    }
+
+    // The PID of the child is not known before it's spawned, the server only validates it when set
+    command.env_remove(ENV_LISTEN_PID).env_remove(ENV_LISTEN_FDNAMES);
+
+    // SAFETY: only async-signal-safe functions are called between `fork` and `exec`.
+    unsafe {
+      command.pre_exec(move || {
+        // `dup2` clears the close-on-exec flag of the duplicate, which is not done when both
+        // descriptors are the same, the flag has to be cleared manually then.
+        let result = if fd == SD_LISTEN_FDS_START {
+          libc::fcntl(fd, libc::F_SETFD, 0)
+        } else {
+          libc::dup2(fd, SD_LISTEN_FDS_START)
+        };
+
+        if result == -1 {
+          return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+      });
+    }
+
+    Ok(Self { child: command.spawn()? })
+  }
+
+  /// Returns whether the server exited on its own, e.g. due to a panic or an invalid config.
+  pub fn has_exited(&mut self) -> bool {
+    !matches!(self.child.try_wait(), Ok(None))
+  }
+
+  /// Asks the server to shut down gracefully, killing it if it doesn't in time.
+  pub fn stop(mut self) {
+    if self.has_exited() {
+      return;
+    }
+
+    // SAFETY: `kill` has no memory safety preconditions, the PID is of our own child, which
+    //         can't be reaped (and its PID reused) before we `wait` for it.
+    unsafe {
+      libc::kill(self.child.id() as libc::pid_t, libc::SIGTERM);
+    }
+
+    let deadline = Instant::now() + STOP_TIMEOUT;
+
+    while Instant::now() < deadline {
+      if self.has_exited() {
+        return;
+      }
+
+      std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
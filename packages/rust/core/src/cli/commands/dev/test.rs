@@ -0,0 +1,36 @@
+use std::fs;
+
+use super::watcher::Watcher;
+
+#[test]
+fn test_watcher_changes() {
+  let dir = std::env::temp_dir().join(format!("ruxy-watcher-test-{}", std::process::id()));
+  let routes = dir.join("app/routes");
+  let ignored = dir.join("app/node_modules");
+
+  fs::create_dir_all(&routes).unwrap();
+  fs::create_dir_all(&ignored).unwrap();
+  fs::write(routes.join("page.rs"), "fn page() {}").unwrap();
+
+  let mut watcher = Watcher::new(vec![dir.join("app"), dir.join("Cargo.toml")]);
+  assert!(!watcher.changed());
+
+  // Modified files are detected by their size, as the modification time may be too coarse
+  fs::write(routes.join("page.rs"), "fn page() { loader(); }").unwrap();
+  assert!(watcher.changed());
+  assert!(!watcher.changed());
+
+  fs::write(routes.join("page.tsx"), "export default () => null;").unwrap();
+  assert!(watcher.changed());
+
+  fs::remove_file(routes.join("page.tsx")).unwrap();
+  assert!(watcher.changed());
+
+  fs::write(ignored.join("index.js"), "").unwrap();
+  assert!(!watcher.changed());
+
+  fs::write(dir.join("Cargo.toml"), "[package]").unwrap();
+  assert!(watcher.changed());
+
+  fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Directories never worth watching, they are either huge or written to by the build itself.
+const IGNORED_DIRS: &[&str] = &["node_modules", "target", ".git"];
+
+/// Detects changes to files in the watched paths by comparing snapshots of their modification
+/// times and sizes. Polling keeps us free of platform-specific notification APIs, and the
+/// app directory of a project is small enough to walk a few times per second.
+pub struct Watcher {
+  paths: Vec<PathBuf>,
+  snapshot: HashMap<PathBuf, (SystemTime, u64)>,
+}
+
+impl Watcher {
+  pub fn new(paths: Vec<PathBuf>) -> Self {
+    let snapshot = take_snapshot(&paths);
+
+    Self { paths, snapshot }
+  }
+
+  /// Returns whether any file was created, modified or removed since the last call.
+  pub fn changed(&mut self) -> bool {
+    let snapshot = take_snapshot(&self.paths);
+    let changed = snapshot != self.snapshot;
+
+    self.snapshot = snapshot;
+    changed
+  }
+}
+
+fn take_snapshot(paths: &[PathBuf]) -> HashMap<PathBuf, (SystemTime, u64)> {
+  let mut snapshot = HashMap::new();
+
+  for path in paths {
+    walk(path, &mut snapshot);
+  }
+
+  snapshot
+}
+
+fn walk(path: &Path, snapshot: &mut HashMap<PathBuf, (SystemTime, u64)>) {
+  let Ok(metadata) = path.metadata() else {
+    return;
+  };
+
+  if metadata.is_file() {
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    snapshot.insert(path.to_path_buf(), (modified, metadata.len()));
+    return;
+  }
+
+  let is_ignored = path.file_name().and_then(|name| name.to_str()).is_some_and(|name| IGNORED_DIRS.contains(&name));
+
+  if !metadata.is_dir() || is_ignored {
+    return;
+  }
+
+  let Ok(entries) = path.read_dir() else {
+    return;
+  };
+
+  for entry in entries.flatten() {
+    walk(&entry.path(), snapshot);
+  }
+}
@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::build::manifest::BuildManifest;
+use crate::constants::{BUILD_MANIFEST_FILE, DIST_DIR};
+use crate::log::{ENV_LOG, LogLevel};
+use crate::server::listener::ENV_PORT;
+
+pub struct CmdServeOptions {
+  pub directory: Option<PathBuf>,
+  pub port: Option<usize>,
+  /// Whether the server logs debug events, see `--debug`.
+  pub debug: bool,
+}
+
+pub fn cmd_serve(mut options: CmdServeOptions) {
+  let build_dir = locate_build_dir(options.directory.take());
+
+  let manifest = BuildManifest::read(&build_dir).unwrap_or_else(|e| {
+    eprintln!("Failed to read the production build in {}: {e}", build_dir.display());
+    std::process::exit(1);
+  });
+
+  let mismatches = manifest.verify(&build_dir);
+
+  if !mismatches.is_empty() {
+    eprintln!("The production build in {} is incomplete or was modified:", build_dir.display());
+
+    for mismatch in mismatches {
+      eprintln!("  - {mismatch}");
+    }
+
+    eprintln!("Please run `ruxy build` again.");
+    std::process::exit(1);
+  }
+
+  let mut command = Command::new(build_dir.join(&manifest.binary.path));
+  command.current_dir(&build_dir);
+
+  if let Some(port) = options.port {
+    let Ok(port) = u16::try_from(port) else {
+      eprintln!("The port must be between 0 and 65535, got {port}");
+      std::process::exit(1);
+    };
+
+    // Applied by the server to the configured addresses
+    command.env(ENV_PORT, port.to_string());
+  }
+
+  if options.debug {
+    command.env(ENV_LOG, LogLevel::Debug.as_str());
+  }
+
+  run(command);
+}
+
+/// Finds the production build, either in the provided (or current) directory itself,
+/// or in the `dist` directory of the project in it.
+fn locate_build_dir(dir_arg: Option<PathBuf>) -> PathBuf {
+  let dir = dir_arg.unwrap_or_else(|| {
+    std::env::current_dir().unwrap_or_else(|_| {
+      eprintln!("Failed to get current working directory");
+      std::process::exit(1);
+    })
+  });
+
+  let is_build_dir = |dir: &Path| dir.join(BUILD_MANIFEST_FILE).is_file();
+
+  if is_build_dir(&dir) {
+    return dir;
+  }
+
+  let dist_dir = dir.join(DIST_DIR);
+
+  if is_build_dir(&dist_dir) {
+    return dist_dir;
+  }
+
+  eprintln!(
+    "No production build found in {}. \
+    Please run `ruxy build` first, or specify the build's directory with the --dir flag.",
+    dir.display()
+  );
+
+  std::process::exit(1);
+}
+
+/// Replaces the CLI with the server, so that signals (e.g. from a process manager) reach it directly.
+#[cfg(unix)]
+fn run(mut command: Command) {
+  use std::os::unix::process::CommandExt;
+
+  // `exec` only returns on failure
+  let e = command.exec();
+
+  eprintln!("Failed to start the server: {e}");
+  std::process::exit(1);
+}
+
+#[cfg(not(unix))]
+fn run(mut command: Command) {
+  let status = command.status().unwrap_or_else(|e| {
+    eprintln!("Failed to start the server: {e}");
+    std::process::exit(1);
+  });
+
+  std::process::exit(status.code().unwrap_or(1));
+}
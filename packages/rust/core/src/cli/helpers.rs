@@ -1,7 +1,9 @@
 mod handle_inquire_error;
 mod resolve_project_directory;
+mod run_cargo_build;
 mod validate_dir_name;
 
 pub use handle_inquire_error::*;
 pub use resolve_project_directory::*;
+pub use run_cargo_build::*;
 pub use validate_dir_name::*;
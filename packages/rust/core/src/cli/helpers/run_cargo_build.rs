@@ -0,0 +1,111 @@
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+use crate::build::ENV_BUILD_MODE;
+use crate::build::app::errors::ERRORS_HEAD;
+use crate::constants::{DOT_RUXY_DIR, GENERATED_APP_RS_FILE};
+
+/// The output of a successful build of the project.
+pub struct CargoBuild {
+  /// The path of the project's binary.
+  pub executable: PathBuf,
+  /// The `OUT_DIR` of the project's build script, where Ruxy writes its build artifacts.
+  pub out_dir: Option<PathBuf>,
+}
+
+/// Runs `cargo build` for the project, printing the compiler's diagnostics as they come.
+/// Release builds are always built in Production mode, see `ENV_BUILD_MODE`.
+///
+/// The errors rendered by Ruxy (see `render_errors`) point to the generated application, which
+/// is a single enormous line, so only their message is printed, without the compiler's snippet.
+/// Warnings in the generated application are not actionable for the user and are skipped.
+pub fn run_cargo_build(project_dir: &Path, release: bool) -> Option<CargoBuild> {
+  let manifest_path = project_dir.join("Cargo.toml").canonicalize().ok();
+
+  let mut command = Command::new("cargo");
+  command.arg("build").arg("--message-format=json-diagnostic-rendered-ansi");
+  command.current_dir(project_dir).stdout(Stdio::piped());
+
+  if release {
+    command.arg("--release").env(ENV_BUILD_MODE, "production");
+  }
+
+  let mut child = match command.spawn() {
+    Ok(child) => child,
+    Err(e) => {
+      eprintln!("Failed to run cargo: {e}");
+      return None;
+    }
+  };
+
+  let mut executable = None;
+  let mut project_package_id = None;
+  let mut out_dirs = Vec::new();
+  let stdout = child.stdout.take()?;
+
+  for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+    let Ok(message) = serde_json::from_str::<Value>(&line) else {
+      continue;
+    };
+
+    match message["reason"].as_str() {
+      Some("compiler-message") => print_diagnostic(&message["message"]),
+      Some("compiler-artifact") if is_project_binary(&message, manifest_path.as_deref()) => {
+        executable = message["executable"].as_str().map(PathBuf::from);
+        project_package_id = message["package_id"].as_str().map(str::to_string);
+      }
+      Some("build-script-executed") => {
+        let package_id = message["package_id"].as_str().unwrap_or_default().to_string();
+        out_dirs.extend(message["out_dir"].as_str().map(|out_dir| (package_id, PathBuf::from(out_dir))));
+      }
+      _ => {}
+    }
+  }
+
+  if !child.wait().is_ok_and(|status| status.success()) {
+    return None;
+  }
+
+  let out_dir = out_dirs.into_iter().find(|(package_id, _)| Some(package_id) == project_package_id.as_ref());
+
+  Some(CargoBuild { executable: executable?, out_dir: out_dir.map(|(_, out_dir)| out_dir) })
+}
+
+fn print_diagnostic(diagnostic: &Value) {
+  let message = diagnostic["message"].as_str().unwrap_or_default();
+
+  if message.starts_with(ERRORS_HEAD) {
+    eprintln!("\n{message}\n");
+    return;
+  }
+
+  if diagnostic["level"] == "warning" && is_in_generated_app(diagnostic) {
+    return;
+  }
+
+  if let Some(rendered) = diagnostic["rendered"].as_str() {
+    eprint!("{rendered}");
+  }
+}
+
+fn is_in_generated_app(diagnostic: &Value) -> bool {
+  let generated_app = Path::new(DOT_RUXY_DIR).join(GENERATED_APP_RS_FILE);
+
+  let Some(spans) = diagnostic["spans"].as_array() else {
+    return false;
+  };
+
+  let primary = spans.iter().find(|span| span["is_primary"] == true);
+  primary.and_then(|span| span["file_name"].as_str()).is_some_and(|file| Path::new(file).ends_with(&generated_app))
+}
+
+/// Whether the artifact is the binary of the project, not of one of its dependencies.
+fn is_project_binary(artifact: &Value, manifest_path: Option<&Path>) -> bool {
+  let is_bin = artifact["target"]["kind"].as_array().is_some_and(|kinds| kinds.iter().any(|kind| kind == "bin"));
+  let is_project = artifact["manifest_path"].as_str().map(Path::new) == manifest_path;
+
+  is_bin && is_project
+}
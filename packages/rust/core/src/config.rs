@@ -1,7 +1,33 @@
+mod access_log_config;
 mod app_config;
+mod compression_config;
+mod cors_config;
+mod health_config;
+mod load_shedding_config;
+mod log_config;
+mod metrics_config;
+mod proxy_config;
+mod request_id_config;
+mod security_headers_config;
+mod static_files_config;
+mod timeout_config;
 mod trailing_slash;
+mod websocket_config;
 mod gen_config_module;
 
+pub use access_log_config::*;
 pub use app_config::*;
+pub use compression_config::*;
+pub use cors_config::*;
+pub use health_config::*;
+pub use load_shedding_config::*;
+pub use log_config::*;
+pub use metrics_config::*;
+pub use proxy_config::*;
+pub use request_id_config::*;
+pub use security_headers_config::*;
+pub use static_files_config::*;
+pub use timeout_config::*;
 pub use trailing_slash::*;
+pub use websocket_config::*;
 pub use gen_config_module::*;
@@ -0,0 +1,30 @@
+#[derive(Default)]
+pub struct AccessLogConfig {
+  /// Whether to record the served requests. Disabled by default.
+  pub enabled: bool,
+  /// The format of the access log lines.
+  pub format: AccessLogFormat,
+  /// Where to write the access log lines.
+  pub output: AccessLogOutput,
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub enum AccessLogFormat {
+  /// The Common Log Format, e.g. `127.0.0.1 - - [01/Jun/2025:12:30:45 +0000] "GET / HTTP/1.1" 200 2326`.
+  #[default]
+  Common,
+  /// The Combined Log Format, which is the Common Log Format followed by the quoted `Referer`
+  /// and `User-Agent` request headers.
+  Combined,
+  /// One JSON object per line, also including the request ID, the matched route ID and the duration of the request.
+  Json,
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub enum AccessLogOutput {
+  /// Writes to the standard output.
+  #[default]
+  Stdout,
+  /// Appends to the file at the provided path, creating it if it doesn't exist.
+  File(&'static str),
+}
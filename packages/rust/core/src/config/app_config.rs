@@ -1,11 +1,41 @@
+use std::time::Duration;
+
 use tokio::sync::{OnceCell, SetError};
 
 use crate::config::trailing_slash::TrailingSlashConfig;
+use crate::config::{
+  AccessLogConfig, CompressionConfig, CorsConfig, HealthConfig, LoadSheddingConfig, LogConfig, MetricsConfig,
+  ProxyConfig, RequestIdConfig, SecurityHeadersConfig, StaticFilesConfig, TimeoutConfig, WebSocketConfig,
+};
+use crate::log;
 
 pub struct AppConfig {
   pub trailing_slash: TrailingSlashConfig,
+  /// Addresses to listen on – TCP socket addresses, `unix:<path>` for Unix domain sockets,
+  /// or `fd:<index|name>` for sockets inherited using the `LISTEN_FDS` protocol (systemd).
+  /// Overridden by the `RUXY_LISTEN` environment variable, set e.g. by `ruxy dev`, the port of TCP addresses
+  /// is overridden by `RUXY_PORT`, set e.g. by `ruxy serve --port`.
   pub addresses: &'static [&'static str],
   pub partytown: PartytownConfig,
+  pub log: LogConfig,
+  pub access_log: AccessLogConfig,
+  pub metrics: MetricsConfig,
+  pub health: HealthConfig,
+  pub request_id: RequestIdConfig,
+  /// Per-worker limits of connections and requests, over which they are rejected with `503`.
+  pub load_shedding: LoadSheddingConfig,
+  /// Proxies in front of the application, which report the original client address and scheme.
+  pub proxy: ProxyConfig,
+  pub compression: CompressionConfig,
+  pub cors: CorsConfig,
+  pub security_headers: SecurityHeadersConfig,
+  pub static_files: StaticFilesConfig,
+  /// Default timeouts of requests and loaders, can be overridden for each route.
+  pub timeout: TimeoutConfig,
+  /// Default limits of WebSocket connections, can be overridden for each upgrade.
+  pub websocket: WebSocketConfig,
+  /// How long to wait for open connections to finish during graceful shutdown before exiting anyway.
+  pub shutdown_timeout: Duration,
 }
 
 impl Default for AppConfig {
@@ -14,6 +44,20 @@ impl Default for AppConfig {
       trailing_slash: TrailingSlashConfig::default(),
       addresses: &["127.0.0.1:3000"],
       partytown: PartytownConfig::default(),
+      log: LogConfig::default(),
+      access_log: AccessLogConfig::default(),
+      metrics: MetricsConfig::default(),
+      health: HealthConfig::default(),
+      request_id: RequestIdConfig::default(),
+      load_shedding: LoadSheddingConfig::default(),
+      proxy: ProxyConfig::default(),
+      compression: CompressionConfig::default(),
+      cors: CorsConfig::default(),
+      security_headers: SecurityHeadersConfig::default(),
+      static_files: StaticFilesConfig::default(),
+      timeout: TimeoutConfig::default(),
+      websocket: WebSocketConfig::default(),
+      shutdown_timeout: Duration::from_secs(30),
     }
   }
 }
@@ -33,13 +77,15 @@ pub fn register_app_config(app_config: AppConfig) {
   if let Err(err) = APP_CONFIG.set(app_config) {
     match err {
       SetError::AlreadyInitializedError(_) => {
-        // TODO: Better logging
-        eprintln!("warning: app config already initialized");
+        log::warn("app_config_already_initialized", "app config already initialized", &[]);
       }
       SetError::InitializingError(_) => {
-        // TODO: Better logging
-        eprintln!("warning: app config already being initialized");
+        log::warn("app_config_already_initializing", "app config already being initialized", &[]);
       }
     }
+
+    return;
   }
+
+  log::init(&get_app_config().log);
 }
@@ -0,0 +1,73 @@
+pub struct CompressionConfig {
+  /// Whether to compress responses for clients accepting it. Enabled by default.
+  pub enabled: bool,
+  /// Supported encodings in the order of preference, used when the client accepts multiple
+  /// encodings with the same quality.
+  pub encodings: &'static [ContentEncoding],
+  /// The compression level used for responses compressed at runtime. Pre-rendered
+  /// static responses are compressed at build time with the best compression.
+  pub level: CompressionLevel,
+  /// Responses are only compressed if their content type matches one of these rules,
+  /// and their body is at least as large as the rule's `min_size`.
+  pub rules: &'static [CompressionRule],
+}
+
+impl Default for CompressionConfig {
+  fn default() -> Self {
+    CompressionConfig {
+      enabled: true,
+      encodings: &[ContentEncoding::Zstd, ContentEncoding::Brotli, ContentEncoding::Gzip],
+      level: CompressionLevel::default(),
+      rules: DEFAULT_COMPRESSION_RULES,
+    }
+  }
+}
+
+/// The rules used by default – textual content types larger than 1 kB.
+pub const DEFAULT_COMPRESSION_RULES: &[CompressionRule] = &[
+  CompressionRule { content_type: "text/", min_size: 1024 },
+  CompressionRule { content_type: "application/json", min_size: 1024 },
+  CompressionRule { content_type: "application/javascript", min_size: 1024 },
+  CompressionRule { content_type: "application/xml", min_size: 1024 },
+  CompressionRule { content_type: "application/manifest+json", min_size: 1024 },
+  CompressionRule { content_type: "application/wasm", min_size: 1024 },
+  CompressionRule { content_type: "image/svg+xml", min_size: 1024 },
+];
+
+#[derive(Debug, Copy, Clone)]
+pub struct CompressionRule {
+  /// The media type to match, without parameters (e.g. `application/json`).
+  /// Ending it with a slash matches all the subtypes (e.g. `text/`).
+  pub content_type: &'static str,
+  /// The minimum size of the body in bytes. Smaller responses are sent uncompressed.
+  pub min_size: u64,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ContentEncoding {
+  Gzip,
+  Brotli,
+  Zstd,
+}
+
+impl ContentEncoding {
+  /// Returns the token of this encoding as used in the `Content-Encoding` and `Accept-Encoding` headers.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ContentEncoding::Gzip => "gzip",
+      ContentEncoding::Brotli => "br",
+      ContentEncoding::Zstd => "zstd",
+    }
+  }
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub enum CompressionLevel {
+  /// The fastest compression, trading compression ratio for latency.
+  Fastest,
+  /// A balanced level suitable for compressing responses on the fly.
+  #[default]
+  Default,
+  /// The best compression ratio, significantly slower.
+  Best,
+}
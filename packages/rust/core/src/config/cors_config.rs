@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+/// A Cross-Origin Resource Sharing policy.
+///
+/// Set globally as `AppConfig::cors`, or for a segment and all its descendants
+/// by returning it from the `cors` function of the segment's `cors.rs` file.
+pub struct CorsConfig {
+  /// Whether CORS headers are sent and preflight requests answered. Disabled by default.
+  pub enabled: bool,
+  /// The origins allowed to access the resources.
+  pub allowed_origins: &'static [CorsOrigin],
+  /// The methods allowed in cross-origin requests, announced in preflight responses.
+  pub allowed_methods: &'static [&'static str],
+  /// The request headers allowed in cross-origin requests. `"*"` allows any header.
+  pub allowed_headers: &'static [&'static str],
+  /// The response headers exposed to the scripts of other origins, besides the CORS-safelisted ones.
+  pub exposed_headers: &'static [&'static str],
+  /// Whether cross-origin requests can include credentials (cookies, HTTP authentication).
+  pub allow_credentials: bool,
+  /// How long the browser can cache the preflight response. The browser's default (5 seconds) if `None`.
+  pub max_age: Option<Duration>,
+}
+
+impl Default for CorsConfig {
+  fn default() -> Self {
+    CorsConfig {
+      enabled: false,
+      allowed_origins: &[],
+      allowed_methods: &["GET", "HEAD", "POST"],
+      allowed_headers: &[],
+      exposed_headers: &[],
+      allow_credentials: false,
+      max_age: None,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum CorsOrigin {
+  /// Any origin.
+  Any,
+  /// A single origin, e.g. `https://example.com`.
+  Exact(&'static str),
+  /// Origins matching a pattern with a single `*` wildcard standing for one or more characters,
+  /// e.g. `https://*.example.com`.
+  Pattern(&'static str),
+}
+
+impl CorsOrigin {
+  /// Whether the value of the request's `Origin` header matches. Origins are compared case-insensitively.
+  pub fn matches(&self, origin: &str) -> bool {
+    match self {
+      CorsOrigin::Any => true,
+      CorsOrigin::Exact(allowed) => allowed.eq_ignore_ascii_case(origin),
+      CorsOrigin::Pattern(pattern) => match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+          origin.len() > prefix.len() + suffix.len()
+            && origin.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+            && origin.get(origin.len() - suffix.len()..).is_some_and(|end| end.eq_ignore_ascii_case(suffix))
+        }
+        None => pattern.eq_ignore_ascii_case(origin),
+      },
+    }
+  }
+}
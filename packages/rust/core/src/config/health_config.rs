@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+pub struct HealthConfig {
+  /// Whether to serve the health endpoints. Disabled by default.
+  pub enabled: bool,
+  /// The path of the liveness endpoint, which responds with `200 OK` as long as the server processes requests.
+  pub liveness_path: &'static str,
+  /// The path of the readiness endpoint, which responds with `200 OK` once all the listeners are bound
+  /// and all the registered readiness checks pass, and with `503 Service Unavailable` otherwise,
+  /// including during graceful shutdown.
+  pub readiness_path: &'static str,
+  /// How long the readiness endpoint fails before graceful shutdown starts, so that load balancers stop
+  /// sending new requests first. Connections are still accepted and served meanwhile. Set it to a few
+  /// periods of the readiness probe. Zero by default, shutting down right away.
+  pub shutdown_delay: Duration,
+}
+
+impl Default for HealthConfig {
+  fn default() -> Self {
+    HealthConfig { enabled: false, liveness_path: "/livez", readiness_path: "/readyz", shutdown_delay: Duration::ZERO }
+  }
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+/// Limits on the work taken by each worker thread. Connections and requests over them are rejected
+/// right away with `503 Service Unavailable`, instead of piling up and slowing everything down.
+///
+/// Accepted connections wait for a worker in a queue of `Server::REQUEST_QUEUE_SIZE` connections.
+/// A worker at `max_connections_per_worker` doesn't take any more connections from the queue,
+/// and connections accepted while the queue is full are shed.
+pub struct LoadSheddingConfig {
+  /// The maximum number of connections processed by a worker at once, `None` for no limit.
+  pub max_connections_per_worker: Option<usize>,
+  /// The maximum number of requests processed by a worker at once, across all its connections.
+  /// Requests over the limit are answered right away, `None` for no limit.
+  pub max_requests_per_worker: Option<usize>,
+  /// The delay sent in the `Retry-After` header of the `503` responses, rounded up to whole seconds.
+  pub retry_after: Duration,
+}
+
+impl Default for LoadSheddingConfig {
+  fn default() -> Self {
+    LoadSheddingConfig {
+      max_connections_per_worker: Some(1024),
+      max_requests_per_worker: None,
+      retry_after: Duration::from_secs(1),
+    }
+  }
+}
@@ -0,0 +1,23 @@
+use crate::log::{LogLevel, Logger};
+
+#[derive(Default)]
+pub struct LogConfig {
+  /// The most verbose level of events to log. Defaults to `Info`.
+  ///
+  /// This can be overridden at runtime with the `RUXY_LOG` environment variable
+  /// (e.g. `RUXY_LOG=debug`), or with the `--debug` flag of the Ruxy CLI.
+  pub level: LogLevel,
+  /// The output format of the default stderr logger.
+  pub format: LogFormat,
+  /// A custom logger to receive all log events instead of the default stderr logger.
+  pub logger: Option<&'static dyn Logger>,
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+pub enum LogFormat {
+  /// Human-readable lines, e.g. `2025-06-01T12:30:45.123Z ERROR listener_bind_failed: ... address=[::]:80`.
+  #[default]
+  Text,
+  /// One JSON object per line, suitable for log pipelines.
+  Json,
+}
@@ -0,0 +1,13 @@
+pub struct MetricsConfig {
+  /// Whether to collect request metrics and serve them at `path`. Disabled by default.
+  pub enabled: bool,
+  /// The path of the endpoint serving the metrics in the Prometheus text format.
+  /// Requests to this path are not passed to the router.
+  pub path: &'static str,
+}
+
+impl Default for MetricsConfig {
+  fn default() -> Self {
+    MetricsConfig { enabled: false, path: "/metrics" }
+  }
+}
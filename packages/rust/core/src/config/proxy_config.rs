@@ -0,0 +1,10 @@
+#[derive(Default)]
+pub struct ProxyConfig {
+  /// Proxies trusted to report the address of the client and the scheme of its request – IP addresses
+  /// (`10.0.0.1`), CIDR ranges (`10.0.0.0/8`, `fd00::/8`), or `unix` for connections accepted on
+  /// Unix domain sockets. Forwarding headers and PROXY protocol headers from anyone else are ignored.
+  pub trusted_proxies: &'static [&'static str],
+  /// Whether connections start with a PROXY protocol header (v1 or v2), as sent by HAProxy, AWS NLB
+  /// and others. Connections from untrusted peers and connections without a valid header are closed.
+  pub proxy_protocol: bool,
+}
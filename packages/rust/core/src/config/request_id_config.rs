@@ -0,0 +1,15 @@
+pub struct RequestIdConfig {
+  /// The header to take the incoming request ID from, and to send it back in.
+  pub header: &'static str,
+  /// Whether to take the request ID from the incoming request header, if present and valid.
+  /// Disable this if clients can reach the application directly, without a proxy setting the header.
+  pub trust_incoming: bool,
+  /// Whether to send the request ID back in the response header.
+  pub echo: bool,
+}
+
+impl Default for RequestIdConfig {
+  fn default() -> Self {
+    RequestIdConfig { header: "x-request-id", trust_incoming: true, echo: true }
+  }
+}
@@ -0,0 +1,52 @@
+/// A strict Content Security Policy allowing only the scripts carrying the per-request nonce
+/// (and the scripts they load), as recommended by https://csp.withgoogle.com/docs/strict-csp.html.
+pub const STRICT_CONTENT_SECURITY_POLICY: &str =
+  "script-src 'nonce-{nonce}' 'strict-dynamic'; object-src 'none'; base-uri 'none'";
+
+pub struct SecurityHeadersConfig {
+  /// Whether to add the security headers to responses. Enabled by default.
+  /// Headers already set by a handler or a middleware are kept as they are.
+  pub enabled: bool,
+  /// The `Content-Security-Policy` header, not sent by default. Each `{nonce}` is replaced with
+  /// a nonce generated for the request, which Ruxy adds to every script tag it emits.
+  pub content_security_policy: Option<&'static str>,
+  /// The `Strict-Transport-Security` header, e.g. `max-age=63072000; includeSubDomains`. Not sent by default,
+  /// as it only makes sense when the application is served over HTTPS.
+  pub strict_transport_security: Option<&'static str>,
+  /// Whether to send `X-Content-Type-Options: nosniff`.
+  pub content_type_options: bool,
+  /// The `Referrer-Policy` header.
+  pub referrer_policy: Option<&'static str>,
+  /// The `X-Frame-Options` header.
+  pub frame_options: Option<FrameOptions>,
+}
+
+impl Default for SecurityHeadersConfig {
+  fn default() -> Self {
+    SecurityHeadersConfig {
+      enabled: true,
+      content_security_policy: None,
+      strict_transport_security: None,
+      content_type_options: true,
+      referrer_policy: Some("strict-origin-when-cross-origin"),
+      frame_options: Some(FrameOptions::SameOrigin),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameOptions {
+  /// The pages can't be displayed in a frame at all.
+  Deny,
+  /// The pages can only be displayed in a frame on the same origin.
+  SameOrigin,
+}
+
+impl FrameOptions {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      FrameOptions::Deny => "DENY",
+      FrameOptions::SameOrigin => "SAMEORIGIN",
+    }
+  }
+}
@@ -0,0 +1,37 @@
+pub struct StaticFilesConfig {
+  /// Whether to serve the files from the `public/` directory next to `app/`. Enabled by default.
+  pub enabled: bool,
+  /// Whether the files are looked up before the routes are matched, or only for requests no route matched.
+  pub priority: StaticFilesPriority,
+  /// The `Cache-Control` header sent with the files.
+  pub cache_control: &'static str,
+  /// Whether to embed the files into the binary, so that the application can be deployed as a single file.
+  /// Otherwise, the files are read from `dir` at runtime.
+  pub embed: bool,
+  /// The directory the files are read from at runtime when they aren't embedded, `public` by default.
+  /// A relative path is resolved against the working directory of the server, which `ruxy serve` sets
+  /// to the production build (containing a copy of `public/`). The build only indexes the files,
+  /// their contents, length and validators are read when they're served.
+  pub dir: &'static str,
+}
+
+impl Default for StaticFilesConfig {
+  fn default() -> Self {
+    StaticFilesConfig {
+      enabled: true,
+      priority: StaticFilesPriority::default(),
+      cache_control: "public, max-age=3600",
+      embed: false,
+      dir: "public",
+    }
+  }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum StaticFilesPriority {
+  /// Files shadow the routes with the same path.
+  #[default]
+  BeforeRoutes,
+  /// Files are served only when no route matched the request.
+  AfterRoutes,
+}
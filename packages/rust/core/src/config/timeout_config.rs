@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// Limits on how long a request may take. Each of them can be disabled with `None`.
+///
+/// Routes can override `request` and `loader` with a `timeout.rs` file, see `RouteTimeout`.
+pub struct TimeoutConfig {
+  /// How long a client has to send the headers of a request, counted from the accepted connection
+  /// or the previous response on a kept-alive HTTP/1 one. HTTP/2 connections have to send their first
+  /// request in time, after that they're kept alive by `http2_keep_alive`.
+  pub header_read: Option<Duration>,
+  /// The interval at which idle HTTP/2 connections are pinged. A connection whose client doesn't
+  /// answer the ping within the same interval is closed. Off by default, so no pings are sent.
+  pub http2_keep_alive: Option<Duration>,
+  /// How long a route may take to produce its response, including middleware and loaders.
+  /// Bodies streamed after the response head was produced are not limited.
+  pub request: Option<Duration>,
+  /// How long each loader may take. A loader past its deadline is cancelled and the nearest
+  /// error loader gets a `LoaderTimeout` error.
+  pub loader: Option<Duration>,
+}
+
+impl Default for TimeoutConfig {
+  fn default() -> Self {
+    TimeoutConfig {
+      header_read: Some(Duration::from_secs(30)),
+      http2_keep_alive: None,
+      request: Some(Duration::from_secs(60)),
+      loader: Some(Duration::from_secs(30)),
+    }
+  }
+}
@@ -1,3 +1,5 @@
+use crate::log;
+
 #[derive(Default, Debug, Copy, Clone)]
 pub enum TrailingSlashConfig {
   /// Requires that the trailing slash is present in the request URL.
@@ -91,10 +93,14 @@ impl From<u8> for TrailingSlashConfig {
       3 => Self::RedirectToRemoved,
       4 => Self::RedirectToAdded,
       _ => {
-        // TODO: Logging
-        eprintln!("[WARNING] Invalid trailing slash config value: {value}. Using default.");
+        log::warn(
+          "invalid_trailing_slash_config",
+          "invalid trailing slash config value, using default",
+          &[("value", value.into())],
+        );
+
         Self::default()
-      },
+      }
     }
   }
 }
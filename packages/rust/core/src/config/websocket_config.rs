@@ -0,0 +1,12 @@
+pub struct WebSocketConfig {
+  /// The maximum size of a single incoming frame in bytes. Larger frames close the connection.
+  pub max_frame_size: usize,
+  /// The maximum size of an incoming message in bytes, which can be split into multiple frames.
+  pub max_message_size: usize,
+}
+
+impl Default for WebSocketConfig {
+  fn default() -> Self {
+    WebSocketConfig { max_frame_size: 16 << 20, max_message_size: 64 << 20 }
+  }
+}
@@ -35,3 +35,11 @@ pub const SEG_FILE_LAYOUT_TS: &str = "layout.ts";
 pub const SEG_FILE_LAYOUT_TSX: &str = "layout.tsx";
 pub const SEG_FILE_LAYOUT_MD: &str = "layout.md";
 pub const SEG_FILE_LAYOUT_MDX: &str = "layout.mdx";
+
+pub const SEG_FILE_MIDDLEWARE_RS: &str = "middleware.rs";
+
+pub const SEG_FILE_CORS_RS: &str = "cors.rs";
+
+pub const SEG_FILE_RATE_LIMIT_RS: &str = "rate_limit.rs";
+
+pub const SEG_FILE_TIMEOUT_RS: &str = "timeout.rs";
@@ -1,6 +1,13 @@
 pub const APP_DIR: &str = "app";
 pub const DOT_RUXY_DIR: &str = ".ruxy";
+pub const PUBLIC_DIR: &str = "public";
 pub const ROUTES_DIR: &str = "routes";
 
 pub const CONFIG_FILE: &str = "config.rs";
 pub const GENERATED_APP_RS_FILE: &str = "app.rs";
+
+pub const DIST_DIR: &str = "dist";
+pub const DIST_ASSETS_DIR: &str = "assets";
+pub const BUILD_MANIFEST_FILE: &str = "manifest.json";
+pub const CLIENT_OUT_DIR: &str = "client";
+pub const CLIENT_ASSETS_FILE: &str = "assets.json";
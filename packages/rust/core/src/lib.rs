@@ -2,6 +2,8 @@ pub mod build;
 pub mod cli;
 pub mod compiler;
 pub mod config;
+pub mod log;
+pub mod metrics;
 pub mod routing;
 pub mod runtime;
 pub mod server;
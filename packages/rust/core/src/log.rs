@@ -0,0 +1,113 @@
+//! Ruxy's logging facade.
+//!
+//! All the events emitted by Ruxy (and by the user code through this module) are leveled and
+//! structured – each event has a machine-readable name, a human-readable message, and a list of
+//! key-value fields. Events are passed to the configured `Logger`, which is the stderr logger
+//! by default, configured using `AppConfig::log`.
+
+mod event;
+mod level;
+mod logger;
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::{LogConfig, LogFormat};
+use crate::server::request::request_id;
+
+pub use event::{LogEvent, LogValue};
+pub use level::LogLevel;
+pub use logger::{Logger, StderrLogger};
+
+/// The level of events to log, overriding `LogConfig::level`. The Ruxy CLI sets it to `debug`
+/// for the servers it runs with `--debug`.
+pub const ENV_LOG: &str = "RUXY_LOG";
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+static LOGGER: OnceLock<&'static dyn Logger> = OnceLock::new();
+
+static STDERR_TEXT_LOGGER: StderrLogger = StderrLogger::new(LogFormat::Text);
+static STDERR_JSON_LOGGER: StderrLogger = StderrLogger::new(LogFormat::Json);
+
+/// Initializes the logging facade from the app config.
+///
+/// The `RUXY_LOG` environment variable takes precedence over the configured level.
+/// Events emitted before the initialization are logged to stderr at the `Info` level.
+pub fn init(config: &LogConfig) {
+  let logger = match (config.logger, config.format) {
+    (Some(logger), _) => logger,
+    (None, LogFormat::Text) => &STDERR_TEXT_LOGGER,
+    (None, LogFormat::Json) => &STDERR_JSON_LOGGER,
+  };
+
+  if LOGGER.set(logger).is_err() {
+    warn("logger_already_initialized", "logger already initialized", &[]);
+  }
+
+  set_max_level(get_env_level().unwrap_or(config.level));
+}
+
+/// Sets the most verbose level of events that will be logged.
+pub fn set_max_level(level: LogLevel) {
+  MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns the most verbose level of events that will be logged.
+pub fn max_level() -> LogLevel {
+  LogLevel::from(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Whether events of the provided level will be logged.
+///
+/// Use this to skip expensive computations of log fields when the event would be discarded anyway.
+#[inline]
+pub fn enabled(level: LogLevel) -> bool {
+  level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+/// Logs an event with the provided level.
+pub fn log(level: LogLevel, name: &'static str, message: &str, fields: &[(&'static str, LogValue)]) {
+  if !enabled(level) {
+    return;
+  }
+
+  let logger = LOGGER.get().copied().unwrap_or(&STDERR_TEXT_LOGGER);
+  let request_id = request_id::current();
+
+  logger.log(&LogEvent { level, name, message, request_id: request_id.as_deref(), fields });
+}
+
+/// Logs an event with the `Error` level.
+pub fn error(name: &'static str, message: &str, fields: &[(&'static str, LogValue)]) {
+  log(LogLevel::Error, name, message, fields);
+}
+
+/// Logs an event with the `Warn` level.
+pub fn warn(name: &'static str, message: &str, fields: &[(&'static str, LogValue)]) {
+  log(LogLevel::Warn, name, message, fields);
+}
+
+/// Logs an event with the `Info` level.
+pub fn info(name: &'static str, message: &str, fields: &[(&'static str, LogValue)]) {
+  log(LogLevel::Info, name, message, fields);
+}
+
+/// Logs an event with the `Debug` level.
+pub fn debug(name: &'static str, message: &str, fields: &[(&'static str, LogValue)]) {
+  log(LogLevel::Debug, name, message, fields);
+}
+
+/// Logs an event with the `Trace` level.
+pub fn trace(name: &'static str, message: &str, fields: &[(&'static str, LogValue)]) {
+  log(LogLevel::Trace, name, message, fields);
+}
+
+fn get_env_level() -> Option<LogLevel> {
+  match std::env::var(ENV_LOG) {
+    Err(std::env::VarError::NotUnicode(e)) => {
+      panic!("\"{ENV_LOG}\" must be valid unicode, error: {e:?}")
+    }
+    Err(std::env::VarError::NotPresent) => None,
+    Ok(value) => Some(value.parse().unwrap_or_else(|e| panic!("\"{ENV_LOG}\" is invalid: {e}"))),
+  }
+}
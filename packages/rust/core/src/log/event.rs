@@ -0,0 +1,76 @@
+use std::fmt::{Display, Formatter};
+
+use super::LogLevel;
+
+/// A single structured log event.
+pub struct LogEvent<'a> {
+  pub level: LogLevel,
+  /// A machine-readable name of the event, e.g. `listener_bind_failed`.
+  pub name: &'static str,
+  /// A human-readable description of the event.
+  pub message: &'a str,
+  /// The ID of the request being processed when the event was emitted, if any.
+  pub request_id: Option<&'a str>,
+  /// Additional structured data of the event.
+  pub fields: &'a [(&'static str, LogValue<'a>)],
+}
+
+/// A value of a log event field.
+///
+/// Values can be created from strings, numbers, booleans, and references
+/// to anything implementing `Display` using the `From` conversions.
+pub enum LogValue<'a> {
+  Str(&'a str),
+  Int(i64),
+  Uint(u64),
+  Float(f64),
+  Bool(bool),
+  Display(&'a dyn Display),
+}
+
+impl Display for LogValue<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      LogValue::Str(value) => f.write_str(value),
+      LogValue::Int(value) => write!(f, "{value}"),
+      LogValue::Uint(value) => write!(f, "{value}"),
+      LogValue::Float(value) => write!(f, "{value}"),
+      LogValue::Bool(value) => write!(f, "{value}"),
+      LogValue::Display(value) => write!(f, "{value}"),
+    }
+  }
+}
+
+impl<'a> From<&'a str> for LogValue<'a> {
+  fn from(value: &'a str) -> Self {
+    LogValue::Str(value)
+  }
+}
+
+impl<'a, T: Display> From<&'a T> for LogValue<'a> {
+  fn from(value: &'a T) -> Self {
+    LogValue::Display(value)
+  }
+}
+
+macro_rules! impl_from_number {
+  ($variant:ident, $target:ty, $($source:ty),*) => {
+    $(
+      impl From<$source> for LogValue<'_> {
+        fn from(value: $source) -> Self {
+          LogValue::$variant(value as $target)
+        }
+      }
+    )*
+  };
+}
+
+impl_from_number!(Int, i64, i8, i16, i32, i64, isize);
+impl_from_number!(Uint, u64, u8, u16, u32, u64, usize);
+impl_from_number!(Float, f64, f32, f64);
+
+impl From<bool> for LogValue<'_> {
+  fn from(value: bool) -> Self {
+    LogValue::Bool(value)
+  }
+}
@@ -0,0 +1,58 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The severity of a log event, from the most severe to the most verbose.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LogLevel {
+  Error = 1,
+  Warn = 2,
+  #[default]
+  Info = 3,
+  Debug = 4,
+  Trace = 5,
+}
+
+impl LogLevel {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LogLevel::Error => "error",
+      LogLevel::Warn => "warn",
+      LogLevel::Info => "info",
+      LogLevel::Debug => "debug",
+      LogLevel::Trace => "trace",
+    }
+  }
+}
+
+impl From<u8> for LogLevel {
+  fn from(value: u8) -> Self {
+    match value {
+      0 | 1 => LogLevel::Error,
+      2 => LogLevel::Warn,
+      3 => LogLevel::Info,
+      4 => LogLevel::Debug,
+      _ => LogLevel::Trace,
+    }
+  }
+}
+
+impl FromStr for LogLevel {
+  type Err = String;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    match value.to_ascii_lowercase().as_str() {
+      "error" => Ok(LogLevel::Error),
+      "warn" | "warning" => Ok(LogLevel::Warn),
+      "info" => Ok(LogLevel::Info),
+      "debug" => Ok(LogLevel::Debug),
+      "trace" => Ok(LogLevel::Trace),
+      _ => Err(format!("unknown log level '{value}', expected one of: error, warn, info, debug, trace")),
+    }
+  }
+}
+
+impl Display for LogLevel {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+use crate::config::LogFormat;
+use crate::util::json::write_json_string;
+use crate::util::time::UtcDateTime;
+
+use super::{LogEvent, LogValue};
+
+/// A destination of log events.
+///
+/// Implement this trait and set it as `AppConfig::log.logger` to forward Ruxy's
+/// log events to your own logging pipeline. The events are already filtered by
+/// the configured log level when they reach the logger.
+pub trait Logger: Send + Sync {
+  fn log(&self, event: &LogEvent);
+}
+
+/// The default logger, writing one line per event to the standard error output.
+pub struct StderrLogger {
+  format: LogFormat,
+}
+
+impl StderrLogger {
+  pub const fn new(format: LogFormat) -> Self {
+    Self { format }
+  }
+}
+
+impl Logger for StderrLogger {
+  fn log(&self, event: &LogEvent) {
+    let line = match self.format {
+      LogFormat::Text => format_text(event),
+      LogFormat::Json => format_json(event),
+    };
+
+    // Writing the whole line at once so that lines from multiple threads don't interleave.
+    // There's nowhere to report a failure to write to stderr, so we ignore it.
+    let _ = std::io::stderr().lock().write_all(line.as_bytes());
+  }
+}
+
+/// Formats the event as `<timestamp> <LEVEL> <name>: <message> key=value key="quoted value"`.
+fn format_text(event: &LogEvent) -> String {
+  let mut line = String::with_capacity(128);
+
+  let timestamp = UtcDateTime::now().to_rfc3339();
+  let level = event.level.as_str().to_ascii_uppercase();
+
+  // Writing to String can't fail
+  let _ = write!(line, "{timestamp} {level:<5} {}: {}", event.name, event.message);
+
+  if let Some(request_id) = event.request_id {
+    write_text_field(&mut line, "request_id", request_id);
+  }
+
+  for (key, value) in event.fields {
+    write_text_field(&mut line, key, &value.to_string());
+  }
+
+  line.push('\n');
+  line
+}
+
+/// Writes ` key=value`, quoting the value if it's empty or contains whitespace, quotes, or equal signs.
+fn write_text_field(line: &mut String, key: &str, value: &str) {
+  if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+    let _ = write!(line, " {key}={value:?}");
+  } else {
+    let _ = write!(line, " {key}={value}");
+  }
+}
+
+/// Formats the event as a single-line JSON object.
+fn format_json(event: &LogEvent) -> String {
+  let mut line = String::with_capacity(192);
+
+  line.push_str("{\"ts\":\"");
+  line.push_str(&UtcDateTime::now().to_rfc3339());
+  line.push_str("\",\"level\":\"");
+  line.push_str(event.level.as_str());
+  line.push_str("\",\"event\":");
+  write_json_string(&mut line, event.name);
+  line.push_str(",\"message\":");
+  write_json_string(&mut line, event.message);
+
+  if let Some(request_id) = event.request_id {
+    line.push_str(",\"request_id\":");
+    write_json_string(&mut line, request_id);
+  }
+
+  for (key, value) in event.fields {
+    line.push(',');
+    write_json_string(&mut line, key);
+    line.push(':');
+
+    match value {
+      LogValue::Str(value) => write_json_string(&mut line, value),
+      LogValue::Display(value) => write_json_string(&mut line, &value.to_string()),
+      LogValue::Float(value) if !value.is_finite() => line.push_str("null"),
+      // Numbers and booleans are written as they are. Writing to String can't fail.
+      value => _ = write!(line, "{value}"),
+    }
+  }
+
+  line.push_str("}\n");
+  line
+}
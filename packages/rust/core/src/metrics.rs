@@ -0,0 +1,138 @@
+//! Ruxy's metrics registry.
+//!
+//! Ruxy collects per-route request counts and latencies, connection accept errors, connections and
+//! requests shed due to overload, and runtime statistics (the depth of the connection queue and the
+//! tasks and requests of each worker). When enabled
+//! using `AppConfig::metrics`, all of these, along with counters registered by the user code,
+//! are served in the Prometheus text format.
+
+#[cfg(test)]
+mod test;
+
+mod counter;
+mod histogram;
+mod registry;
+mod render;
+mod worker;
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use hyper::{Response, http};
+
+use crate::config::MetricsConfig;
+use crate::server::response::body::ResponseBody;
+
+pub use counter::Counter;
+pub use render::render;
+pub use worker::{ActiveRequestGuard, WorkerMetrics};
+
+use registry::REGISTRY;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+  /// The metrics of the worker running on the current thread, if it's a worker thread.
+  static CURRENT_WORKER: Cell<Option<&'static WorkerMetrics>> = const { Cell::new(None) };
+}
+
+/// Enables collecting of request metrics if configured so.
+pub fn init(config: &MetricsConfig) {
+  ENABLED.store(config.enabled, Ordering::Relaxed);
+}
+
+/// Whether request metrics are collected and served.
+#[inline]
+pub fn enabled() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the counter registered with the provided name, registering it first if it doesn't exist yet.
+///
+/// Registering takes a lock, so prefer keeping the returned reference (e.g. in a `LazyLock`)
+/// over calling this function on every request.
+///
+/// ### Panics
+/// This function panics if the name is not a valid Prometheus metric name,
+/// or if it is reserved for the metrics collected by Ruxy (starts with `ruxy_`).
+pub fn counter(name: &'static str, help: &'static str) -> &'static Counter {
+  REGISTRY.counter(name, help)
+}
+
+/// Allocates the request metrics of the routes with the provided IDs. Called from the generated `main` function,
+/// before the server starts. Requests of routes not registered here aren't recorded.
+pub fn register_routes(route_ids: &[&'static str]) {
+  REGISTRY.init_routes(route_ids);
+}
+
+/// Records a request served by the route with the provided ID (`None` if no route was matched).
+pub fn record_request(
+  route_id: Option<&'static str>,
+  response: &http::Result<Response<ResponseBody>>,
+  duration: Duration,
+) {
+  // Hyper closes the connection when the service fails, which the client sees as a server error.
+  let status = response.as_ref().map_or(500, |response| response.status().as_u16());
+
+  if let Some(route) = REGISTRY.route(route_id) {
+    route.record(status, duration);
+  }
+}
+
+/// Records a connection accepted by one of the listeners.
+#[inline]
+pub fn record_connection_accepted() {
+  REGISTRY.connections_accepted.inc();
+}
+
+/// Records a failure to accept a connection.
+#[inline]
+pub fn record_accept_error() {
+  REGISTRY.accept_errors.inc();
+}
+
+/// Records a connection closed right away because no worker could take it.
+#[inline]
+pub fn record_shed_connection() {
+  REGISTRY.shed_connections.inc();
+}
+
+/// Records a request rejected because the worker processing it was at its limit.
+#[inline]
+pub fn record_shed_request() {
+  REGISTRY.shed_requests.inc();
+}
+
+/// Registers the function reporting how many connections wait in the queue for a worker thread.
+pub fn register_queue_depth(depth: impl Fn() -> usize + Send + Sync + 'static) {
+  REGISTRY.register_queue_depth(Box::new(depth));
+}
+
+/// Allocates the metrics of the worker threads processing the connections.
+pub fn init_workers(count: usize) {
+  REGISTRY.init_workers(count);
+}
+
+/// Returns the number of connection tasks currently running on all the worker threads.
+pub fn active_tasks() -> u64 {
+  REGISTRY.workers().iter().map(WorkerMetrics::active_tasks).sum()
+}
+
+/// Marks the current thread as the worker thread with the provided index, see `current_worker`.
+pub fn set_current_worker(index: usize) {
+  CURRENT_WORKER.set(Some(worker(index)));
+}
+
+/// Returns the metrics of the worker running on the current thread, `None` outside of worker threads.
+pub fn current_worker() -> Option<&'static WorkerMetrics> {
+  CURRENT_WORKER.get()
+}
+
+/// Returns the metrics of the worker thread with the provided index.
+///
+/// ### Panics
+/// This function panics if the workers were not initialized using `init_workers` or the index is out of range.
+pub fn worker(index: usize) -> &'static WorkerMetrics {
+  &REGISTRY.workers()[index]
+}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A monotonically increasing counter.
+#[derive(Debug, Default)]
+pub struct Counter {
+  value: AtomicU64,
+}
+
+impl Counter {
+  pub const fn new() -> Self {
+    Self { value: AtomicU64::new(0) }
+  }
+
+  /// Increments the counter by one.
+  #[inline]
+  pub fn inc(&self) {
+    self.add(1);
+  }
+
+  /// Increments the counter by the provided amount.
+  #[inline]
+  pub fn add(&self, amount: u64) {
+    self.value.fetch_add(amount, Ordering::Relaxed);
+  }
+
+  /// Returns the current value of the counter.
+  #[inline]
+  pub fn get(&self) -> u64 {
+    self.value.load(Ordering::Relaxed)
+  }
+}
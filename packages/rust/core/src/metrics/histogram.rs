@@ -0,0 +1,39 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds of the latency buckets, in seconds.
+pub const LATENCY_BUCKETS: [f64; 14] =
+  [0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// A histogram of durations with fixed `LATENCY_BUCKETS`.
+#[derive(Debug, Default)]
+pub struct Histogram {
+  /// Observations per bucket (not cumulative), the last one is for observations above all the bounds.
+  buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+  sum_micros: AtomicU64,
+}
+
+impl Histogram {
+  pub fn observe(&self, duration: Duration) {
+    let seconds = duration.as_secs_f64();
+    let index = LATENCY_BUCKETS.iter().position(|&bound| seconds <= bound).unwrap_or(LATENCY_BUCKETS.len());
+
+    self.buckets[index].fetch_add(1, Ordering::Relaxed);
+    self.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+  }
+
+  /// Returns the cumulative counts of all the buckets, the last one being the total count.
+  pub fn cumulative_counts(&self) -> [u64; LATENCY_BUCKETS.len() + 1] {
+    let mut total = 0;
+
+    self.buckets.each_ref().map(|bucket| {
+      total += bucket.load(Ordering::Relaxed);
+      total
+    })
+  }
+
+  /// Returns the sum of all the observations, in seconds.
+  pub fn sum(&self) -> f64 {
+    self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+  }
+}
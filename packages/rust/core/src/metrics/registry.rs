@@ -0,0 +1,135 @@
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::Duration;
+
+use super::counter::Counter;
+use super::histogram::Histogram;
+use super::worker::WorkerMetrics;
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+/// All the metrics collected by the application.
+#[derive(Default)]
+pub struct Registry {
+  /// Request metrics per route ID, `None` for requests not matching any route, sorted by the route ID.
+  /// Routes are known at compile time, so they're all registered at startup and looked up without locking.
+  routes: OnceLock<Box<[RouteEntry]>>,
+  counters: Mutex<Vec<UserCounter>>,
+  pub connections_accepted: Counter,
+  pub accept_errors: Counter,
+  /// Connections closed right away because the queue of connections waiting for a worker was full.
+  pub shed_connections: Counter,
+  /// Requests rejected because the worker processing them was at its limit.
+  pub shed_requests: Counter,
+  queue_depth: OnceLock<Box<dyn Fn() -> usize + Send + Sync>>,
+  workers: OnceLock<Box<[WorkerMetrics]>>,
+}
+
+/// The ID of a route (`None` for requests not matching any route) and its request metrics.
+type RouteEntry = (Option<&'static str>, RouteMetrics);
+
+/// Request metrics of a single route.
+#[derive(Default)]
+pub struct RouteMetrics {
+  /// Responses by status class, from `1xx` to `5xx`.
+  pub responses: [Counter; 5],
+  pub latency: Histogram,
+}
+
+/// A counter registered by the user code.
+#[derive(Copy, Clone)]
+pub struct UserCounter {
+  pub name: &'static str,
+  pub help: &'static str,
+  pub counter: &'static Counter,
+}
+
+impl Registry {
+  /// Allocates the metrics of all the routes of the application, along with those of unmatched requests.
+  pub fn init_routes(&self, route_ids: &[&'static str]) {
+    let mut routes: Vec<_> = std::iter::once(None).chain(route_ids.iter().copied().map(Some)).collect();
+    routes.sort_unstable();
+    routes.dedup();
+
+    if self.routes.set(routes.into_iter().map(|id| (id, RouteMetrics::default())).collect()).is_err() {
+      panic!("route metrics already initialized");
+    }
+  }
+
+  /// Returns the metrics of the route, `None` if the route wasn't registered using `init_routes`.
+  pub fn route(&self, route_id: Option<&str>) -> Option<&RouteMetrics> {
+    let routes = self.routes();
+    routes.binary_search_by(|(id, _)| id.cmp(&route_id)).ok().map(|i| &routes[i].1)
+  }
+
+  /// Returns all the route metrics, sorted by the route ID.
+  pub fn routes(&self) -> &[RouteEntry] {
+    self.routes.get().map_or(&[], |routes| routes)
+  }
+
+  pub fn counter(&self, name: &'static str, help: &'static str) -> &'static Counter {
+    if !is_valid_metric_name(name) {
+      panic!("invalid metric name: '{name}'");
+    }
+
+    if name.starts_with("ruxy_") {
+      panic!("metric name '{name}' is reserved, names starting with 'ruxy_' are used by Ruxy itself");
+    }
+
+    let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(registered) = counters.iter().find(|c| c.name == name) {
+      return registered.counter;
+    }
+
+    let counter = Box::leak(Box::default());
+    counters.push(UserCounter { name, help, counter });
+    counter
+  }
+
+  /// Returns all the counters registered by the user code, sorted by their name.
+  pub fn counters(&self) -> Vec<UserCounter> {
+    let counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut counters: Vec<_> = counters.iter().copied().collect();
+    counters.sort_unstable_by_key(|c| c.name);
+    counters
+  }
+
+  pub fn register_queue_depth(&self, depth: Box<dyn Fn() -> usize + Send + Sync>) {
+    if self.queue_depth.set(depth).is_err() {
+      panic!("queue depth already registered");
+    }
+  }
+
+  /// Returns the number of connections waiting for a worker, `None` if there's no queue.
+  pub fn queue_depth(&self) -> Option<usize> {
+    self.queue_depth.get().map(|depth| depth())
+  }
+
+  pub fn init_workers(&self, count: usize) {
+    if self.workers.set((0..count).map(|_| WorkerMetrics::default()).collect()).is_err() {
+      panic!("worker metrics already initialized");
+    }
+  }
+
+  pub fn workers(&self) -> &[WorkerMetrics] {
+    self.workers.get().map_or(&[], |workers| workers)
+  }
+}
+
+impl RouteMetrics {
+  pub fn record(&self, status: u16, duration: Duration) {
+    let class = (status / 100).clamp(1, 5) as usize;
+
+    self.responses[class - 1].inc();
+    self.latency.observe(duration);
+  }
+}
+
+/// Whether the name matches `[a-zA-Z_:][a-zA-Z0-9_:]*`.
+fn is_valid_metric_name(name: &str) -> bool {
+  let mut chars = name.chars();
+
+  let valid_first = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == ':');
+  valid_first && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+}
@@ -0,0 +1,108 @@
+use std::fmt::Write;
+
+use super::histogram::LATENCY_BUCKETS;
+use super::registry::{REGISTRY, Registry};
+
+const STATUS_CLASSES: [&str; 5] = ["1xx", "2xx", "3xx", "4xx", "5xx"];
+
+/// Renders all the collected metrics in the Prometheus text format.
+pub fn render() -> String {
+  render_registry(&REGISTRY)
+}
+
+pub(super) fn render_registry(registry: &Registry) -> String {
+  let mut out = String::new();
+
+  let routes = registry.routes();
+
+  header(&mut out, "ruxy_http_requests_total", "counter", "Number of served HTTP requests.");
+
+  for (route_id, metrics) in routes {
+    let route = route_label(*route_id);
+
+    for (class, counter) in STATUS_CLASSES.iter().zip(&metrics.responses) {
+      if counter.get() > 0 {
+        let _ = writeln!(out, "ruxy_http_requests_total{{{route}status=\"{class}\"}} {}", counter.get());
+      }
+    }
+  }
+
+  header(&mut out, "ruxy_http_request_duration_seconds", "histogram", "Time it took to produce the response.");
+
+  for (route_id, metrics) in routes {
+    let route = route_label(*route_id);
+    let counts = metrics.latency.cumulative_counts();
+    let total = counts[LATENCY_BUCKETS.len()];
+
+    // All routes are registered upfront, only those that served a request are reported
+    if total == 0 {
+      continue;
+    }
+
+    for (bound, count) in LATENCY_BUCKETS.iter().zip(counts) {
+      let _ = writeln!(out, "ruxy_http_request_duration_seconds_bucket{{{route}le=\"{bound}\"}} {count}");
+    }
+
+    let _ = writeln!(out, "ruxy_http_request_duration_seconds_bucket{{{route}le=\"+Inf\"}} {total}");
+    let route = route.trim_end_matches(',');
+    let _ = writeln!(out, "ruxy_http_request_duration_seconds_sum{{{route}}} {}", metrics.latency.sum());
+    let _ = writeln!(out, "ruxy_http_request_duration_seconds_count{{{route}}} {total}");
+  }
+
+  header(&mut out, "ruxy_connections_accepted_total", "counter", "Number of accepted connections.");
+  let _ = writeln!(out, "ruxy_connections_accepted_total {}", registry.connections_accepted.get());
+
+  header(&mut out, "ruxy_connection_accept_errors_total", "counter", "Number of failures to accept a connection.");
+  let _ = writeln!(out, "ruxy_connection_accept_errors_total {}", registry.accept_errors.get());
+
+  header(&mut out, "ruxy_load_shed_total", "counter", "Number of connections and requests rejected due to overload.");
+  let _ = writeln!(out, "ruxy_load_shed_total{{kind=\"connection\"}} {}", registry.shed_connections.get());
+  let _ = writeln!(out, "ruxy_load_shed_total{{kind=\"request\"}} {}", registry.shed_requests.get());
+
+  if let Some(depth) = registry.queue_depth() {
+    header(&mut out, "ruxy_connection_queue_depth", "gauge", "Number of connections waiting for a worker thread.");
+    let _ = writeln!(out, "ruxy_connection_queue_depth {depth}");
+  }
+
+  let workers = registry.workers();
+
+  header(&mut out, "ruxy_worker_active_tasks", "gauge", "Number of connections being processed by the worker.");
+
+  for (index, worker) in workers.iter().enumerate() {
+    let _ = writeln!(out, "ruxy_worker_active_tasks{{worker=\"{index}\"}} {}", worker.active_tasks());
+  }
+
+  header(&mut out, "ruxy_worker_active_requests", "gauge", "Number of requests being processed by the worker.");
+
+  for (index, worker) in workers.iter().enumerate() {
+    let _ = writeln!(out, "ruxy_worker_active_requests{{worker=\"{index}\"}} {}", worker.active_requests());
+  }
+
+  header(&mut out, "ruxy_worker_tasks_total", "counter", "Number of connections processed by the worker.");
+
+  for (index, worker) in workers.iter().enumerate() {
+    let _ = writeln!(out, "ruxy_worker_tasks_total{{worker=\"{index}\"}} {}", worker.tasks_total());
+  }
+
+  for counter in registry.counters() {
+    header(&mut out, counter.name, "counter", counter.help);
+    let _ = writeln!(out, "{} {}", counter.name, counter.counter.get());
+  }
+
+  out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+  let _ = writeln!(out, "# HELP {name} {}", help.replace('\\', "\\\\").replace('\n', "\\n"));
+  let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Returns the `route` label followed by a comma, or an empty string for requests not matching any route.
+fn route_label(route_id: Option<&str>) -> String {
+  let Some(route_id) = route_id else {
+    return String::new();
+  };
+
+  let escaped = route_id.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+  format!("route=\"{escaped}\",")
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use super::WorkerMetrics;
+use super::registry::Registry;
+use super::render::render_registry;
+
+#[test]
+fn test_render_metrics() {
+  let registry = Registry::default();
+  registry.init_routes(&["blog/[slug]", "about"]);
+
+  registry.route(Some("blog/[slug]")).unwrap().record(200, Duration::from_millis(3));
+  registry.route(Some("blog/[slug]")).unwrap().record(404, Duration::from_millis(30));
+  registry.route(None).unwrap().record(308, Duration::from_secs(20));
+
+  // Unknown routes aren't recorded
+  assert!(registry.route(Some("admin")).is_none());
+  registry.accept_errors.inc();
+  registry.shed_requests.add(2);
+  registry.init_workers(2);
+  registry.counter("app_signups_total", "Number of signups.").add(5);
+
+  // Registering the same counter again returns the existing one
+  registry.counter("app_signups_total", "Number of signups.").inc();
+
+  let rendered = render_registry(&registry);
+
+  let expected_lines = [
+    "# TYPE ruxy_http_requests_total counter",
+    "ruxy_http_requests_total{status=\"3xx\"} 1",
+    "ruxy_http_requests_total{route=\"blog/[slug]\",status=\"2xx\"} 1",
+    "ruxy_http_requests_total{route=\"blog/[slug]\",status=\"4xx\"} 1",
+    "ruxy_http_request_duration_seconds_bucket{route=\"blog/[slug]\",le=\"0.0025\"} 0",
+    "ruxy_http_request_duration_seconds_bucket{route=\"blog/[slug]\",le=\"0.005\"} 1",
+    "ruxy_http_request_duration_seconds_bucket{route=\"blog/[slug]\",le=\"0.05\"} 2",
+    "ruxy_http_request_duration_seconds_bucket{le=\"10\"} 0",
+    "ruxy_http_request_duration_seconds_bucket{le=\"+Inf\"} 1",
+    "ruxy_http_request_duration_seconds_sum{route=\"blog/[slug]\"} 0.033",
+    "ruxy_http_request_duration_seconds_count{route=\"blog/[slug]\"} 2",
+    "ruxy_connection_accept_errors_total 1",
+    "ruxy_load_shed_total{kind=\"connection\"} 0",
+    "ruxy_load_shed_total{kind=\"request\"} 2",
+    "ruxy_worker_active_requests{worker=\"0\"} 0",
+    "ruxy_worker_active_tasks{worker=\"1\"} 0",
+    "# TYPE app_signups_total counter",
+    "app_signups_total 6",
+  ];
+
+  for line in expected_lines {
+    assert!(rendered.lines().any(|l| l == line), "missing line: {line}\n{rendered}");
+  }
+
+  // Routes that didn't serve any request aren't reported
+  assert!(!rendered.contains("route=\"about\""));
+  assert!(!rendered.contains("ruxy_connection_queue_depth"));
+  assert!(!rendered.contains("status=\"5xx\""));
+}
+
+#[test]
+#[should_panic(expected = "reserved")]
+fn test_reserved_counter_name() {
+  Registry::default().counter("ruxy_requests", "");
+}
+
+#[test]
+#[should_panic(expected = "invalid metric name")]
+fn test_invalid_counter_name() {
+  Registry::default().counter("app-signups", "");
+}
+
+#[test]
+fn test_worker_request_limit() {
+  let worker: &'static WorkerMetrics = Box::leak(Box::default());
+
+  let first = worker.try_start_request(Some(2));
+  let second = worker.try_start_request(Some(2));
+
+  assert!(first.is_some() && second.is_some());
+  assert!(worker.try_start_request(Some(2)).is_none());
+  assert_eq!(worker.active_requests(), 2);
+
+  // Finished requests make room for new ones
+  drop(first);
+  assert!(worker.try_start_request(Some(2)).is_some());
+  assert_eq!(worker.active_requests(), 1);
+
+  assert!(worker.try_start_request(None).is_some());
+}
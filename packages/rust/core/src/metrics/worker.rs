@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::Counter;
+
+/// Statistics of a single worker thread.
+#[derive(Debug, Default)]
+pub struct WorkerMetrics {
+  /// The number of connection tasks currently running on the worker.
+  active_tasks: AtomicU64,
+  /// The number of connection tasks ever spawned on the worker.
+  tasks_total: Counter,
+  /// The number of requests currently processed by the worker, across all its connections.
+  active_requests: AtomicU64,
+}
+
+impl WorkerMetrics {
+  /// Wraps the task future, so that it is counted while running on this worker.
+  pub fn track<F: Future>(&'static self, task: F) -> impl Future<Output = F::Output> {
+    self.tasks_total.inc();
+    self.active_tasks.fetch_add(1, Ordering::Relaxed);
+
+    // The guard is moved into the future, so the task is uncounted even if it's cancelled.
+    let guard = ActiveTaskGuard(self);
+
+    async move {
+      let _guard = guard;
+      task.await
+    }
+  }
+
+  /// Counts a request as processed by this worker until the returned guard is dropped.
+  /// Returns `None` without counting it if the worker already processes `limit` requests.
+  pub fn try_start_request(&'static self, limit: Option<usize>) -> Option<ActiveRequestGuard> {
+    let active = self.active_requests.fetch_add(1, Ordering::Relaxed);
+
+    if limit.is_some_and(|limit| active >= limit as u64) {
+      self.active_requests.fetch_sub(1, Ordering::Relaxed);
+      return None;
+    }
+
+    Some(ActiveRequestGuard(self))
+  }
+
+  pub fn active_tasks(&self) -> u64 {
+    self.active_tasks.load(Ordering::Relaxed)
+  }
+
+  pub fn tasks_total(&self) -> u64 {
+    self.tasks_total.get()
+  }
+
+  pub fn active_requests(&self) -> u64 {
+    self.active_requests.load(Ordering::Relaxed)
+  }
+}
+
+struct ActiveTaskGuard(&'static WorkerMetrics);
+
+impl Drop for ActiveTaskGuard {
+  fn drop(&mut self) {
+    self.0.active_tasks.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+/// Keeps a request counted as processed by the worker, see `WorkerMetrics::try_start_request`.
+pub struct ActiveRequestGuard(&'static WorkerMetrics);
+
+impl Drop for ActiveRequestGuard {
+  fn drop(&mut self) {
+    self.0.active_requests.fetch_sub(1, Ordering::Relaxed);
+  }
+}
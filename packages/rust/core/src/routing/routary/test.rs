@@ -1,9 +1,12 @@
 use std::path::Path;
 
+use crate::config::{AppConfig, register_app_config};
 use crate::routing::routary::Routary;
 
 #[test]
 fn test_build_routes() {
+  register_app_config(AppConfig::default());
+
   let routes_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sand/app/routes");
   let _routary = Routary::parse(&routes_dir);
   
@@ -64,6 +64,26 @@ pub struct RouteSegment {
   /// Option containing Layout Target config for this segment,
   /// `None` if this segment does not have a Layout Target.
   pub layout_target: Option<RenderTarget>,
+  /// Option containing Middleware Target config for this segment,
+  /// `None` if this segment does not have a Middleware Target.
+  ///
+  /// Middleware wraps the Route Targets of this segment and all its descendants.
+  pub middleware_target: Option<HandlerTarget>,
+  /// Option containing CORS Target config for this segment,
+  /// `None` if this segment does not have a CORS Target.
+  ///
+  /// The CORS policy overrides the global one for this segment and all its descendants.
+  pub cors_target: Option<HandlerTarget>,
+  /// Option containing Rate Limit Target config for this segment,
+  /// `None` if this segment does not have a Rate Limit Target.
+  ///
+  /// The rate limit applies to this segment and all its descendants, sharing a single budget per client.
+  pub rate_limit_target: Option<HandlerTarget>,
+  /// Option containing Timeout Target config for this segment,
+  /// `None` if this segment does not have a Timeout Target.
+  ///
+  /// The timeouts override the global ones for this segment and all its descendants.
+  pub timeout_target: Option<HandlerTarget>,
   /// Whether this segment is a leaf segment (i.e. it does not have any nested segments)
   pub is_leaf: bool,
   /// Whether this segment is a root segment (i.e. it is the root of the "routes" directory)
@@ -127,6 +127,10 @@ pub fn build_segment_map(
     not_found_target: file_registry.take_not_found_target(),
     error_target: file_registry.take_error_target(),
     layout_target: file_registry.take_layout_target(),
+    middleware_target: file_registry.take_middleware_target(),
+    cors_target: file_registry.take_cors_target(),
+    rate_limit_target: file_registry.take_rate_limit_target(),
+    timeout_target: file_registry.take_timeout_target(),
     is_root,
     is_leaf,
     effect,
@@ -43,6 +43,18 @@ pub struct SegmentFileRegistry {
   layout_rs: bool,
   // layout.<js|jsx|ts|tsx|md|mdx>
   layout_client: Option<SegmentClientFile>,
+
+  // middleware.rs
+  middleware_rs: bool,
+
+  // cors.rs
+  cors_rs: bool,
+
+  // rate_limit.rs
+  rate_limit_rs: bool,
+
+  // timeout.rs
+  timeout_rs: bool,
 }
 
 impl SegmentFileRegistry {
@@ -92,6 +104,14 @@ impl SegmentFileRegistry {
       constants::SEG_FILE_LAYOUT_TSX => self.register_layout_client(file_name, RouteSegmentClientEntryExt::Tsx),
       constants::SEG_FILE_LAYOUT_MD => self.register_layout_client(file_name, RouteSegmentClientEntryExt::Md),
       constants::SEG_FILE_LAYOUT_MDX => self.register_layout_client(file_name, RouteSegmentClientEntryExt::Mdx),
+
+      constants::SEG_FILE_MIDDLEWARE_RS => self.register_middleware_rs(),
+
+      constants::SEG_FILE_CORS_RS => self.register_cors_rs(),
+
+      constants::SEG_FILE_RATE_LIMIT_RS => self.register_rate_limit_rs(),
+
+      constants::SEG_FILE_TIMEOUT_RS => self.register_timeout_rs(),
       _ => Ok(false),
     }
   }
@@ -181,6 +201,38 @@ impl SegmentFileRegistry {
     None
   }
 
+  pub fn take_middleware_target(&mut self) -> Option<HandlerTarget> {
+    if !self.middleware_rs {
+      return None;
+    }
+
+    Some(HandlerTarget { rs_module: self.get_rs_module("middleware", constants::SEG_FILE_MIDDLEWARE_RS) })
+  }
+
+  pub fn take_cors_target(&mut self) -> Option<HandlerTarget> {
+    if !self.cors_rs {
+      return None;
+    }
+
+    Some(HandlerTarget { rs_module: self.get_rs_module("cors", constants::SEG_FILE_CORS_RS) })
+  }
+
+  pub fn take_rate_limit_target(&mut self) -> Option<HandlerTarget> {
+    if !self.rate_limit_rs {
+      return None;
+    }
+
+    Some(HandlerTarget { rs_module: self.get_rs_module("rate_limit", constants::SEG_FILE_RATE_LIMIT_RS) })
+  }
+
+  pub fn take_timeout_target(&mut self) -> Option<HandlerTarget> {
+    if !self.timeout_rs {
+      return None;
+    }
+
+    Some(HandlerTarget { rs_module: self.get_rs_module("timeout", constants::SEG_FILE_TIMEOUT_RS) })
+  }
+
   fn get_rs_module(&self, name: &str, file: &str) -> RouteSegmentRsModule {
     let module_prefix = format!("rsgmod_{}_", self.segment_hex);
     let path = &self.rs_module_rel_path.join(file);
@@ -313,6 +365,26 @@ impl SegmentFileRegistry {
     Ok(true)
   }
 
+  fn register_middleware_rs(&mut self) -> Result<bool, String> {
+    self.middleware_rs = true;
+    Ok(true)
+  }
+
+  fn register_cors_rs(&mut self) -> Result<bool, String> {
+    self.cors_rs = true;
+    Ok(true)
+  }
+
+  fn register_rate_limit_rs(&mut self) -> Result<bool, String> {
+    self.rate_limit_rs = true;
+    Ok(true)
+  }
+
+  fn register_timeout_rs(&mut self) -> Result<bool, String> {
+    self.timeout_rs = true;
+    Ok(true)
+  }
+
   fn get_file_conflict_error(&self, file1: &str, file2: &str) -> String {
     let prefix = self.get_segment_path_prefix_for_error();
 
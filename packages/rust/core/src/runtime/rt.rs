@@ -1,45 +1,40 @@
+mod dispatcher;
 mod multi_threaded;
 mod single_threaded;
 
-use tokio::net::TcpStream;
-
+use crate::config::get_app_config;
 use crate::runtime::threads::get_thread_count;
+use crate::server::listener::Connection;
 
-const QUEUE_SIZE: usize = 8;
+pub use dispatcher::Dispatcher;
 
-pub fn create<Listener, RunnerFut, Task>(listener: Listener)
+/// Starts the thread-per-core runtime.
+///
+/// The `acceptor` future runs on the main thread. It's responsible for accepting incoming
+/// connections and handing them over to the provided `Dispatcher`, which load-balances them
+/// across worker threads. Each connection is then fully processed by the `handler` on its
+/// assigned worker thread.
+///
+/// When running on a single thread, the connections are processed on the main thread,
+/// alongside the acceptor.
+///
+/// Up to `queue_size` accepted connections wait for a worker, connections accepted while the queue
+/// is full are shed. Each worker processes at most `LoadSheddingConfig::max_connections_per_worker`.
+pub fn create<Acceptor, AcceptorFut, Handler, HandlerFut>(queue_size: usize, acceptor: Acceptor, handler: Handler)
 where
-  Listener: FnOnce() -> RunnerFut,
-  RunnerFut: Future<Output = ()> + Send + 'static,
-  Task: Future<Output = ()> + Send + 'static,
+  Acceptor: FnOnce(Dispatcher<Handler>) -> AcceptorFut,
+  AcceptorFut: Future<Output = ()>,
+  Handler: Fn(Connection) -> HandlerFut + Clone + Send + 'static,
+  HandlerFut: Future<Output = ()> + Send + 'static,
 {
+  let max_connections = get_app_config().load_shedding.max_connections_per_worker;
   let thread_count = get_thread_count();
 
-  let main_rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
-
-  let (tx, rx) = flume::bounded::<TcpStream>(QUEUE_SIZE);
-  
   if thread_count > 1 {
     // We keep the main thread only for load-balancing of incoming sockets and nothing
     // else, so the worker threads count we want to spawn here is (thread_count - 1).
-    for _ in 0..thread_count - 1 {
-      let rx = rx.clone();
-      
-      std::thread::spawn(move || {
-        let worker_rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
-        
-        worker_rt.block_on(async {
-          while let Ok(stream) = rx.recv_async().await {
-            
-          }
-        });
-      });
-    }
+    multi_threaded::run(thread_count - 1, queue_size, max_connections, acceptor, handler);
   } else {
-    // We're running on a single thread, so we don't need to utilize channels
-    // to work-steal the incoming sockets. We'll just spawn a local task.
-    main_rt.block_on(async {
-      listener();
-    });
+    single_threaded::run(max_connections, acceptor, handler);
   }
 }
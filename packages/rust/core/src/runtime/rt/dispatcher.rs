@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use flume::TrySendError;
+use tokio::sync::Semaphore;
+
+use crate::log;
+use crate::metrics;
+use crate::server::listener::{Connection, DetachedConnection};
+use crate::server::load_shedding;
+
+/// Hands the accepted connections over to the threads processing them.
+#[derive(Clone)]
+pub struct Dispatcher<Handler> {
+  target: DispatchTarget<Handler>,
+}
+
+#[derive(Clone)]
+enum DispatchTarget<Handler> {
+  /// Connections are sent to the worker threads through a channel.
+  Workers(flume::Sender<DetachedConnection>),
+  /// Connections are processed on the current thread, at most as many at once as there are `slots`.
+  Local { handler: Handler, slots: Option<Arc<Semaphore>> },
+}
+
+impl<Handler, HandlerFut> Dispatcher<Handler>
+where
+  Handler: Fn(Connection) -> HandlerFut,
+  HandlerFut: Future<Output = ()> + Send + 'static,
+{
+  pub(super) fn workers(sender: flume::Sender<DetachedConnection>) -> Self {
+    Self { target: DispatchTarget::Workers(sender) }
+  }
+
+  pub(super) fn local(handler: Handler, max_connections: Option<usize>) -> Self {
+    let slots = max_connections.map(|max| Arc::new(Semaphore::new(max)));
+    Self { target: DispatchTarget::Local { handler, slots } }
+  }
+
+  /// Dispatches the connection for processing.
+  ///
+  /// Connections that can't be processed right away, because the queue of the worker threads is full
+  /// or the current thread is at its limit, are shed, see `load_shedding::shed_connection`.
+  pub async fn dispatch(&self, connection: Connection) {
+    match &self.target {
+      DispatchTarget::Workers(sender) => {
+        let detached = match connection.detach() {
+          Ok(detached) => detached,
+          Err(e) => {
+            log::error("connection_handover_failed", "failed to hand over connection", &[("error", (&e).into())]);
+            return;
+          }
+        };
+
+        match sender.try_send(detached) {
+          Ok(()) => {}
+          Err(TrySendError::Full(detached)) => match detached.attach() {
+            Ok(connection) => {
+              tokio::task::spawn(load_shedding::shed_connection(connection));
+            }
+            Err(e) => {
+              log::error("connection_takeover_failed", "failed to take over connection", &[("error", (&e).into())]);
+            }
+          },
+          // All the workers are gone, there's nothing to do with the connection then.
+          Err(TrySendError::Disconnected(_)) => {}
+        }
+      }
+      DispatchTarget::Local { handler, slots } => {
+        let slot = match slots.as_ref().map(|slots| slots.clone().try_acquire_owned()) {
+          Some(Ok(slot)) => Some(slot),
+          Some(Err(_)) => {
+            tokio::task::spawn(load_shedding::shed_connection(connection));
+            return;
+          }
+          None => None,
+        };
+
+        let task = handler(connection);
+
+        tokio::task::spawn(metrics::worker(0).track(async move {
+          let _slot = slot;
+          task.await
+        }));
+      }
+    }
+  }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+
+use crate::log;
+use crate::metrics;
+use crate::server::listener::{Connection, DetachedConnection};
+
+use super::Dispatcher;
+
+pub(super) fn run<Acceptor, AcceptorFut, Handler, HandlerFut>(
+  worker_count: usize,
+  queue_size: usize,
+  max_connections: Option<usize>,
+  acceptor: Acceptor,
+  handler: Handler,
+) where
+  Acceptor: FnOnce(Dispatcher<Handler>) -> AcceptorFut,
+  AcceptorFut: Future<Output = ()>,
+  Handler: Fn(Connection) -> HandlerFut + Clone + Send + 'static,
+  HandlerFut: Future<Output = ()> + Send + 'static,
+{
+  let (tx, rx) = flume::bounded::<DetachedConnection>(queue_size);
+
+  let weak_tx = tx.downgrade();
+  metrics::register_queue_depth(move || weak_tx.upgrade().map_or(0, |tx| tx.len()));
+  metrics::init_workers(worker_count);
+
+  for index in 0..worker_count {
+    let rx = rx.clone();
+    let handler = handler.clone();
+
+    let spawned = std::thread::Builder::new().name(format!("ruxy-worker-{index}")).spawn(move || {
+      let worker_rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+      metrics::set_current_worker(index);
+      let metrics = metrics::worker(index);
+
+      // A worker at its limit stops taking connections, leaving them in the queue for the other
+      // workers. Once the queue is full too, the acceptor sheds the new connections.
+      let slots = max_connections.map(|max| Arc::new(Semaphore::new(max)));
+
+      worker_rt.block_on(async {
+        loop {
+          let slot = match &slots {
+            Some(slots) => Some(slots.clone().acquire_owned().await.expect("the semaphore is never closed")),
+            None => None,
+          };
+
+          let Ok(detached) = rx.recv_async().await else {
+            break;
+          };
+
+          match detached.attach() {
+            Ok(connection) => {
+              let task = handler(connection);
+
+              tokio::task::spawn(metrics.track(async move {
+                let _slot = slot;
+                task.await
+              }));
+            }
+            Err(e) => {
+              log::error("connection_takeover_failed", "failed to take over connection", &[("error", (&e).into())]);
+            }
+          }
+        }
+      });
+    });
+
+    if let Err(e) = spawned {
+      panic!("failed to spawn worker thread: {e}");
+    }
+  }
+
+  let main_rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+  main_rt.block_on(acceptor(Dispatcher::workers(tx)));
+}
@@ -0,0 +1,24 @@
+use crate::metrics;
+use crate::server::listener::Connection;
+
+use super::Dispatcher;
+
+pub(super) fn run<Acceptor, AcceptorFut, Handler, HandlerFut>(
+  max_connections: Option<usize>,
+  acceptor: Acceptor,
+  handler: Handler,
+) where
+  Acceptor: FnOnce(Dispatcher<Handler>) -> AcceptorFut,
+  AcceptorFut: Future<Output = ()>,
+  Handler: Fn(Connection) -> HandlerFut + Clone + Send + 'static,
+  HandlerFut: Future<Output = ()> + Send + 'static,
+{
+  metrics::init_workers(1);
+  metrics::set_current_worker(0);
+
+  let main_rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+  // We're running on a single thread, so we don't need to utilize channels
+  // to load-balance the incoming sockets. We'll just spawn local tasks.
+  main_rt.block_on(acceptor(Dispatcher::local(handler, max_connections)));
+}
@@ -1,7 +1,24 @@
+pub mod access_log;
+pub mod compression;
+pub mod conditional;
+pub mod cors;
+pub mod handler;
+pub mod health;
+pub mod load_shedding;
 pub mod page;
+pub mod proxy;
+pub mod public;
+pub mod rate_limit;
 pub mod request;
 pub mod response;
+pub mod security_headers;
+pub mod listener;
+pub mod middleware;
+pub mod shutdown;
+pub mod sse;
+pub mod timeout;
 pub mod tserver;
+pub mod websocket;
 
 mod routing;
 mod redirect;
@@ -0,0 +1,69 @@
+mod format;
+mod record;
+mod writer;
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::config::AccessLogConfig;
+use crate::log;
+
+pub use record::{AccessLogRecord, PendingAccessLogRecord};
+
+/// How many records can wait for the writer thread before new records start being dropped.
+const QUEUE_SIZE: usize = 8192;
+
+static ACCESS_LOG: OnceLock<AccessLog> = OnceLock::new();
+
+/// A handle to the access log writer.
+///
+/// Records are formatted and written on a dedicated thread, so that recording a request never
+/// blocks the executor processing it. If the writer can't keep up, new records are dropped.
+pub struct AccessLog {
+  sender: flume::Sender<AccessLogRecord>,
+  dropped: AtomicU64,
+}
+
+/// Starts the access log writer if the access log is enabled.
+pub fn init(config: &AccessLogConfig) {
+  if !config.enabled {
+    return;
+  }
+
+  let (sender, receiver) = flume::bounded(QUEUE_SIZE);
+
+  if let Err(e) = writer::spawn(config.output, config.format, receiver) {
+    log::error("access_log_init_failed", "failed to open the access log", &[("error", (&e).into())]);
+    std::process::exit(1);
+  }
+
+  if ACCESS_LOG.set(AccessLog { sender, dropped: AtomicU64::new(0) }).is_err() {
+    log::warn("access_log_already_initialized", "access log already initialized", &[]);
+  }
+}
+
+/// Returns the access log handle, or `None` if the access log is disabled.
+#[inline]
+pub fn get() -> Option<&'static AccessLog> {
+  ACCESS_LOG.get()
+}
+
+impl AccessLog {
+  /// Queues the record to be written. This never blocks.
+  pub fn record(&self, record: AccessLogRecord) {
+    if self.sender.try_send(record).is_ok() {
+      return;
+    }
+
+    let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+
+    // Reporting on powers of two only, so that an overloaded writer doesn't flood the logs too.
+    if dropped.is_power_of_two() {
+      log::warn(
+        "access_log_records_dropped",
+        "access log writer can't keep up, records are being dropped",
+        &[("dropped_total", dropped.into())],
+      );
+    }
+  }
+}
@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use crate::config::AccessLogFormat;
+use crate::util::json::write_json_string;
+use crate::util::time::UtcDateTime;
+
+use super::AccessLogRecord;
+
+/// Formats the record as a single line (including the line break) in the provided format.
+pub fn format_record(record: &AccessLogRecord, format: AccessLogFormat) -> String {
+  match format {
+    AccessLogFormat::Common => format_common(record, false),
+    AccessLogFormat::Combined => format_common(record, true),
+    AccessLogFormat::Json => format_json(record),
+  }
+}
+
+/// `host ident authuser [date] "request" status bytes`, optionally followed by `"referer" "user-agent"`.
+fn format_common(record: &AccessLogRecord, combined: bool) -> String {
+  let mut line = String::with_capacity(128);
+
+  match record.client_addr {
+    Some(addr) => line.push_str(&addr.to_string()),
+    None => line.push('-'),
+  }
+
+  line.push_str(" - - [");
+  line.push_str(&UtcDateTime::from_system_time(record.time).to_common_log_format());
+  line.push_str("] ");
+
+  let request_line = format!("{} {} {:?}", record.method, record.target, record.version);
+  write_quoted(&mut line, Some(&request_line));
+
+  // Writing to String can't fail
+  let _ = write!(line, " {}", record.status);
+
+  match record.bytes_sent {
+    Some(bytes) => _ = write!(line, " {bytes}"),
+    None => line.push_str(" -"),
+  }
+
+  if combined {
+    line.push(' ');
+    write_quoted(&mut line, record.referer.as_deref());
+    line.push(' ');
+    write_quoted(&mut line, record.user_agent.as_deref());
+  }
+
+  line.push('\n');
+  line
+}
+
+fn format_json(record: &AccessLogRecord) -> String {
+  let mut line = String::with_capacity(256);
+
+  line.push_str("{\"ts\":\"");
+  line.push_str(&UtcDateTime::from_system_time(record.time).to_rfc3339());
+  line.push_str("\",\"request_id\":");
+  write_json_string(&mut line, &record.request_id);
+  line.push_str(",\"client_addr\":");
+  write_json_optional(&mut line, record.client_addr.map(|addr| addr.to_string()).as_deref());
+  line.push_str(",\"method\":");
+  write_json_string(&mut line, record.method.as_str());
+  line.push_str(",\"path\":");
+  write_json_string(&mut line, &record.target);
+  line.push_str(",\"protocol\":");
+  write_json_string(&mut line, &format!("{:?}", record.version));
+  line.push_str(",\"route_id\":");
+  write_json_optional(&mut line, record.route_id);
+
+  // Writing to String can't fail
+  let _ = write!(line, ",\"status\":{}", record.status);
+
+  match record.bytes_sent {
+    Some(bytes) => _ = write!(line, ",\"bytes_sent\":{bytes}"),
+    None => line.push_str(",\"bytes_sent\":null"),
+  }
+
+  let _ = write!(line, ",\"duration_ms\":{:.3}", record.duration.as_secs_f64() * 1000.0);
+
+  line.push_str(",\"referer\":");
+  write_json_optional(&mut line, record.referer.as_deref());
+  line.push_str(",\"user_agent\":");
+  write_json_optional(&mut line, record.user_agent.as_deref());
+
+  line.push_str("}\n");
+  line
+}
+
+/// Writes the value double-quoted, escaping quotes, backslashes and non-printable characters.
+/// A missing value is written as `"-"`.
+fn write_quoted(line: &mut String, value: Option<&str>) {
+  let Some(value) = value else {
+    line.push_str("\"-\"");
+    return;
+  };
+
+  line.push('"');
+
+  for ch in value.chars() {
+    match ch {
+      '"' => line.push_str("\\\""),
+      '\\' => line.push_str("\\\\"),
+      // Writing to String can't fail
+      ch if ch.is_control() => _ = write!(line, "\\x{:02x}", ch as u32),
+      ch => line.push(ch),
+    }
+  }
+
+  line.push('"');
+}
+
+fn write_json_optional(line: &mut String, value: Option<&str>) {
+  match value {
+    Some(value) => write_json_string(line, value),
+    None => line.push_str("null"),
+  }
+}
+
+#[test]
+fn formats_records() {
+  use std::time::{Duration, UNIX_EPOCH};
+
+  use hyper::{Method, Version};
+
+  use crate::server::request::request_id::RequestId;
+
+  let record = AccessLogRecord {
+    time: UNIX_EPOCH + Duration::from_secs(1_748_781_045),
+    request_id: RequestId::from_header(b"req-1").unwrap(),
+    client_addr: Some([10, 0, 0, 1].into()),
+    method: Method::GET,
+    target: "/blog?page=2".to_string(),
+    version: Version::HTTP_11,
+    referer: None,
+    user_agent: Some("Mozilla/5.0 \"quoted\"".to_string()),
+    route_id: Some("blog"),
+    status: 200,
+    bytes_sent: Some(2326),
+    duration: Duration::from_micros(1500),
+  };
+
+  assert_eq!(
+    format_record(&record, AccessLogFormat::Common),
+    "10.0.0.1 - - [01/Jun/2025:12:30:45 +0000] \"GET /blog?page=2 HTTP/1.1\" 200 2326\n"
+  );
+
+  assert_eq!(
+    format_record(&record, AccessLogFormat::Combined),
+    "10.0.0.1 - - [01/Jun/2025:12:30:45 +0000] \"GET /blog?page=2 HTTP/1.1\" 200 2326 \"-\" \"Mozilla/5.0 \\\"quoted\\\"\"\n"
+  );
+
+  assert_eq!(
+    format_record(&record, AccessLogFormat::Json),
+    "{\"ts\":\"2025-06-01T12:30:45.000Z\",\"request_id\":\"req-1\",\"client_addr\":\"10.0.0.1\",\"method\":\"GET\",\"path\":\"/blog?page=2\",\
+    \"protocol\":\"HTTP/1.1\",\"route_id\":\"blog\",\"status\":200,\"bytes_sent\":2326,\"duration_ms\":1.500,\
+    \"referer\":null,\"user_agent\":\"Mozilla/5.0 \\\"quoted\\\"\"}\n"
+  );
+}
@@ -0,0 +1,91 @@
+use std::net::IpAddr;
+use std::time::{Duration, Instant, SystemTime};
+
+use hyper::body::Body;
+use hyper::{Method, Response, Version, http};
+
+use crate::server::request::peer::PeerInfo;
+use crate::server::request::request_id::RequestId;
+use crate::server::response::body::ResponseBody;
+use crate::server::tserver::HyperRequest;
+
+/// A single served request, as written to the access log.
+pub struct AccessLogRecord {
+  /// The time when the request was received.
+  pub time: SystemTime,
+  pub request_id: RequestId,
+  /// The address of the client, as resolved using the trusted proxies.
+  pub client_addr: Option<IpAddr>,
+  pub method: Method,
+  /// Path and query of the request.
+  pub target: String,
+  pub version: Version,
+  pub referer: Option<String>,
+  pub user_agent: Option<String>,
+  /// The ID of the matched route, `None` if no route was matched.
+  pub route_id: Option<&'static str>,
+  pub status: u16,
+  /// The size of the response body, `None` if it isn't known upfront.
+  pub bytes_sent: Option<u64>,
+  /// The time it took to produce the response.
+  pub duration: Duration,
+}
+
+/// The part of the access log record known before the request is handled.
+pub struct PendingAccessLogRecord {
+  start: Instant,
+  time: SystemTime,
+  request_id: RequestId,
+  client_addr: Option<IpAddr>,
+  method: Method,
+  target: String,
+  version: Version,
+  referer: Option<String>,
+  user_agent: Option<String>,
+}
+
+impl PendingAccessLogRecord {
+  pub fn new(req: &HyperRequest, peer: &PeerInfo, request_id: RequestId) -> Self {
+    let header = |name| req.headers().get(name).map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned());
+    let target = req.uri().path_and_query().map_or_else(|| req.uri().path(), |p| p.as_str());
+
+    Self {
+      start: Instant::now(),
+      time: SystemTime::now(),
+      request_id,
+      client_addr: peer.client_ip,
+      method: req.method().clone(),
+      target: target.to_string(),
+      version: req.version(),
+      referer: header(http::header::REFERER),
+      user_agent: header(http::header::USER_AGENT),
+    }
+  }
+
+  pub fn finish(
+    self,
+    route_id: Option<&'static str>,
+    response: &http::Result<Response<ResponseBody>>,
+  ) -> AccessLogRecord {
+    let (status, bytes_sent) = match response {
+      Ok(response) => (response.status().as_u16(), response.body().size_hint().exact()),
+      // Hyper closes the connection when the service fails, there's no response sent.
+      Err(_) => (500, None),
+    };
+
+    AccessLogRecord {
+      time: self.time,
+      request_id: self.request_id,
+      client_addr: self.client_addr,
+      method: self.method,
+      target: self.target,
+      version: self.version,
+      referer: self.referer,
+      user_agent: self.user_agent,
+      route_id,
+      status,
+      bytes_sent,
+      duration: self.start.elapsed(),
+    }
+  }
+}
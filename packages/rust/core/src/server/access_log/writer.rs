@@ -0,0 +1,42 @@
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+
+use crate::config::{AccessLogFormat, AccessLogOutput};
+use crate::log;
+
+use super::AccessLogRecord;
+use super::format::format_record;
+
+/// Spawns the thread writing the received records to the output.
+pub fn spawn(
+  output: AccessLogOutput,
+  format: AccessLogFormat,
+  receiver: flume::Receiver<AccessLogRecord>,
+) -> io::Result<()> {
+  let writer: Box<dyn Write + Send> = match output {
+    AccessLogOutput::Stdout => Box::new(io::stdout()),
+    AccessLogOutput::File(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+  };
+
+  let mut writer = BufWriter::new(writer);
+
+  std::thread::Builder::new().name("ruxy-access-log".to_string()).spawn(move || {
+    while let Ok(record) = receiver.recv() {
+      let mut result = writer.write_all(format_record(&record, format).as_bytes());
+
+      // Writing everything that's already queued before flushing, so that we don't
+      // flush on every line under load, but don't hold the lines in the buffer when idle.
+      while let Ok(record) = receiver.try_recv()
+        && result.is_ok()
+      {
+        result = writer.write_all(format_record(&record, format).as_bytes());
+      }
+
+      if let Err(e) = result.and_then(|_| writer.flush()) {
+        log::error("access_log_write_failed", "failed to write to the access log", &[("error", (&e).into())]);
+      }
+    }
+  })?;
+
+  Ok(())
+}
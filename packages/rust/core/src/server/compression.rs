@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod test;
+
+mod encoder;
+mod negotiate;
+
+use bytes::Bytes;
+use hyper::StatusCode;
+use hyper::body::Body;
+use hyper::http::{HeaderMap, HeaderValue, header};
+
+use crate::config::{CompressionConfig, CompressionRule};
+use crate::log;
+use crate::server::conditional::ETag;
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+
+pub use encoder::compress;
+pub use negotiate::negotiate;
+
+/// Compresses the response body with the best encoding accepted by the client, if the response is eligible.
+///
+/// Bodies backed by a `Precompressed` variant use the variant compressed at build time instead.
+pub fn apply(config: &CompressionConfig, accept_encoding: Option<&HeaderValue>, response: &mut Response) {
+  if !config.enabled || !is_eligible(response) {
+    return;
+  }
+
+  let Some(rule) = find_rule(config.rules, response.headers()) else {
+    return;
+  };
+
+  let Some(size) = response.body().size_hint().exact() else {
+    return;
+  };
+
+  if size < rule.min_size {
+    return;
+  }
+
+  // From now on, the representation depends on the `Accept-Encoding`, even if we end up not compressing.
+  append_vary(response.headers_mut());
+
+  let Some(encoding) = accept_encoding.and_then(|accept| negotiate(accept.as_bytes(), config.encodings)) else {
+    return;
+  };
+
+  let compressed = match response.body().precompressed().map(|p| p.variant(encoding)) {
+    Some(variant) if !variant.is_empty() => Bytes::from_static(variant),
+    _ => match compress(encoding, config.level, response.body().chunks()) {
+      Ok(compressed) => Bytes::from(compressed),
+      Err(e) => {
+        log::warn("compression_failed", "failed to compress the response", &[("error", (&e).into())]);
+        return;
+      }
+    },
+  };
+
+  // Not worth it, the client would have to decompress a larger body
+  if compressed.len() as u64 >= size {
+    return;
+  }
+
+  let mut body = ResponseBody::new();
+  body.push(compressed);
+  *response.body_mut() = body;
+
+  let headers = response.headers_mut();
+  headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+  headers.remove(header::CONTENT_LENGTH);
+
+  // The compressed bytes differ from the ones the strong entity tag was computed for
+  let etag = headers.get(header::ETAG).and_then(|etag| ETag::parse(etag.to_str().ok()?));
+
+  if let Some(etag) = etag.filter(|etag| !etag.is_weak()) {
+    headers.insert(header::ETAG, etag.into_weak().to_header_value());
+  }
+}
+
+/// Whether the response can be compressed, regardless of its content type and size.
+fn is_eligible(response: &Response) -> bool {
+  let status = response.status();
+
+  if status.is_informational() || status == StatusCode::NO_CONTENT || status == StatusCode::NOT_MODIFIED {
+    return false;
+  }
+
+  // Ranges are relative to the uncompressed representation
+  if status == StatusCode::PARTIAL_CONTENT {
+    return false;
+  }
+
+  let headers = response.headers();
+
+  if headers.contains_key(header::CONTENT_ENCODING) || headers.contains_key(header::CONTENT_RANGE) {
+    return false;
+  }
+
+  let no_transform = headers.get_all(header::CACHE_CONTROL).iter().any(|value| {
+    value.to_str().is_ok_and(|value| value.split(',').any(|d| d.trim().eq_ignore_ascii_case("no-transform")))
+  });
+
+  !no_transform
+}
+
+/// Finds the first rule matching the content type of the response.
+fn find_rule(rules: &[CompressionRule], headers: &HeaderMap) -> Option<CompressionRule> {
+  let content_type = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+  let media_type = content_type.split(';').next().unwrap_or_default().trim();
+
+  rules.iter().copied().find(|rule| match rule.content_type.ends_with('/') {
+    true => media_type.len() > rule.content_type.len() && starts_with_ignore_case(media_type, rule.content_type),
+    false => media_type.eq_ignore_ascii_case(rule.content_type),
+  })
+}
+
+/// Adds `Accept-Encoding` to the `Vary` header, keeping any values already present.
+fn append_vary(headers: &mut HeaderMap) {
+  let already_varies = headers.get_all(header::VARY).iter().any(|value| {
+    value
+      .to_str()
+      .is_ok_and(|value| value.split(',').any(|v| v.trim() == "*" || v.trim().eq_ignore_ascii_case("accept-encoding")))
+  });
+
+  if !already_varies {
+    headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+  }
+}
+
+fn starts_with_ignore_case(value: &str, prefix: &str) -> bool {
+  value.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
@@ -0,0 +1,60 @@
+use std::io::{self, Write};
+
+use bytes::Bytes;
+
+use crate::config::{CompressionLevel, ContentEncoding};
+
+/// Compresses the chunks into a single buffer using the provided encoding.
+pub fn compress<'a, I>(encoding: ContentEncoding, level: CompressionLevel, chunks: I) -> io::Result<Vec<u8>>
+where
+  I: IntoIterator<Item = &'a Bytes>,
+  I::IntoIter: Clone,
+{
+  let chunks = chunks.into_iter();
+  let capacity = chunks.clone().map(Bytes::len).sum::<usize>() / 2;
+  let output = Vec::with_capacity(capacity);
+  let level = encoder_level(encoding, level);
+
+  match encoding {
+    ContentEncoding::Gzip => {
+      let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::new(level));
+      write_chunks(&mut encoder, chunks)?;
+      encoder.finish()
+    }
+    ContentEncoding::Brotli => {
+      // 22 is the default window size of the reference implementation
+      let mut encoder = brotli::CompressorWriter::new(output, 4096, level, 22);
+      write_chunks(&mut encoder, chunks)?;
+      encoder.flush()?;
+      Ok(encoder.into_inner())
+    }
+    ContentEncoding::Zstd => {
+      let mut encoder = zstd::stream::Encoder::new(output, level as i32)?;
+      write_chunks(&mut encoder, chunks)?;
+      encoder.finish()
+    }
+  }
+}
+
+fn write_chunks<'a>(writer: &mut impl Write, chunks: impl Iterator<Item = &'a Bytes>) -> io::Result<()> {
+  for chunk in chunks {
+    writer.write_all(chunk)?;
+  }
+
+  Ok(())
+}
+
+/// Maps the configured level to the level of the underlying encoder.
+fn encoder_level(encoding: ContentEncoding, level: CompressionLevel) -> u32 {
+  match (encoding, level) {
+    (ContentEncoding::Gzip, CompressionLevel::Fastest) => 1,
+    (ContentEncoding::Gzip, CompressionLevel::Default) => 6,
+    (ContentEncoding::Gzip, CompressionLevel::Best) => 9,
+    (ContentEncoding::Brotli, CompressionLevel::Fastest) => 1,
+    (ContentEncoding::Brotli, CompressionLevel::Default) => 4,
+    (ContentEncoding::Brotli, CompressionLevel::Best) => 11,
+    (ContentEncoding::Zstd, CompressionLevel::Fastest) => 1,
+    (ContentEncoding::Zstd, CompressionLevel::Default) => 3,
+    (ContentEncoding::Zstd, CompressionLevel::Best) => 19,
+  }
+}
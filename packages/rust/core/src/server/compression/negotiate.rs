@@ -0,0 +1,72 @@
+use crate::config::ContentEncoding;
+
+/// Picks the encoding to use based on the value of the `Accept-Encoding` request header.
+///
+/// The encoding with the highest quality value wins, ties are broken by the order of `supported`.
+/// Encodings not listed by the client are only acceptable if the client lists `*`.
+/// Returns `None` if none of the supported encodings is acceptable.
+pub fn negotiate(accept_encoding: &[u8], supported: &[ContentEncoding]) -> Option<ContentEncoding> {
+  let accept_encoding = std::str::from_utf8(accept_encoding).ok()?;
+
+  let mut best: Option<(ContentEncoding, u16)> = None;
+
+  for &encoding in supported {
+    let Some(quality) = get_quality(accept_encoding, encoding) else {
+      continue;
+    };
+
+    if quality > 0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+      best = Some((encoding, quality));
+    }
+  }
+
+  best.map(|(encoding, _)| encoding)
+}
+
+/// Returns the quality (in thousandths) the client assigned to the encoding, either explicitly or through `*`.
+fn get_quality(accept_encoding: &str, encoding: ContentEncoding) -> Option<u16> {
+  let mut wildcard = None;
+
+  for item in accept_encoding.split(',') {
+    let mut params = item.split(';');
+    let token = params.next().unwrap_or_default().trim();
+
+    let quality = params
+      .filter_map(|param| param.trim().strip_prefix("q=").or_else(|| param.trim().strip_prefix("Q=")))
+      .map(parse_quality)
+      .next()
+      .unwrap_or(Some(1000));
+
+    // Items with a malformed quality are ignored
+    let Some(quality) = quality else {
+      continue;
+    };
+
+    if token == "*" {
+      wildcard = Some(quality);
+    } else if token.eq_ignore_ascii_case(encoding.as_str())
+      || (encoding == ContentEncoding::Gzip && token.eq_ignore_ascii_case("x-gzip"))
+    {
+      return Some(quality);
+    }
+  }
+
+  wildcard
+}
+
+/// Parses the quality value (`0`, `0.5`, `1.000`, ...) into thousandths. Returns `None` if invalid.
+fn parse_quality(value: &str) -> Option<u16> {
+  let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+  if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+
+  let fraction = fraction.bytes().zip([100, 10, 1]).map(|(digit, weight)| (digit - b'0') as u16 * weight).sum();
+
+  match integer {
+    "0" => Some(fraction),
+    "1" if fraction == 0 => Some(1000),
+    _ => None,
+  }
+}
@@ -0,0 +1,84 @@
+use bytes::Bytes;
+use hyper::http::{HeaderValue, header};
+
+use crate::config::{CompressionConfig, CompressionLevel, ContentEncoding};
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+
+use super::{apply, compress, negotiate};
+
+const ALL: &[ContentEncoding] = &[ContentEncoding::Zstd, ContentEncoding::Brotli, ContentEncoding::Gzip];
+
+#[test]
+fn test_negotiate() {
+  assert_eq!(negotiate(b"gzip, deflate, br, zstd", ALL), Some(ContentEncoding::Zstd));
+  assert_eq!(negotiate(b"gzip, deflate, br", ALL), Some(ContentEncoding::Brotli));
+  assert_eq!(negotiate(b"gzip;q=1.0, br;q=0.5", ALL), Some(ContentEncoding::Gzip));
+  assert_eq!(negotiate(b"x-gzip", ALL), Some(ContentEncoding::Gzip));
+  assert_eq!(negotiate(b"*", ALL), Some(ContentEncoding::Zstd));
+  assert_eq!(negotiate(b"*;q=0.1, br;q=0.2", ALL), Some(ContentEncoding::Brotli));
+  assert_eq!(negotiate(b"zstd;q=0, *", ALL), Some(ContentEncoding::Brotli));
+  assert_eq!(negotiate(b"br;q=2, gzip;q=0.001", ALL), Some(ContentEncoding::Gzip));
+
+  assert_eq!(negotiate(b"", ALL), None);
+  assert_eq!(negotiate(b"identity", ALL), None);
+  assert_eq!(negotiate(b"gzip;q=0", ALL), None);
+  assert_eq!(negotiate(b"br", &[ContentEncoding::Gzip]), None);
+}
+
+#[test]
+fn test_apply_compression() {
+  let config = CompressionConfig::default();
+  let html = "<p>Hello, compression!</p>".repeat(100);
+
+  let response = |content_type: &'static str, text: &str| {
+    let mut body = ResponseBody::new();
+    body.push(Bytes::copy_from_slice(text.as_bytes()));
+
+    let mut response = Response::new(body);
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+  };
+
+  let accept = HeaderValue::from_static("gzip");
+
+  // Compressed, with the Vary header set
+  let mut compressed = response("text/html; charset=utf-8", &html);
+  compressed.headers_mut().insert(header::ETAG, HeaderValue::from_static("\"abc\""));
+  apply(&config, Some(&accept), &mut compressed);
+  assert_eq!(compressed.headers()[header::CONTENT_ENCODING], "gzip");
+  assert_eq!(compressed.headers()[header::VARY], "accept-encoding");
+  assert_eq!(compressed.headers()[header::ETAG], "W/\"abc\"");
+
+  let body = compressed.body().chunks().flat_map(|chunk| chunk.to_vec()).collect::<Vec<_>>();
+  let mut decoded = String::new();
+  std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(body.as_slice()), &mut decoded).unwrap();
+  assert_eq!(decoded, html);
+
+  // Not accepted by the client, but the representation still varies
+  let mut uncompressed = response("text/html", &html);
+  apply(&config, None, &mut uncompressed);
+  assert!(!uncompressed.headers().contains_key(header::CONTENT_ENCODING));
+  assert_eq!(uncompressed.headers()[header::VARY], "accept-encoding");
+
+  // Below the threshold or not matching any rule
+  for (content_type, text) in [("text/html", "<p>Too small</p>"), ("image/png", html.as_str())] {
+    let mut skipped = response(content_type, text);
+    apply(&config, Some(&accept), &mut skipped);
+    assert!(!skipped.headers().contains_key(header::CONTENT_ENCODING));
+    assert!(!skipped.headers().contains_key(header::VARY));
+  }
+}
+
+#[test]
+fn test_compress_roundtrip() {
+  let chunks = [Bytes::from_static(b"Hello, "), Bytes::from_static(b"world!")];
+
+  let zstd = compress(ContentEncoding::Zstd, CompressionLevel::Default, &chunks).unwrap();
+  assert_eq!(zstd::decode_all(zstd.as_slice()).unwrap(), b"Hello, world!");
+
+  let brotli = compress(ContentEncoding::Brotli, CompressionLevel::Best, &chunks).unwrap();
+  let mut decoded = Vec::new();
+  brotli::BrotliDecompress(&mut brotli.as_slice(), &mut decoded).unwrap();
+  assert_eq!(decoded, b"Hello, world!");
+}
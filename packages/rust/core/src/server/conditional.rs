@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test;
+
+mod etag;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hyper::http::response::Builder;
+use hyper::http::{self, HeaderMap, HeaderValue, Method, StatusCode, header};
+
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+use crate::util::time::{UtcDateTime, parse_http_date};
+
+pub use etag::ETag;
+
+/// Evaluates the `If-None-Match` and `If-Modified-Since` preconditions of a GET or HEAD request
+/// against the current validators of the response. Returns `true` if the client's copy is still
+/// fresh and the response can be replaced with `304 Not Modified`.
+///
+/// `If-Modified-Since` is only evaluated when the request doesn't contain `If-None-Match`.
+pub fn is_not_modified(
+  method: &Method,
+  headers: &HeaderMap,
+  etag: Option<&ETag>,
+  last_modified: Option<SystemTime>,
+) -> bool {
+  if method != Method::GET && method != Method::HEAD {
+    return false;
+  }
+
+  if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+    return etag.is_some_and(|etag| etag.matches_if_none_match(if_none_match.as_bytes()));
+  }
+
+  let Some(last_modified) = last_modified else {
+    return false;
+  };
+
+  let if_modified_since = headers.get(header::IF_MODIFIED_SINCE).and_then(|value| value.to_str().ok());
+
+  // HTTP dates have a resolution of one second
+  if_modified_since.and_then(parse_http_date).is_some_and(|since| truncate_to_secs(last_modified) <= since)
+}
+
+/// Creates a `304 Not Modified` response carrying the validators.
+pub fn not_modified(etag: Option<&ETag>, last_modified: Option<SystemTime>) -> http::Result<Response> {
+  with_validators(hyper::Response::builder().status(StatusCode::NOT_MODIFIED), etag, last_modified)
+    .body(ResponseBody::new())
+}
+
+/// Sets the `ETag` and `Last-Modified` headers of the response being built.
+pub fn with_validators(mut builder: Builder, etag: Option<&ETag>, last_modified: Option<SystemTime>) -> Builder {
+  if let Some(etag) = etag {
+    builder = builder.header(header::ETAG, etag.to_header_value());
+  }
+
+  if let Some(last_modified) = last_modified {
+    let date = UtcDateTime::from_system_time(last_modified).to_http_date();
+    builder =
+      builder.header(header::LAST_MODIFIED, HeaderValue::try_from(date).expect("HTTP date is a valid header value"));
+  }
+
+  builder
+}
+
+fn truncate_to_secs(time: SystemTime) -> SystemTime {
+  UNIX_EPOCH + Duration::from_secs(time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+}
@@ -0,0 +1,142 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+
+use hyper::http::HeaderValue;
+
+/// An entity tag identifying a specific version of a response, sent in the `ETag` header.
+///
+/// Strong tags promise byte-for-byte identical responses, while weak tags only promise
+/// semantically equivalent ones (e.g. the same Props rendered into the same page).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ETag {
+  weak: bool,
+  tag: Cow<'static, str>,
+}
+
+impl ETag {
+  /// Creates a strong entity tag. The tag is the opaque value without the surrounding quotes.
+  ///
+  /// Panics if the tag contains characters not allowed in entity tags (double quotes, whitespace or non-ASCII).
+  pub fn strong(tag: impl Into<Cow<'static, str>>) -> Self {
+    Self::new(false, tag.into())
+  }
+
+  /// Creates a weak entity tag. The tag is the opaque value without the surrounding quotes and the `W/` prefix.
+  ///
+  /// Panics if the tag contains characters not allowed in entity tags (double quotes, whitespace or non-ASCII).
+  pub fn weak(tag: impl Into<Cow<'static, str>>) -> Self {
+    Self::new(true, tag.into())
+  }
+
+  /// Creates a strong entity tag from the hash of the content.
+  pub fn from_content(content: &[u8]) -> Self {
+    Self { weak: false, tag: Cow::Owned(format!("{:032x}", fnv1a_128(content))) }
+  }
+
+  /// Creates a weak entity tag from the hash of the content.
+  pub fn weak_from_content(content: &[u8]) -> Self {
+    Self { weak: true, ..Self::from_content(content) }
+  }
+
+  fn new(weak: bool, tag: Cow<'static, str>) -> Self {
+    if let Some(c) = tag.bytes().find(|&c| !is_etag_char(c)) {
+      panic!("entity tag {tag:?} contains a disallowed character {:?}", c as char);
+    }
+
+    Self { weak, tag }
+  }
+
+  pub fn is_weak(&self) -> bool {
+    self.weak
+  }
+
+  /// The opaque value of the tag, without the quotes and the `W/` prefix.
+  pub fn tag(&self) -> &str {
+    &self.tag
+  }
+
+  /// Turns the tag into a weak one, e.g. when the response is transformed in a way that
+  /// doesn't preserve the exact bytes.
+  pub fn into_weak(self) -> Self {
+    Self { weak: true, ..self }
+  }
+
+  pub fn to_header_value(&self) -> HeaderValue {
+    // The tag characters were validated when the tag was created
+    HeaderValue::try_from(self.to_string()).expect("entity tag should be a valid header value")
+  }
+
+  /// Parses an entity tag from its header representation, e.g. `"abc"` or `W/"abc"`.
+  pub fn parse(value: &str) -> Option<Self> {
+    let (weak, quoted) = match value.strip_prefix("W/") {
+      Some(quoted) => (true, quoted),
+      None => (false, value),
+    };
+
+    let tag = quoted.strip_prefix('"')?.strip_suffix('"')?;
+
+    tag.bytes().all(is_etag_char).then(|| Self { weak, tag: Cow::Owned(tag.to_string()) })
+  }
+
+  /// Whether the tag matches the value of an `If-None-Match` header. Tags are compared using
+  /// the weak comparison, i.e. `W/"abc"` matches `"abc"`.
+  pub fn matches_if_none_match(&self, if_none_match: &[u8]) -> bool {
+    let mut rest = if_none_match.trim_ascii();
+
+    if rest == b"*" {
+      return true;
+    }
+
+    // A list of entity tags separated by commas. The tags themselves may contain commas,
+    // so the value is scanned tag by tag instead of being split.
+    loop {
+      rest = rest.trim_ascii();
+
+      if let Some(unprefixed) = rest.strip_prefix(b"W/") {
+        rest = unprefixed;
+      }
+
+      let Some(quoted) = rest.strip_prefix(b"\"") else {
+        return false;
+      };
+
+      let Some(end) = quoted.iter().position(|&c| c == b'"') else {
+        return false;
+      };
+
+      if &quoted[..end] == self.tag.as_bytes() {
+        return true;
+      }
+
+      rest = quoted[end + 1..].trim_ascii();
+
+      match rest.strip_prefix(b",") {
+        Some(next) => rest = next,
+        None => return false,
+      }
+    }
+  }
+}
+
+impl Display for ETag {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.weak {
+      true => write!(f, "W/\"{}\"", self.tag),
+      false => write!(f, "\"{}\"", self.tag),
+    }
+  }
+}
+
+/// `etagc = %x21 / %x23-7E / obs-text`, leaving out the obsolete non-ASCII characters.
+fn is_etag_char(c: u8) -> bool {
+  c == 0x21 || (0x23..=0x7E).contains(&c)
+}
+
+/// The 128-bit FNV-1a hash. It's not cryptographic, but it's stable across builds and platforms,
+/// which is what matters for entity tags computed at build time.
+fn fnv1a_128(content: &[u8]) -> u128 {
+  const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+  const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+  content.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u128).wrapping_mul(PRIME))
+}
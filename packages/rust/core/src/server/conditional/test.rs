@@ -0,0 +1,57 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use hyper::http::{HeaderMap, HeaderValue, Method, header};
+
+use super::{ETag, is_not_modified};
+
+#[test]
+fn test_etag_matching() {
+  let etag = ETag::strong("abc");
+
+  assert_eq!(etag.to_string(), "\"abc\"");
+  assert_eq!(ETag::weak("abc").to_string(), "W/\"abc\"");
+  assert_eq!(ETag::parse("W/\"abc\""), Some(ETag::weak("abc")));
+  assert_eq!(ETag::parse("abc"), None);
+
+  assert!(etag.matches_if_none_match(b"\"abc\""));
+  assert!(etag.matches_if_none_match(b"W/\"abc\""));
+  assert!(etag.matches_if_none_match(b"*"));
+  assert!(etag.matches_if_none_match(b"\"x,y\", W/\"abc\""));
+  assert!(!etag.matches_if_none_match(b"\"abcd\""));
+  assert!(!etag.matches_if_none_match(b"abc"));
+  assert!(!etag.matches_if_none_match(b"\"x\" \"abc\""));
+
+  assert_eq!(ETag::from_content(b"hello"), ETag::from_content(b"hello"));
+  assert_ne!(ETag::from_content(b"hello"), ETag::from_content(b"hello!"));
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_etag() {
+  ETag::weak("has \"quotes\"");
+}
+
+#[test]
+fn test_preconditions() {
+  let etag = ETag::strong("abc");
+  let last_modified = UNIX_EPOCH + Duration::from_millis(1_748_781_045_500);
+
+  let headers = |name, value| HeaderMap::from_iter([(name, HeaderValue::from_static(value))]);
+
+  let if_none_match = headers(header::IF_NONE_MATCH, "W/\"abc\"");
+  assert!(is_not_modified(&Method::GET, &if_none_match, Some(&etag), None));
+  assert!(is_not_modified(&Method::HEAD, &if_none_match, Some(&etag), None));
+  assert!(!is_not_modified(&Method::POST, &if_none_match, Some(&etag), None));
+  assert!(!is_not_modified(&Method::GET, &if_none_match, None, Some(last_modified)));
+
+  let same_second = headers(header::IF_MODIFIED_SINCE, "Sun, 01 Jun 2025 12:30:45 GMT");
+  let second_before = headers(header::IF_MODIFIED_SINCE, "Sun, 01 Jun 2025 12:30:44 GMT");
+  assert!(is_not_modified(&Method::GET, &same_second, None, Some(last_modified)));
+  assert!(!is_not_modified(&Method::GET, &second_before, None, Some(last_modified)));
+  assert!(!is_not_modified(&Method::GET, &same_second, None, None));
+
+  // If-Modified-Since is ignored when If-None-Match is present
+  let mut both = headers(header::IF_NONE_MATCH, "\"other\"");
+  both.insert(header::IF_MODIFIED_SINCE, HeaderValue::from_static("Sun, 01 Jun 2025 12:30:45 GMT"));
+  assert!(!is_not_modified(&Method::GET, &both, Some(&etag), Some(last_modified)));
+}
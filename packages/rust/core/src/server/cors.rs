@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod test;
+
+use hyper::http::{self, HeaderMap, HeaderValue, Method, StatusCode, header};
+
+use crate::config::{CorsConfig, CorsOrigin};
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+
+/// Whether the request is a CORS preflight request, i.e. an `OPTIONS` request
+/// with the `Origin` and `Access-Control-Request-Method` headers.
+pub fn is_preflight(method: &Method, headers: &HeaderMap) -> bool {
+  method == Method::OPTIONS
+    && headers.contains_key(header::ORIGIN)
+    && headers.contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+/// Answers a preflight request. When the origin isn't allowed, the response contains no CORS headers,
+/// and the browser doesn't send the actual request.
+pub fn preflight_response(policy: &CorsConfig, headers: &HeaderMap) -> http::Result<Response> {
+  let mut response = hyper::Response::builder().status(StatusCode::NO_CONTENT).body(ResponseBody::new())?;
+  let response_headers = response.headers_mut();
+
+  response_headers.append(
+    header::VARY,
+    HeaderValue::from_static("origin, access-control-request-method, access-control-request-headers"),
+  );
+
+  if !set_allow_origin(policy, headers.get(header::ORIGIN), response_headers) {
+    return Ok(response);
+  }
+
+  if let Some(methods) = join(policy.allowed_methods) {
+    response_headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, methods);
+  }
+
+  // With credentials, the wildcard is taken literally by browsers, so the requested headers are echoed instead
+  let allowed_headers = match policy.allowed_headers.contains(&"*") {
+    true if policy.allow_credentials => headers.get(header::ACCESS_CONTROL_REQUEST_HEADERS).cloned(),
+    true => Some(HeaderValue::from_static("*")),
+    false => join(policy.allowed_headers),
+  };
+
+  if let Some(allowed_headers) = allowed_headers {
+    response_headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+  }
+
+  if let Some(max_age) = policy.max_age {
+    response_headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age.as_secs()));
+  }
+
+  Ok(response)
+}
+
+/// Adds the CORS headers to the response of an actual (non-preflight) request.
+pub fn apply(policy: &CorsConfig, origin: Option<&HeaderValue>, response: &mut Response) {
+  let headers = response.headers_mut();
+
+  if !is_origin_independent(policy) {
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+  }
+
+  if !set_allow_origin(policy, origin, headers) {
+    return;
+  }
+
+  if let Some(exposed) = join(policy.exposed_headers) {
+    headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, exposed);
+  }
+}
+
+/// Sets `Access-Control-Allow-Origin` (and `Access-Control-Allow-Credentials`) if the origin is allowed.
+/// Returns whether it's allowed.
+fn set_allow_origin(policy: &CorsConfig, origin: Option<&HeaderValue>, headers: &mut HeaderMap) -> bool {
+  let Some(origin) = origin else {
+    return false;
+  };
+
+  let Some(origin_str) = origin.to_str().ok() else {
+    return false;
+  };
+
+  if !policy.allowed_origins.iter().any(|allowed| allowed.matches(origin_str)) {
+    return false;
+  }
+
+  let allow_origin = match is_origin_independent(policy) {
+    true => HeaderValue::from_static("*"),
+    false => origin.clone(),
+  };
+
+  headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+
+  if policy.allow_credentials {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+  }
+
+  true
+}
+
+/// Whether the response is the same for all origins, i.e. any origin is allowed without credentials.
+/// Browsers reject the `*` wildcard for requests with credentials, so then the origin has to be echoed.
+fn is_origin_independent(policy: &CorsConfig) -> bool {
+  !policy.allow_credentials && policy.allowed_origins.iter().any(|origin| matches!(origin, CorsOrigin::Any))
+}
+
+fn join(values: &[&str]) -> Option<HeaderValue> {
+  match values.is_empty() {
+    true => None,
+    false => HeaderValue::try_from(values.join(", ")).ok(),
+  }
+}
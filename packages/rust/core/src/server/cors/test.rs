@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use hyper::http::{HeaderMap, HeaderValue, Method, header};
+
+use crate::config::{CorsConfig, CorsOrigin};
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+
+use super::{apply, is_preflight, preflight_response};
+
+const POLICY: CorsConfig = CorsConfig {
+  enabled: true,
+  allowed_origins: &[CorsOrigin::Exact("https://example.com"), CorsOrigin::Pattern("https://*.example.org")],
+  allowed_methods: &["GET", "PUT"],
+  allowed_headers: &["content-type"],
+  exposed_headers: &["x-total-count"],
+  allow_credentials: true,
+  max_age: Some(Duration::from_secs(600)),
+};
+
+fn headers(pairs: &[(header::HeaderName, &'static str)]) -> HeaderMap {
+  pairs.iter().map(|(name, value)| (name.clone(), HeaderValue::from_static(value))).collect()
+}
+
+#[test]
+fn test_origin_matching() {
+  let pattern = CorsOrigin::Pattern("https://*.example.org");
+
+  assert!(pattern.matches("https://api.example.org"));
+  assert!(pattern.matches("HTTPS://API.EXAMPLE.ORG"));
+  assert!(!pattern.matches("https://.example.org"));
+  assert!(!pattern.matches("https://example.org"));
+  assert!(!pattern.matches("http://api.example.org"));
+
+  assert!(CorsOrigin::Exact("https://example.com").matches("https://example.com"));
+  assert!(!CorsOrigin::Exact("https://example.com").matches("https://example.com.evil"));
+  assert!(CorsOrigin::Any.matches("null"));
+}
+
+#[test]
+fn test_preflight() {
+  let request = headers(&[(header::ORIGIN, "https://api.example.org"), (header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")]);
+
+  assert!(is_preflight(&Method::OPTIONS, &request));
+  assert!(!is_preflight(&Method::GET, &request));
+  assert!(!is_preflight(&Method::OPTIONS, &headers(&[(header::ORIGIN, "https://example.com")])));
+
+  let response = preflight_response(&POLICY, &request).unwrap();
+  let response = response.headers();
+
+  assert_eq!(response[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://api.example.org");
+  assert_eq!(response[header::ACCESS_CONTROL_ALLOW_METHODS], "GET, PUT");
+  assert_eq!(response[header::ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+  assert_eq!(response[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+  assert_eq!(response[header::ACCESS_CONTROL_MAX_AGE], "600");
+
+  let request = headers(&[(header::ORIGIN, "https://evil.com"), (header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")]);
+  let response = preflight_response(&POLICY, &request).unwrap();
+
+  assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+  assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_METHODS));
+}
+
+#[test]
+fn test_apply() {
+  let mut response = Response::new(ResponseBody::new());
+  apply(&POLICY, Some(&HeaderValue::from_static("https://example.com")), &mut response);
+
+  assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+  assert_eq!(response.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS], "x-total-count");
+  assert_eq!(response.headers()[header::VARY], "origin");
+
+  let public = CorsConfig { enabled: true, allowed_origins: &[CorsOrigin::Any], ..CorsConfig::default() };
+  let mut response = Response::new(ResponseBody::new());
+  apply(&public, Some(&HeaderValue::from_static("https://example.com")), &mut response);
+
+  assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+  assert!(!response.headers().contains_key(header::VARY));
+}
@@ -0,0 +1,16 @@
+use crate::server::tserver::HyperRequest;
+
+/// The request as seen by a handler.
+///
+/// A `handler.rs` Route Target exports a `handler` function producing the whole response,
+/// regardless of the request method:
+///
+/// ```ignore
+/// pub async fn handler(request: &ruxy::HandlerRequest) -> ruxy::Response {
+///   let mut body = ruxy::ResponseBody::new();
+///   body.push(format!("{} {}", request.method(), request.uri()).into());
+///
+///   ruxy::Response::new(body)
+/// }
+/// ```
+pub type HandlerRequest = HyperRequest;
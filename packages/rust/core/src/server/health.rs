@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod test;
+
+use std::fmt::{Display, Write};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::future::join_all;
+use hyper::{Response, StatusCode, http};
+
+use crate::server::response::body::ResponseBody;
+use crate::server::shutdown;
+
+/// How long the readiness checks can take before the unfinished ones are considered failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+type CheckFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+type Check = Arc<dyn Fn() -> CheckFuture + Send + Sync>;
+
+static LISTENERS_BOUND: AtomicBool = AtomicBool::new(false);
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static CHECKS: Mutex<Vec<(&'static str, Check)>> = Mutex::new(Vec::new());
+
+/// Registers a check that must pass for the application to be considered ready, e.g. a database ping.
+///
+/// Checks are run concurrently on every request to the readiness endpoint,
+/// those not completed within 5 seconds are considered failed.
+pub fn register_readiness_check<F, Fut, E>(name: &'static str, check: F)
+where
+  F: Fn() -> Fut + Send + Sync + 'static,
+  Fut: Future<Output = Result<(), E>> + Send + 'static,
+  E: Display,
+{
+  CHECKS.lock().unwrap_or_else(|e| e.into_inner()).push((name, into_check(check)));
+}
+
+fn into_check<F, Fut, E>(check: F) -> Check
+where
+  F: Fn() -> Fut + Send + Sync + 'static,
+  Fut: Future<Output = Result<(), E>> + Send + 'static,
+  E: Display,
+{
+  Arc::new(move || {
+    let future = check();
+    Box::pin(async move { future.await.map_err(|e| e.to_string()) })
+  })
+}
+
+/// Marks all the listeners as bound. Until then, the application is not ready.
+pub fn set_listeners_bound() {
+  LISTENERS_BOUND.store(true, Ordering::Relaxed);
+}
+
+/// Marks the application as shutting down, failing the readiness from now on. Set as soon as shutdown
+/// is requested, before the shutdown delay (see `HealthConfig::shutdown_delay`) and graceful shutdown.
+pub fn set_shutting_down() {
+  SHUTTING_DOWN.store(true, Ordering::Relaxed);
+}
+
+/// The outcome of all the readiness checks.
+pub struct Readiness {
+  pub checks: Vec<(&'static str, Result<(), String>)>,
+}
+
+impl Readiness {
+  pub fn is_ready(&self) -> bool {
+    self.checks.iter().all(|(_, result)| result.is_ok())
+  }
+}
+
+/// Runs the built-in and all the registered readiness checks.
+pub async fn readiness() -> Readiness {
+  // Cloned out, so that the lock isn't held while the checks are running.
+  let registered = CHECKS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+
+  let state = ReadinessState {
+    listeners_bound: LISTENERS_BOUND.load(Ordering::Relaxed),
+    shutting_down: SHUTTING_DOWN.load(Ordering::Relaxed) || shutdown::is_shutting_down(),
+  };
+
+  evaluate(state, &registered, CHECK_TIMEOUT).await
+}
+
+/// The state of the server the built-in readiness checks are derived from.
+#[derive(Clone, Copy)]
+struct ReadinessState {
+  listeners_bound: bool,
+  shutting_down: bool,
+}
+
+async fn evaluate(state: ReadinessState, registered: &[(&'static str, Check)], check_timeout: Duration) -> Readiness {
+  let mut checks = Vec::new();
+
+  let listeners = match state.listeners_bound {
+    true => Ok(()),
+    false => Err("not all listeners are bound yet".to_string()),
+  };
+
+  let shutdown = match state.shutting_down {
+    true => Err("graceful shutdown in progress".to_string()),
+    false => Ok(()),
+  };
+
+  checks.push(("listeners", listeners));
+  checks.push(("shutdown", shutdown));
+
+  // All checks share a single deadline, so that a probe doesn't take longer with more checks registered
+  let deadline = tokio::time::Instant::now() + check_timeout;
+
+  let results = join_all(registered.iter().map(|(name, check)| async move {
+    match tokio::time::timeout_at(deadline, check()).await {
+      Ok(result) => (*name, result),
+      Err(_) => (*name, Err(format!("timed out after {}ms", check_timeout.as_millis()))),
+    }
+  }));
+
+  checks.extend(results.await);
+
+  Readiness { checks }
+}
+
+/// Produces the response of the liveness endpoint.
+pub fn liveness_response() -> http::Result<Response<ResponseBody>> {
+  text_response(StatusCode::OK, "ok\n".to_string())
+}
+
+/// Produces the response of the readiness endpoint, listing the outcome of every check.
+pub async fn readiness_response() -> http::Result<Response<ResponseBody>> {
+  let readiness = readiness().await;
+  let mut text = String::new();
+
+  for (name, result) in &readiness.checks {
+    let _ = match result {
+      Ok(()) => writeln!(text, "[+]{name} ok"),
+      Err(reason) => writeln!(text, "[-]{name} failed: {reason}"),
+    };
+  }
+
+  match readiness.is_ready() {
+    true => {
+      text.push_str("ready\n");
+      text_response(StatusCode::OK, text)
+    }
+    false => {
+      text.push_str("not ready\n");
+      text_response(StatusCode::SERVICE_UNAVAILABLE, text)
+    }
+  }
+}
+
+fn text_response(status: StatusCode, text: String) -> http::Result<Response<ResponseBody>> {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from(text));
+
+  http::Response::builder()
+    .status(status)
+    .header(http::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+    .header(http::header::CACHE_CONTROL, "no-store")
+    .body(body)
+}
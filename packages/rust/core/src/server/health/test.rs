@@ -0,0 +1,59 @@
+use std::future::pending;
+use std::time::{Duration, Instant};
+
+use super::{Check, ReadinessState, evaluate, into_check};
+
+const CHECK_TIMEOUT: Duration = Duration::from_millis(20);
+
+const READY: ReadinessState = ReadinessState { listeners_bound: true, shutting_down: false };
+
+fn failed(checks: &[(&'static str, Result<(), String>)]) -> Vec<&'static str> {
+  checks.iter().filter(|(_, result)| result.is_err()).map(|(name, _)| *name).collect()
+}
+
+#[tokio::test]
+async fn test_readiness_follows_server_state() {
+  // Starting up, the listeners are not bound yet
+  let starting = evaluate(ReadinessState { listeners_bound: false, shutting_down: false }, &[], CHECK_TIMEOUT).await;
+  assert!(!starting.is_ready());
+  assert_eq!(failed(&starting.checks), ["listeners"]);
+
+  let running = evaluate(READY, &[], CHECK_TIMEOUT).await;
+  assert!(running.is_ready());
+
+  let shutting_down = evaluate(ReadinessState { listeners_bound: true, shutting_down: true }, &[], CHECK_TIMEOUT).await;
+  assert!(!shutting_down.is_ready());
+  assert_eq!(failed(&shutting_down.checks), ["shutdown"]);
+}
+
+#[tokio::test]
+async fn test_registered_checks() {
+  let checks: Vec<(&'static str, Check)> = vec![
+    ("database", into_check(|| async { Ok::<_, String>(()) })),
+    ("cache", into_check(|| async { Err("connection refused") })),
+    ("queue", into_check(pending::<Result<(), String>>)),
+  ];
+
+  let readiness = evaluate(READY, &checks, CHECK_TIMEOUT).await;
+  assert!(!readiness.is_ready());
+  assert_eq!(failed(&readiness.checks), ["cache", "queue"]);
+
+  let reasons: Vec<_> = readiness.checks.iter().filter_map(|(_, result)| result.as_ref().err()).collect();
+  assert_eq!(reasons, ["connection refused", "timed out after 20ms"]);
+
+  let passing = evaluate(READY, &checks[..1], CHECK_TIMEOUT).await;
+  assert!(passing.is_ready());
+}
+
+#[tokio::test]
+async fn test_checks_run_concurrently() {
+  let checks: Vec<(&'static str, Check)> =
+    ["database", "cache", "queue", "search"].map(|name| (name, into_check(pending::<Result<(), String>>))).into();
+
+  let start = Instant::now();
+  let readiness = evaluate(READY, &checks, CHECK_TIMEOUT).await;
+
+  // Run one after another, the checks would take 80ms
+  assert!(start.elapsed() < CHECK_TIMEOUT * 3);
+  assert_eq!(failed(&readiness.checks), ["database", "cache", "queue", "search"]);
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod test;
+
+mod address;
+mod connection;
+#[cfg(unix)]
+mod inherited;
+
+use std::io;
+use std::net::SocketAddr;
+
+use tokio::net::TcpListener;
+
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+pub use address::{InheritedFd, ListenAddress};
+pub use connection::{Connection, ConnectionInfo, DetachedConnection};
+
+#[cfg(unix)]
+pub(crate) use inherited::{ENV_LISTEN_FDNAMES, ENV_LISTEN_FDS, ENV_LISTEN_PID, SD_LISTEN_FDS_START};
+
+/// Comma-separated list of addresses overriding `AppConfig::addresses`. The Ruxy CLI sets it
+/// to listen on the socket it keeps open during development.
+pub const ENV_LISTEN: &str = "RUXY_LISTEN";
+
+/// A port replacing the port of every TCP address to listen on, other addresses are kept as they are.
+/// The Ruxy CLI sets it to the port passed with `--port`.
+pub const ENV_PORT: &str = "RUXY_PORT";
+
+/// Returns the addresses to listen on, the ones from `RUXY_LISTEN` if set, the configured ones otherwise,
+/// with the port from `RUXY_PORT` applied.
+pub fn listen_addresses(configured: &[&str]) -> Result<Vec<String>, String> {
+  let listen_env = std::env::var(ENV_LISTEN).ok();
+  let port_env = std::env::var(ENV_PORT).ok();
+
+  select_addresses(configured, listen_env.as_deref(), port_env.as_deref())
+}
+
+fn select_addresses(
+  configured: &[&str],
+  listen_env: Option<&str>,
+  port_env: Option<&str>,
+) -> Result<Vec<String>, String> {
+  let addresses: Vec<String> = match listen_env.map(str::trim).filter(|addresses| !addresses.is_empty()) {
+    Some(addresses) => addresses.split(',').map(|address| address.trim().to_string()).collect(),
+    None => configured.iter().map(|address| address.to_string()).collect(),
+  };
+
+  let Some(port) = port_env.map(str::trim).filter(|port| !port.is_empty()) else {
+    return Ok(addresses);
+  };
+
+  let Ok(port) = port.parse::<u16>() else {
+    return Err(format!("{ENV_PORT} must be a valid port number, value: {port}"));
+  };
+
+  let with_port = addresses.into_iter().map(|address| match address.parse::<SocketAddr>() {
+    Ok(mut tcp) => {
+      tcp.set_port(port);
+      tcp.to_string()
+    }
+    Err(_) => address,
+  });
+
+  Ok(with_port.collect())
+}
+
+/// A socket accepting incoming connections.
+pub enum Listener {
+  Tcp(TcpListener),
+  #[cfg(unix)]
+  Unix(UnixListener),
+}
+
+impl Listener {
+  /// Creates a listener for the provided address.
+  ///
+  /// Must be called from within a Tokio runtime with I/O enabled.
+  pub async fn bind(address: &ListenAddress) -> io::Result<Self> {
+    match address {
+      ListenAddress::Tcp(address) => Ok(Listener::Tcp(TcpListener::bind(address).await?)),
+      #[cfg(unix)]
+      ListenAddress::Unix(path) => {
+        remove_stale_socket(path)?;
+        Ok(Listener::Unix(UnixListener::bind(path)?))
+      }
+      #[cfg(unix)]
+      ListenAddress::Inherited(selector) => Self::from_fd(inherited::take_inherited_fd(selector)?),
+      #[cfg(not(unix))]
+      _ => Err(io::Error::new(io::ErrorKind::Unsupported, format!("'{address}' is not supported on this platform"))),
+    }
+  }
+
+  /// Creates a listener from an already bound and listening socket.
+  /// Both TCP and Unix domain sockets are supported, the kind is detected from the socket itself.
+  #[cfg(unix)]
+  pub fn from_fd(fd: std::os::fd::OwnedFd) -> io::Result<Self> {
+    let tcp = std::net::TcpListener::from(fd);
+
+    // `getsockname` reports the address family of the socket, which only
+    // converts to `SocketAddr` successfully for the IPv4 and IPv6 families.
+    if tcp.local_addr().is_ok() {
+      tcp.set_nonblocking(true)?;
+      return Ok(Listener::Tcp(TcpListener::from_std(tcp)?));
+    }
+
+    let unix = std::os::unix::net::UnixListener::from(std::os::fd::OwnedFd::from(tcp));
+
+    if let Err(e) = unix.local_addr() {
+      return Err(io::Error::new(e.kind(), format!("inherited descriptor is neither a TCP nor a Unix socket: {e}")));
+    }
+
+    unix.set_nonblocking(true)?;
+    Ok(Listener::Unix(UnixListener::from_std(unix)?))
+  }
+
+  /// Accepts a new incoming connection.
+  pub async fn accept(&self) -> io::Result<Connection> {
+    match self {
+      Listener::Tcp(listener) => Ok(Connection::Tcp(listener.accept().await?.0)),
+      #[cfg(unix)]
+      Listener::Unix(listener) => Ok(Connection::Unix(listener.accept().await?.0)),
+    }
+  }
+}
+
+/// Removes a socket file left behind by a previous run of the application, so that we can bind again.
+/// A socket is stale only if nothing accepts connections on it, a socket of a running instance is kept
+/// and binding fails. Anything else than a socket is left untouched and binding to it will fail.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> io::Result<()> {
+  use std::os::unix::fs::FileTypeExt;
+
+  match std::fs::symlink_metadata(path) {
+    Ok(metadata) if metadata.file_type().is_socket() => {}
+    _ => return Ok(()),
+  }
+
+  match std::os::unix::net::UnixStream::connect(path) {
+    Ok(_) => {
+      Err(io::Error::new(io::ErrorKind::AddrInUse, format!("another process is listening on {}", path.display())))
+    }
+    Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => std::fs::remove_file(path),
+    Err(_) => Ok(()),
+  }
+}
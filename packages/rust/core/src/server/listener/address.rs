@@ -0,0 +1,73 @@
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const UNIX_PREFIX: &str = "unix:";
+const FD_PREFIX: &str = "fd:";
+
+/// A parsed entry of `AppConfig::addresses`.
+///
+/// Supported formats:
+/// - `127.0.0.1:3000`, `[::1]:3000` – a TCP socket bound to the provided address,
+/// - `unix:/path/to/app.sock` – a Unix domain socket bound to the provided path,
+/// - `fd:0` – a socket inherited from the parent process using the `LISTEN_FDS` protocol
+///   (systemd socket activation), selected by its index among the passed descriptors,
+/// - `fd:name` – a socket inherited using the `LISTEN_FDS` protocol, selected by its name
+///   as listed in `LISTEN_FDNAMES` (the `FileDescriptorName=` option of the systemd socket unit).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ListenAddress {
+  Tcp(SocketAddr),
+  Unix(PathBuf),
+  Inherited(InheritedFd),
+}
+
+/// Selects one of the file descriptors passed to the process using the `LISTEN_FDS` protocol.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InheritedFd {
+  /// Zero-based index of the descriptor, `fd:0` being the first passed descriptor (`SD_LISTEN_FDS_START`).
+  Index(usize),
+  /// Name of the descriptor as listed in `LISTEN_FDNAMES`.
+  Name(String),
+}
+
+impl FromStr for ListenAddress {
+  type Err = String;
+
+  fn from_str(address: &str) -> Result<Self, Self::Err> {
+    if let Some(path) = address.strip_prefix(UNIX_PREFIX) {
+      if path.is_empty() {
+        return Err(format!("invalid address '{address}': Unix socket path cannot be empty"));
+      }
+
+      return Ok(ListenAddress::Unix(PathBuf::from(path)));
+    }
+
+    if let Some(selector) = address.strip_prefix(FD_PREFIX) {
+      if selector.is_empty() {
+        return Err(format!("invalid address '{address}': expected a descriptor index or name after 'fd:'"));
+      }
+
+      return match selector.parse::<usize>() {
+        Ok(index) => Ok(ListenAddress::Inherited(InheritedFd::Index(index))),
+        Err(_) => Ok(ListenAddress::Inherited(InheritedFd::Name(selector.to_string()))),
+      };
+    }
+
+    match SocketAddr::from_str(address) {
+      Ok(address) => Ok(ListenAddress::Tcp(address)),
+      Err(e) => Err(format!("invalid address '{address}': {e}")),
+    }
+  }
+}
+
+impl Display for ListenAddress {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ListenAddress::Tcp(address) => write!(f, "{address}"),
+      ListenAddress::Unix(path) => write!(f, "{UNIX_PREFIX}{}", path.display()),
+      ListenAddress::Inherited(InheritedFd::Index(index)) => write!(f, "{FD_PREFIX}{index}"),
+      ListenAddress::Inherited(InheritedFd::Name(name)) => write!(f, "{FD_PREFIX}{name}"),
+    }
+  }
+}
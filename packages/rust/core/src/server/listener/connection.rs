@@ -0,0 +1,135 @@
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+
+use crate::server::proxy::ProxyHeader;
+
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+/// An accepted connection, regardless of the kind of the listener it was accepted on.
+pub enum Connection {
+  Tcp(TcpStream),
+  #[cfg(unix)]
+  Unix(UnixStream),
+}
+
+/// Information about an accepted connection, available to every request served on it.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ConnectionInfo {
+  /// The address of the remote peer, `None` for connections accepted on a Unix domain socket.
+  pub peer_addr: Option<SocketAddr>,
+  /// The PROXY protocol header sent by the peer, if enabled. The peer is a trusted proxy then.
+  pub proxy: Option<ProxyHeader>,
+}
+
+/// A connection detached from the async runtime it was accepted on.
+///
+/// Tokio streams are bound to the I/O driver of the runtime that created them. When a connection
+/// is handed over from the acceptor thread to a worker thread, it travels detached and is attached
+/// to the I/O driver of the worker's runtime, so that its I/O is driven by the core processing it.
+pub enum DetachedConnection {
+  Tcp(std::net::TcpStream),
+  #[cfg(unix)]
+  Unix(std::os::unix::net::UnixStream),
+}
+
+impl Connection {
+  /// Returns the address of the remote peer.
+  /// Connections accepted on a Unix domain socket don't have a peer address.
+  pub fn peer_addr(&self) -> Option<SocketAddr> {
+    match self {
+      Connection::Tcp(stream) => stream.peer_addr().ok(),
+      #[cfg(unix)]
+      Connection::Unix(_) => None,
+    }
+  }
+
+  /// Returns the information about this connection.
+  pub fn info(&self) -> ConnectionInfo {
+    ConnectionInfo { peer_addr: self.peer_addr(), proxy: None }
+  }
+
+  /// Detaches this connection from the current runtime, so that it can be sent to another thread.
+  pub fn detach(self) -> io::Result<DetachedConnection> {
+    match self {
+      Connection::Tcp(stream) => Ok(DetachedConnection::Tcp(stream.into_std()?)),
+      #[cfg(unix)]
+      Connection::Unix(stream) => Ok(DetachedConnection::Unix(stream.into_std()?)),
+    }
+  }
+}
+
+impl DetachedConnection {
+  /// Attaches this connection to the runtime of the calling thread.
+  ///
+  /// ### Panics
+  /// This function panics if called outside of a Tokio runtime with I/O enabled.
+  pub fn attach(self) -> io::Result<Connection> {
+    match self {
+      DetachedConnection::Tcp(stream) => Ok(Connection::Tcp(TcpStream::from_std(stream)?)),
+      #[cfg(unix)]
+      DetachedConnection::Unix(stream) => Ok(Connection::Unix(UnixStream::from_std(stream)?)),
+    }
+  }
+}
+
+impl AsyncRead for Connection {
+  fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+    match self.get_mut() {
+      Connection::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+      #[cfg(unix)]
+      Connection::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+    }
+  }
+}
+
+impl AsyncWrite for Connection {
+  fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+    match self.get_mut() {
+      Connection::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+      #[cfg(unix)]
+      Connection::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+    }
+  }
+
+  fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    match self.get_mut() {
+      Connection::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+      #[cfg(unix)]
+      Connection::Unix(stream) => Pin::new(stream).poll_flush(cx),
+    }
+  }
+
+  fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    match self.get_mut() {
+      Connection::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+      #[cfg(unix)]
+      Connection::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+    }
+  }
+
+  fn poll_write_vectored(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    bufs: &[io::IoSlice<'_>],
+  ) -> Poll<io::Result<usize>> {
+    match self.get_mut() {
+      Connection::Tcp(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+      #[cfg(unix)]
+      Connection::Unix(stream) => Pin::new(stream).poll_write_vectored(cx, bufs),
+    }
+  }
+
+  fn is_write_vectored(&self) -> bool {
+    match self {
+      Connection::Tcp(stream) => stream.is_write_vectored(),
+      #[cfg(unix)]
+      Connection::Unix(stream) => stream.is_write_vectored(),
+    }
+  }
+}
@@ -0,0 +1,87 @@
+use std::io;
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+use std::sync::Mutex;
+
+use super::address::InheritedFd;
+
+/// The first file descriptor passed using the `LISTEN_FDS` protocol (after stdin, stdout and stderr).
+pub(crate) const SD_LISTEN_FDS_START: RawFd = 3;
+
+pub(crate) const ENV_LISTEN_PID: &str = "LISTEN_PID";
+pub(crate) const ENV_LISTEN_FDS: &str = "LISTEN_FDS";
+pub(crate) const ENV_LISTEN_FDNAMES: &str = "LISTEN_FDNAMES";
+
+/// Descriptors that have already been taken over by a listener. Each inherited descriptor
+/// can only be owned once, otherwise it would get closed multiple times.
+static CLAIMED_FDS: Mutex<Vec<RawFd>> = Mutex::new(Vec::new());
+
+/// Takes ownership of a file descriptor passed to this process using the `LISTEN_FDS` protocol.
+pub fn take_inherited_fd(selector: &InheritedFd) -> io::Result<OwnedFd> {
+  let listen_pid = std::env::var(ENV_LISTEN_PID).ok();
+  let listen_fds = std::env::var(ENV_LISTEN_FDS).ok();
+  let listen_fdnames = std::env::var(ENV_LISTEN_FDNAMES).ok();
+
+  let env = ListenEnv {
+    listen_pid: listen_pid.as_deref(),
+    listen_fds: listen_fds.as_deref(),
+    listen_fdnames: listen_fdnames.as_deref(),
+  };
+
+  let fd = resolve_fd(selector, &env, std::process::id()).map_err(io::Error::other)?;
+
+  let mut claimed = CLAIMED_FDS.lock().unwrap_or_else(|e| e.into_inner());
+
+  if claimed.contains(&fd) {
+    return Err(io::Error::other(format!("inherited file descriptor {fd} is already used by another listener")));
+  }
+
+  claimed.push(fd);
+
+  // SAFETY: the descriptor was passed to this process by its parent using the `LISTEN_FDS` protocol,
+  //         which hands its ownership over to us, and we just made sure it's not owned by anyone else.
+  Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Raw values of the `LISTEN_*` environment variables.
+pub(super) struct ListenEnv<'a> {
+  pub(super) listen_pid: Option<&'a str>,
+  pub(super) listen_fds: Option<&'a str>,
+  pub(super) listen_fdnames: Option<&'a str>,
+}
+
+/// Resolves the selected descriptor to its raw number, validating the `LISTEN_*` environment.
+pub(super) fn resolve_fd(selector: &InheritedFd, env: &ListenEnv, pid: u32) -> Result<RawFd, String> {
+  let Some(listen_fds) = env.listen_fds else {
+    return Err(format!("no file descriptors were passed to this process ({ENV_LISTEN_FDS} is not set)"));
+  };
+
+  let Ok(count) = listen_fds.parse::<usize>() else {
+    return Err(format!("{ENV_LISTEN_FDS} must be a valid number, value: {listen_fds}"));
+  };
+
+  // The variables are inherited by child processes too, so we need to make sure they were meant for us.
+  if let Some(listen_pid) = env.listen_pid
+    && listen_pid.parse::<u32>().ok() != Some(pid)
+  {
+    return Err(format!("file descriptors were passed to a different process ({ENV_LISTEN_PID}={listen_pid})"));
+  }
+
+  let index = match selector {
+    InheritedFd::Index(index) => *index,
+    InheritedFd::Name(name) => {
+      let names = env.listen_fdnames.unwrap_or_default();
+
+      let Some(index) = names.split(':').position(|n| n == name) else {
+        return Err(format!("no file descriptor named '{name}' was passed to this process ({ENV_LISTEN_FDNAMES})"));
+      };
+
+      index
+    }
+  };
+
+  if index >= count {
+    return Err(format!("file descriptor index {index} is out of range, {count} descriptor(s) passed"));
+  }
+
+  Ok(SD_LISTEN_FDS_START + index as RawFd)
+}
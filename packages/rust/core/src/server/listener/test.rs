@@ -0,0 +1,136 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::{InheritedFd, ListenAddress, select_addresses};
+
+#[test]
+fn test_parse_listen_address() {
+  let tcp = ListenAddress::from_str("127.0.0.1:3000");
+  assert_eq!(tcp, Ok(ListenAddress::Tcp(SocketAddr::from(([127, 0, 0, 1], 3000)))));
+
+  let unix = ListenAddress::from_str("unix:/run/app.sock");
+  assert_eq!(unix, Ok(ListenAddress::Unix(PathBuf::from("/run/app.sock"))));
+
+  let by_index = ListenAddress::from_str("fd:1");
+  assert_eq!(by_index, Ok(ListenAddress::Inherited(InheritedFd::Index(1))));
+
+  let by_name = ListenAddress::from_str("fd:http");
+  assert_eq!(by_name, Ok(ListenAddress::Inherited(InheritedFd::Name("http".to_string()))));
+
+  assert!(ListenAddress::from_str("unix:").is_err());
+  assert!(ListenAddress::from_str("fd:").is_err());
+  assert!(ListenAddress::from_str("localhost").is_err());
+
+  for address in ["[::1]:8080", "unix:/tmp/x.sock", "fd:0", "fd:web"] {
+    assert_eq!(ListenAddress::from_str(address).unwrap().to_string(), address);
+  }
+}
+
+#[test]
+fn test_select_addresses() {
+  let configured = ["127.0.0.1:3000", "unix:/run/app.sock"];
+
+  assert_eq!(select_addresses(&configured, None, None).unwrap(), configured);
+  assert_eq!(select_addresses(&configured, Some(" "), None).unwrap(), configured);
+  assert_eq!(select_addresses(&configured, Some("0.0.0.0:8080"), None).unwrap(), ["0.0.0.0:8080"]);
+  assert_eq!(select_addresses(&configured, Some("fd:0, [::]:8080"), None).unwrap(), ["fd:0", "[::]:8080"]);
+}
+
+#[test]
+fn test_select_addresses_with_port() {
+  let configured = ["127.0.0.1:3000", "[::1]:3000", "unix:/run/app.sock"];
+
+  let with_port = select_addresses(&configured, None, Some("8080")).unwrap();
+  assert_eq!(with_port, ["127.0.0.1:8080", "[::1]:8080", "unix:/run/app.sock"]);
+
+  let overridden = select_addresses(&configured, Some("0.0.0.0:3000, fd:0"), Some("80")).unwrap();
+  assert_eq!(overridden, ["0.0.0.0:80", "fd:0"]);
+
+  assert_eq!(select_addresses(&configured, None, Some("")).unwrap(), configured);
+  assert!(select_addresses(&configured, None, Some("65536")).is_err());
+  assert!(select_addresses(&configured, None, Some("http")).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_inherited_fd() {
+  use super::inherited::{ListenEnv, resolve_fd};
+
+  let env = ListenEnv { listen_pid: Some("42"), listen_fds: Some("2"), listen_fdnames: Some("http:admin") };
+
+  assert_eq!(resolve_fd(&InheritedFd::Index(0), &env, 42), Ok(3));
+  assert_eq!(resolve_fd(&InheritedFd::Index(1), &env, 42), Ok(4));
+  assert_eq!(resolve_fd(&InheritedFd::Name("admin".to_string()), &env, 42), Ok(4));
+
+  assert!(resolve_fd(&InheritedFd::Index(2), &env, 42).is_err());
+  assert!(resolve_fd(&InheritedFd::Name("metrics".to_string()), &env, 42).is_err());
+  assert!(resolve_fd(&InheritedFd::Index(0), &env, 43).is_err());
+
+  let env = ListenEnv { listen_pid: None, listen_fds: None, listen_fdnames: None };
+  assert!(resolve_fd(&InheritedFd::Index(0), &env, 42).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unix_listeners() {
+  use std::os::fd::OwnedFd;
+
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::UnixStream;
+
+  use super::{Connection, Listener};
+
+  let dir = std::env::temp_dir().join(format!("ruxy-listener-test-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+
+  let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+  async fn roundtrip(listener: &Listener, path: &std::path::Path) {
+    let mut client = UnixStream::connect(path).await.unwrap();
+    let mut connection = listener.accept().await.unwrap();
+
+    assert!(matches!(connection, Connection::Unix(_)));
+    assert_eq!(connection.peer_addr(), None);
+
+    client.write_all(b"ping").await.unwrap();
+
+    let mut buf = [0; 4];
+    connection.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    // A connection handed over to another thread must still be usable afterward.
+    let mut connection = connection.detach().unwrap().attach().unwrap();
+    connection.write_all(b"pong").await.unwrap();
+
+    client.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"pong");
+  }
+
+  rt.block_on(async {
+    // Bound by address, a stale socket file from a previous run must not prevent binding
+    let path = dir.join("bound.sock");
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    let address = ListenAddress::from_str(&format!("unix:{}", path.display())).unwrap();
+    let listener = Listener::bind(&address).await.unwrap();
+    roundtrip(&listener, &path).await;
+
+    // The socket of a running instance must not be taken over
+    let e = Listener::bind(&address).await.err().unwrap();
+    assert_eq!(e.kind(), std::io::ErrorKind::AddrInUse);
+
+    // The connection probing the socket
+    drop(listener.accept().await.unwrap());
+    roundtrip(&listener, &path).await;
+
+    // Created from an already listening socket, the same way inherited descriptors are
+    let path = dir.join("inherited.sock");
+    let socket = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    let listener = Listener::from_fd(OwnedFd::from(socket)).unwrap();
+    assert!(matches!(listener, Listener::Unix(_)));
+    roundtrip(&listener, &path).await;
+  });
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod test;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use hyper::http::{self, StatusCode, header};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::config::get_app_config;
+use crate::metrics::{self, ActiveRequestGuard};
+use crate::server::listener::Connection;
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+
+/// How long a shed connection may take to receive the response, before it's closed anyway.
+const SHED_CONNECTION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A request admitted for processing, counted against the limit of its worker until dropped.
+pub struct AdmittedRequest {
+  _guard: Option<ActiveRequestGuard>,
+}
+
+/// Counts the request as processed by the current worker. Returns `None` if the worker is at
+/// `LoadSheddingConfig::max_requests_per_worker`, the request should be answered with `overloaded` then.
+///
+/// Requests served outside of worker threads are never limited.
+pub fn admit_request() -> Option<AdmittedRequest> {
+  let Some(worker) = metrics::current_worker() else {
+    return Some(AdmittedRequest { _guard: None });
+  };
+
+  let guard = worker.try_start_request(get_app_config().load_shedding.max_requests_per_worker);
+
+  if guard.is_none() {
+    metrics::record_shed_request();
+  }
+
+  guard.map(|guard| AdmittedRequest { _guard: Some(guard) })
+}
+
+/// Produces the `503 Service Unavailable` response to a request shed due to overload.
+pub fn overloaded() -> http::Result<Response> {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(b"Service Unavailable"));
+
+  hyper::Response::builder()
+    .status(StatusCode::SERVICE_UNAVAILABLE)
+    .header(header::RETRY_AFTER, retry_after_secs(get_app_config().load_shedding.retry_after))
+    .header(header::CONTENT_TYPE, "text/plain")
+    .body(body)
+}
+
+/// Answers a connection that no worker can take with `503 Service Unavailable` and closes it,
+/// without reading the request. The response is written as HTTP/1.1, which is what nearly all
+/// clients start with, clients speaking HTTP/2 right away just see the connection closed.
+pub async fn shed_connection(mut connection: Connection) {
+  metrics::record_shed_connection();
+
+  let response = overloaded_http1(retry_after_secs(get_app_config().load_shedding.retry_after));
+
+  let _ = tokio::time::timeout(SHED_CONNECTION_TIMEOUT, async {
+    connection.write_all(&response).await?;
+    connection.shutdown().await?;
+
+    // Closing the socket with unread data would reset the connection, discarding the response on the
+    // client's side, so we wait for the client to close it after reading the response.
+    let mut discarded = [0; 1024];
+    while connection.read(&mut discarded).await? > 0 {}
+
+    Ok::<_, std::io::Error>(())
+  })
+  .await;
+}
+
+/// The whole `503 Service Unavailable` HTTP/1.1 response to a shed connection.
+fn overloaded_http1(retry_after_secs: u64) -> Vec<u8> {
+  let body = "Service Unavailable";
+
+  let response = format!(
+    "HTTP/1.1 503 Service Unavailable\r\n\
+     retry-after: {retry_after_secs}\r\n\
+     content-type: text/plain\r\n\
+     content-length: {}\r\n\
+     connection: close\r\n\
+     \r\n\
+     {body}",
+    body.len()
+  );
+
+  response.into_bytes()
+}
+
+fn retry_after_secs(retry_after: Duration) -> u64 {
+  (retry_after.as_secs_f64().ceil() as u64).max(1)
+}
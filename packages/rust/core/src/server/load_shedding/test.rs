@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use super::{overloaded_http1, retry_after_secs};
+
+#[test]
+fn test_overloaded_http1() {
+  let response = String::from_utf8(overloaded_http1(5)).unwrap();
+
+  assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+  assert!(response.contains("\r\nretry-after: 5\r\n"));
+  assert!(response.contains("\r\nconnection: close\r\n"));
+  assert!(response.ends_with("\r\ncontent-length: 19\r\nconnection: close\r\n\r\nService Unavailable"));
+}
+
+#[test]
+fn test_retry_after_secs() {
+  assert_eq!(retry_after_secs(Duration::from_secs(3)), 3);
+  assert_eq!(retry_after_secs(Duration::from_millis(1500)), 2);
+  assert_eq!(retry_after_secs(Duration::ZERO), 1);
+}
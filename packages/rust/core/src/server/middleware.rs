@@ -0,0 +1,64 @@
+use bytes::Bytes;
+use hyper::http::HeaderValue;
+use hyper::{StatusCode, http};
+
+use crate::log;
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+use crate::server::tserver::HyperRequest;
+
+/// The request as seen by a middleware.
+pub type MiddlewareRequest = HyperRequest;
+
+/// The continuation passed to a middleware, producing the response of the rest of the chain –
+/// the middleware of the nested segments and finally the Route Target.
+///
+/// A middleware can short-circuit by returning its own response (e.g. a `Redirect` converted
+/// using `.into()`) without running `next`, or run it and modify the returned response:
+///
+/// ```ignore
+/// pub async fn middleware(request: &ruxy::MiddlewareRequest, next: impl ruxy::Next) -> ruxy::Response {
+///   if !request.headers().contains_key("authorization") {
+///     return ruxy::redirect("/login").into();
+///   }
+///
+///   let mut response = next.run().await;
+///   response.headers_mut().insert("x-frame-options", "DENY".parse().unwrap());
+///   response
+/// }
+/// ```
+pub trait Next: Send {
+  fn run(self) -> impl Future<Output = Response> + Send;
+}
+
+/// A `Next` implementation wrapping a closure. The middleware chain of a route is built from
+/// nested closures at compile time, so running it doesn't require any allocations.
+#[doc(hidden)]
+pub struct NextFn<F>(pub F);
+
+impl<F, Fut> Next for NextFn<F>
+where
+  F: FnOnce() -> Fut + Send,
+  Fut: Future<Output = Response> + Send,
+{
+  fn run(self) -> impl Future<Output = Response> + Send {
+    (self.0)()
+  }
+}
+
+/// Turns the result of a Route Target into the response passed back through the middleware chain.
+/// A response that failed to build is replaced with a generic "Internal Server Error" response.
+#[doc(hidden)]
+pub fn into_response(result: http::Result<Response>) -> Response {
+  result.unwrap_or_else(|e| {
+    log::error("response_build_failed", "failed to build the response", &[("error", (&e).into())]);
+
+    let mut body = ResponseBody::new();
+    body.push(Bytes::from_static(b"Internal Server Error"));
+
+    let mut response = Response::new(body);
+    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+    response.headers_mut().insert(http::header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    response
+  })
+}
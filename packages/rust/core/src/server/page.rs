@@ -1,7 +1,7 @@
 pub mod error;
 mod generator;
 mod loadable;
-mod props;
+pub(crate) mod props;
 
 pub use generator::GeneratorOutput;
 pub use loadable::{Loadable, LoaderOutput};
@@ -2,3 +2,5 @@ pub(crate) mod caught;
 pub(crate) mod downcastable;
 
 pub use caught::{Caught, ThrownBy, ThrownByLoaderKind};
+#[doc(hidden)]
+pub use downcastable::Downcastable;
@@ -1,4 +1,5 @@
 use std::any::TypeId;
+use std::fmt::{Display, Formatter};
 
 /// A trait implemented by the type of the argument which is passed to the error loaders.
 ///
@@ -31,6 +32,10 @@ pub trait Caught {
   /// Returns some useful information about the origin of this error.
   fn thrown_by(&self) -> ThrownBy;
 
+  /// Returns the ID of the request during which this error was thrown, so that it can be
+  /// tied to the log lines of the same request, or shown to the user for support purposes.
+  fn request_id(&self) -> &str;
+
   /// Returns the type name of the contained error for diagnostic and logging purposes.
   ///
   /// The exact contents and format of the string returned are not specified, other than being a best-effort
@@ -77,3 +82,14 @@ pub enum ThrownByLoaderKind {
   /// to the stderr about an "uncaught" error.
   ErrorPage,
 }
+
+impl Display for ThrownByLoaderKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ThrownByLoaderKind::Layout => f.write_str("layout"),
+      ThrownByLoaderKind::Page => f.write_str("page"),
+      ThrownByLoaderKind::NotFoundPage => f.write_str("not_found_page"),
+      ThrownByLoaderKind::ErrorPage => f.write_str("error_page"),
+    }
+  }
+}
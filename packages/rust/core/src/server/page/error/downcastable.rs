@@ -1,14 +1,32 @@
 use std::any::TypeId;
 
+use crate::server::request::request_id::{self, RequestId};
+
 use super::caught::{Caught, ThrownBy};
 
-pub(crate) struct Downcastable<'err> {
-  pub(crate) error: &'err mut dyn std::any::Any,
+/// The error returned from a loader, passed to an error loader as `impl Caught`.
+pub struct Downcastable<'err> {
+  pub(crate) error: &'err mut (dyn std::any::Any + Send),
   pub(crate) thrown_by: ThrownBy,
+  pub(crate) request_id: RequestId,
   pub(crate) type_name: &'static str,
   pub(crate) type_id: TypeId,
 }
 
+impl<'err> Downcastable<'err> {
+  /// Wraps the error in the `slot`, which the error loader can take it out of.
+  #[doc(hidden)]
+  pub fn new<E: Send + 'static>(slot: &'err mut Option<E>, thrown_by: ThrownBy) -> Self {
+    Downcastable {
+      error: slot,
+      thrown_by,
+      request_id: request_id::current().unwrap_or_else(RequestId::generate),
+      type_name: std::any::type_name::<E>(),
+      type_id: TypeId::of::<E>(),
+    }
+  }
+}
+
 impl<'err> Caught for Downcastable<'err> {
   fn get_error<T: 'static>(&self) -> Option<&T> {
     self.error.downcast_ref::<Option<T>>()?.as_ref()
@@ -26,6 +44,10 @@ impl<'err> Caught for Downcastable<'err> {
     self.thrown_by
   }
 
+  fn request_id(&self) -> &str {
+    &self.request_id
+  }
+
   fn error_type_name(&self) -> &'static str {
     self.type_name
   }
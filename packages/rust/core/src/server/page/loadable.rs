@@ -4,6 +4,7 @@ mod output;
 use crate::server::not_found::NotFound;
 use crate::server::page::Props;
 use crate::server::redirect::{redirect, Redirect};
+use crate::server::request::request_id::{self, RequestId};
 
 use super::error::caught::{Caught, ThrownBy, ThrownByLoaderKind};
 use super::error::downcastable::Downcastable;
@@ -58,13 +59,14 @@ async fn macrogenerated() {
   };
 }
 
-async fn handle_error_macrogenerated<E: 'static>(error: E) -> () {
+async fn handle_error_macrogenerated<E: Send + 'static>(error: E) -> () {
   let thrown_by = ThrownBy { route_id: "routes/project/{project_id}/(proj)", loader_kind: ThrownByLoaderKind::Page };
 
   // TODO: Check whether this type_name is actually useful, otherwise try `type_name_of_val(error)`
   let downcastable = Downcastable {
     error: &mut Some(error),
     thrown_by,
+    request_id: request_id::current().unwrap_or_else(RequestId::generate),
     type_name: std::any::type_name::<E>(),
     type_id: std::any::TypeId::of::<E>(),
   };
@@ -2,8 +2,10 @@ use crate::server::not_found::NotFound;
 use super::{LoaderOutput, Props, Redirect};
 
 pub trait RuxyLoadable {
-  type Props: Props;
-  type Error;
+  // Both are held across the await of the error loader, in a future which has to be sendable.
+  // The error is also downcast by the error loader, so it can't borrow anything.
+  type Props: Props + Send;
+  type Error: Send + 'static;
 
   fn into_loader_result(self) -> Result<LoaderOutput<Self::Props>, Self::Error>;
 }
@@ -29,7 +31,7 @@ impl RuxyLoadable for NotFound {
 }
 
 // Allows `Props` to be returned directly
-impl<P: Props> RuxyLoadable for P {
+impl<P: Props + Send> RuxyLoadable for P {
   type Props = P;
   type Error = ();
 
@@ -39,7 +41,7 @@ impl<P: Props> RuxyLoadable for P {
 }
 
 // Allows `LoaderOutput` to be returned directly
-impl<P: Props> RuxyLoadable for LoaderOutput<P> {
+impl<P: Props + Send> RuxyLoadable for LoaderOutput<P> {
   type Props = P;
   type Error = ();
 
@@ -49,7 +51,7 @@ impl<P: Props> RuxyLoadable for LoaderOutput<P> {
 }
 
 // Allows returning full result
-impl<P: Props, E> RuxyLoadable for Result<LoaderOutput<P>, E> {
+impl<P: Props + Send, E: Send + 'static> RuxyLoadable for Result<LoaderOutput<P>, E> {
   type Props = P;
   type Error = E;
 
@@ -1,17 +1,30 @@
+#[cfg(test)]
+mod test;
+
 use std::borrow::Cow;
+use std::sync::OnceLock;
+use std::time::SystemTime;
 
+use crate::server::conditional::ETag;
 use crate::server::not_found::NotFound;
 use crate::server::page::Props;
+use crate::server::page::props::props_to_json;
 use crate::server::redirect::Redirect;
 
 /// The output of a page loader.
 ///
 /// It can contain either Props or a Redirect, but not both.
-/// It can also contain additional headers and cookies to be set for the HTTP response.
+/// It can also contain additional headers and cookies to be set for the HTTP response,
+/// and the validators (an entity tag and a modification time) used to answer conditional
+/// requests without rendering the page.
 pub struct LoaderOutput<P: Props = ()> {
   data: LoaderData<P>,
   headers: Cow<'static, [(Cow<'static, str>, Cow<'static, str>)]>,
   cookies: Cow<'static, [(Cow<'static, str>, Cow<'static, str>)]>,
+  etag: Option<ETag>,
+  last_modified: Option<SystemTime>,
+  props_json: OnceLock<String>,
+  props_etag: OnceLock<ETag>,
 }
 
 impl LoaderOutput {
@@ -26,9 +39,79 @@ impl LoaderOutput {
   }
 }
 
+impl<P: Props> LoaderOutput<P> {
+  /// Sets a weak entity tag identifying the version of the Props, e.g. a revision number or an
+  /// update timestamp of the underlying data.
+  ///
+  /// When a request's `If-None-Match` header matches the tag, Ruxy responds with
+  /// `304 Not Modified` without rendering the page.
+  ///
+  /// Panics if the tag contains double quotes, whitespace or non-ASCII characters.
+  pub fn with_etag(mut self, tag: impl Into<Cow<'static, str>>) -> Self {
+    self.etag = Some(ETag::weak(tag));
+    self
+  }
+
+  /// Sets the time the data behind the Props was last modified, sent in the `Last-Modified` header.
+  ///
+  /// When a request's `If-Modified-Since` header isn't older than the time, Ruxy responds with
+  /// `304 Not Modified` without rendering the page. Requests with `If-None-Match` are validated
+  /// by the entity tag instead.
+  pub fn with_last_modified(mut self, time: SystemTime) -> Self {
+    self.last_modified = Some(time);
+    self
+  }
+
+  // This should only be called from macro expansions.
+  // We don't guarantee the API stability of this method.
+  #[doc(hidden)]
+  pub fn __internal_etag(&self) -> Option<&ETag> {
+    if let Some(etag) = &self.etag {
+      return Some(etag);
+    }
+
+    // Without an explicit tag, the version of the Props is identified by their serialized form
+    let json = self.props_json()?;
+
+    Some(self.props_etag.get_or_init(|| ETag::weak_from_content(json.as_bytes())))
+  }
+
+  // This should only be called from macro expansions.
+  // We don't guarantee the API stability of this method.
+  #[doc(hidden)]
+  pub fn __internal_last_modified(&self) -> Option<SystemTime> {
+    self.last_modified
+  }
+
+  // This should only be called from macro expansions.
+  // We don't guarantee the API stability of this method.
+  #[doc(hidden)]
+  pub fn __internal_hydration_script(&self) -> String {
+    // `<` only occurs in JSON strings, escaping it keeps e.g. `</script>` in the Props from closing the script
+    let json = self.props_json().unwrap_or("null").replace('<', "\\u003c");
+
+    format!("window.__RUXY_DATA__={json};")
+  }
+
+  fn props_json(&self) -> Option<&str> {
+    match &self.data {
+      LoaderData::Props(props) => Some(self.props_json.get_or_init(|| props_to_json(props))),
+      _ => None,
+    }
+  }
+}
+
 impl<P: Props> Default for LoaderOutput<P> {
   fn default() -> Self {
-    LoaderOutput { data: Default::default(), headers: Default::default(), cookies: Default::default() }
+    LoaderOutput {
+      data: Default::default(),
+      headers: Default::default(),
+      cookies: Default::default(),
+      etag: None,
+      last_modified: None,
+      props_json: OnceLock::new(),
+      props_etag: OnceLock::new(),
+    }
   }
 }
 
@@ -0,0 +1,63 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hyper::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+
+use crate::server::conditional::{is_not_modified, not_modified};
+use crate::server::page::LoaderOutput;
+use crate::util::time::UtcDateTime;
+
+#[test]
+fn test_props_etag() {
+  let etag = |output: &LoaderOutput<String>| output.__internal_etag().cloned();
+
+  let first = etag(&"Hello".to_string().into()).unwrap();
+
+  // Derived from the serialized Props, the same Props produce the same weak tag
+  assert!(first.is_weak());
+  assert_eq!(etag(&"Hello".to_string().into()), Some(first.clone()));
+  assert_ne!(etag(&"Hello!".to_string().into()), Some(first));
+
+  // An explicit tag takes precedence over the derived one
+  let explicit = LoaderOutput::from("Hello".to_string()).with_etag("rev-7");
+  assert_eq!(explicit.__internal_etag().map(|etag| etag.tag()), Some("rev-7"));
+
+  // Redirects and Not Found pages have no Props to derive the tag from
+  let not_found: LoaderOutput<String> = crate::server::not_found::NotFound.into();
+  assert_eq!(etag(&not_found), None);
+}
+
+#[test]
+fn test_hydration_script() {
+  let output = LoaderOutput::from("</script><script>alert(1)".to_string());
+  assert_eq!(output.__internal_hydration_script(), r#"window.__RUXY_DATA__="\u003c/script>\u003cscript>alert(1)";"#);
+
+  let not_found: LoaderOutput<String> = crate::server::not_found::NotFound.into();
+  assert_eq!(not_found.__internal_hydration_script(), "window.__RUXY_DATA__=null;");
+}
+
+#[test]
+fn test_last_modified() {
+  let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+  let output = LoaderOutput::from("Hello".to_string()).with_last_modified(modified);
+
+  let since = |time: SystemTime| {
+    let mut headers = HeaderMap::new();
+    let date = UtcDateTime::from_system_time(time).to_http_date();
+    headers.insert(header::IF_MODIFIED_SINCE, HeaderValue::try_from(date).unwrap());
+    headers
+  };
+
+  let is_fresh = |headers: &HeaderMap| {
+    is_not_modified(&Method::GET, headers, output.__internal_etag(), output.__internal_last_modified())
+  };
+
+  // The client's copy is as new as the data, so it's answered with 304 carrying the modification time
+  assert!(is_fresh(&since(modified)));
+
+  let response = not_modified(output.__internal_etag(), output.__internal_last_modified()).unwrap();
+  assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+  assert_eq!(response.headers()[header::LAST_MODIFIED], "Tue, 14 Nov 2023 22:13:20 GMT");
+
+  // The data changed since the client's copy
+  assert!(!is_fresh(&since(modified - Duration::from_secs(60))));
+}
@@ -1,32 +1,119 @@
-pub trait Props {}
+#[cfg(test)]
+mod test;
+
+use std::fmt::Write;
+
+use crate::util::json::write_json_string;
+
+pub trait Props {
+  /// Serializes the Props as JSON, the form in which they're passed to the client page.
+  /// Implemented by `#[derive(Props)]`, which serializes structs with named fields as objects.
+  ///
+  /// This should only be called from macro expansions.
+  /// We don't guarantee the API stability of this method.
+  #[doc(hidden)]
+  fn __internal_write_json(&self, out: &mut String);
+}
 
 // TODO: Macro-generate these
 
-impl Props for bool {}
+impl Props for bool {
+  fn __internal_write_json(&self, out: &mut String) {
+    out.push_str(if *self { "true" } else { "false" });
+  }
+}
+
+macro_rules! impl_props_for_integers {
+  ($($ty:ty),*) => {
+    $(
+      impl Props for $ty {
+        fn __internal_write_json(&self, out: &mut String) {
+          // Writing to String can't fail
+          let _ = write!(out, "{self}");
+        }
+      }
+    )*
+  };
+}
+
+impl_props_for_integers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_props_for_floats {
+  ($($ty:ty),*) => {
+    $(
+      impl Props for $ty {
+        fn __internal_write_json(&self, out: &mut String) {
+          // JSON has no representation of NaN and infinities
+          match self.is_finite() {
+            true => _ = write!(out, "{self}"),
+            false => out.push_str("null"),
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_props_for_floats!(f32, f64);
+
+impl Props for String {
+  fn __internal_write_json(&self, out: &mut String) {
+    write_json_string(out, self);
+  }
+}
+
+impl Props for str {
+  fn __internal_write_json(&self, out: &mut String) {
+    write_json_string(out, self);
+  }
+}
+
+impl Props for () {
+  fn __internal_write_json(&self, out: &mut String) {
+    out.push_str("null");
+  }
+}
+
+impl<T: Props + ?Sized> Props for &T {
+  fn __internal_write_json(&self, out: &mut String) {
+    (**self).__internal_write_json(out);
+  }
+}
 
-impl Props for i8 {}
-impl Props for i16 {}
-impl Props for i32 {}
-impl Props for i64 {}
-impl Props for i128 {}
-impl Props for isize {}
+impl<T: Props> Props for [T] {
+  fn __internal_write_json(&self, out: &mut String) {
+    out.push('[');
 
-impl Props for u8 {}
-impl Props for u16 {}
-impl Props for u32 {}
-impl Props for u64 {}
-impl Props for u128 {}
-impl Props for usize {}
+    for (i, item) in self.iter().enumerate() {
+      if i > 0 {
+        out.push(',');
+      }
 
-impl Props for f32 {}
-impl Props for f64 {}
+      item.__internal_write_json(out);
+    }
 
-impl Props for String {}
-impl Props for str {}
+    out.push(']');
+  }
+}
 
-impl Props for () {}
+impl<T: Props> Props for Vec<T> {
+  fn __internal_write_json(&self, out: &mut String) {
+    self.as_slice().__internal_write_json(out);
+  }
+}
 
-impl<T> Props for &T {}
+impl<T: Props> Props for Option<T> {
+  fn __internal_write_json(&self, out: &mut String) {
+    match self {
+      Some(value) => value.__internal_write_json(out),
+      None => out.push_str("null"),
+    }
+  }
+}
 
-impl<T> Props for [T] {}
-impl<T> Props for Vec<T> {}
+/// Serializes the Props as JSON, see `Props::__internal_write_json`.
+pub fn props_to_json<P: Props + ?Sized>(props: &P) -> String {
+  let mut json = String::new();
+  props.__internal_write_json(&mut json);
+  json
+}
@@ -0,0 +1,32 @@
+use super::{Props, props_to_json};
+
+struct Post {
+  title: String,
+  tags: Vec<&'static str>,
+}
+
+// What `#[derive(Props)]` generates for the struct above
+impl Props for Post {
+  fn __internal_write_json(&self, out: &mut String) {
+    out.push('{');
+    out.push_str("\"title\":");
+    self.title.__internal_write_json(out);
+    out.push_str(",\"tags\":");
+    self.tags.__internal_write_json(out);
+    out.push('}');
+  }
+}
+
+#[test]
+fn test_props_to_json() {
+  assert_eq!(props_to_json(&()), "null");
+  assert_eq!(props_to_json(&true), "true");
+  assert_eq!(props_to_json(&-42i64), "-42");
+  assert_eq!(props_to_json(&1.5f64), "1.5");
+  assert_eq!(props_to_json(&f32::NAN), "null");
+  assert_eq!(props_to_json("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+  assert_eq!(props_to_json(&vec![Some(1u8), None]), "[1,null]");
+
+  let post = Post { title: "Hello".into(), tags: vec!["rust", "web"] };
+  assert_eq!(props_to_json(&post), r#"{"title":"Hello","tags":["rust","web"]}"#);
+}
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod test;
+
+mod forwarded;
+mod protocol;
+mod rewind;
+mod trusted;
+
+use std::io;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use hyper::http::HeaderMap;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::config::ProxyConfig;
+use crate::log;
+use crate::server::listener::ConnectionInfo;
+use crate::server::request::peer::{PeerInfo, Scheme};
+
+pub use forwarded::{Hop, Node, parse_hops};
+pub use protocol::{ProxyHeader, ProxyProtocolError};
+pub use rewind::Rewind;
+pub use trusted::TrustedProxies;
+
+/// How long a proxy has to send the PROXY protocol header after connecting.
+const PROXY_HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longer v2 headers are rejected, the TLVs sent by common proxies fit easily.
+const MAX_PROXY_HEADER_LEN: usize = 4096;
+
+static TRUSTED_PROXIES: OnceLock<TrustedProxies> = OnceLock::new();
+
+/// Parses the trusted proxies, exiting the process if any of them is invalid.
+pub fn init(config: &ProxyConfig) {
+  let trusted = TrustedProxies::parse(config.trusted_proxies).unwrap_or_else(|e| {
+    log::error("invalid_trusted_proxy", "invalid trusted proxy", &[("error", (&e).into())]);
+    std::process::exit(1);
+  });
+
+  if config.proxy_protocol && trusted.is_empty() {
+    log::warn(
+      "proxy_protocol_without_trusted_proxies",
+      "the PROXY protocol is enabled without any trusted proxies, all connections will be closed",
+      &[],
+    );
+  }
+
+  if TRUSTED_PROXIES.set(trusted).is_err() {
+    log::warn("proxy_already_initialized", "trusted proxies already initialized", &[]);
+  }
+}
+
+fn trusted_proxies() -> &'static TrustedProxies {
+  TRUSTED_PROXIES.get_or_init(TrustedProxies::default)
+}
+
+/// Resolves the client of a request from its connection and the forwarding headers set by the trusted proxies.
+pub fn resolve(connection: &ConnectionInfo, headers: &HeaderMap) -> PeerInfo {
+  resolve_with(trusted_proxies(), connection, headers)
+}
+
+/// Walks the hops reported by the proxies from the nearest one, until reaching a node which isn't a trusted proxy.
+pub(crate) fn resolve_with(trusted: &TrustedProxies, connection: &ConnectionInfo, headers: &HeaderMap) -> PeerInfo {
+  let remote_addr = connection.peer_addr;
+  let mut peer = PeerInfo { client_ip: remote_addr.map(|addr| addr.ip()), scheme: Scheme::Http, remote_addr };
+
+  // Only accepted from trusted proxies, without a source address the proxy connected on its own behalf
+  if let Some(header) = connection.proxy {
+    peer.client_ip = header.source.map(|source| source.ip()).or(peer.client_ip);
+
+    if header.tls {
+      peer.scheme = Scheme::Https;
+    }
+  }
+
+  if !trusted.contains(peer.client_ip) {
+    return peer;
+  }
+
+  for hop in parse_hops(headers).into_iter().rev() {
+    if let Some(proto) = hop.proto {
+      peer.scheme = proto;
+    }
+
+    match hop.node {
+      Some(Node::Ip(ip)) => peer.client_ip = Some(ip),
+      Some(Node::Hidden) => {
+        peer.client_ip = None;
+        break;
+      }
+      None => break,
+    }
+
+    if !trusted.contains(peer.client_ip) {
+      break;
+    }
+  }
+
+  peer
+}
+
+/// Reads the PROXY protocol header the connection starts with, returning the connection with the bytes
+/// read past the header put back, and the connection info carrying the header.
+///
+/// Fails if the peer isn't a trusted proxy, or if it doesn't send a valid header in time.
+pub async fn accept<T: AsyncRead + Unpin>(
+  mut connection: T,
+  info: ConnectionInfo,
+) -> io::Result<(Rewind<T>, ConnectionInfo)> {
+  if !trusted_proxies().contains(info.peer_addr.map(|addr| addr.ip())) {
+    return Err(io::Error::new(io::ErrorKind::PermissionDenied, "peer is not a trusted proxy"));
+  }
+
+  let Ok(read) = tokio::time::timeout(PROXY_HEADER_TIMEOUT, read_header(&mut connection)).await else {
+    return Err(io::Error::new(io::ErrorKind::TimedOut, "timed out reading the PROXY protocol header"));
+  };
+
+  let (header, rest) = read?;
+  Ok((Rewind::new(rest, connection), ConnectionInfo { proxy: Some(header), ..info }))
+}
+
+async fn read_header<T: AsyncRead + Unpin>(connection: &mut T) -> io::Result<(ProxyHeader, Bytes)> {
+  let mut buf = BytesMut::with_capacity(256);
+
+  loop {
+    match protocol::parse(&buf) {
+      Ok(Some((header, len))) => return Ok((header, buf.split_off(len).freeze())),
+      Ok(None) if buf.len() >= MAX_PROXY_HEADER_LEN => {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "PROXY protocol header is too long"));
+      }
+      Ok(None) => {}
+      Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+
+    if connection.read_buf(&mut buf).await? == 0 {
+      return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed before the PROXY protocol header"));
+    }
+  }
+}
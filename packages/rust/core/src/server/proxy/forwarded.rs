@@ -0,0 +1,122 @@
+use std::net::{IpAddr, SocketAddr};
+
+use hyper::http::{HeaderMap, HeaderName, header};
+
+use crate::server::request::peer::Scheme;
+
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+static X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+/// A hop of the request as reported by a proxy – the node that sent the request to the proxy,
+/// and the scheme of the request the proxy received.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Hop {
+  /// `None` if the proxy didn't report the node.
+  pub node: Option<Node>,
+  pub proto: Option<Scheme>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Node {
+  Ip(IpAddr),
+  /// `unknown` or an obfuscated identifier, e.g. `_hidden`.
+  Hidden,
+}
+
+/// Parses the hops from the `Forwarded` headers if present, from `X-Forwarded-For` and `X-Forwarded-Proto` otherwise.
+/// The hops are ordered from the client to the nearest proxy.
+pub fn parse_hops(headers: &HeaderMap) -> Vec<Hop> {
+  let values = |name| headers.get_all(name).into_iter().filter_map(|value| value.to_str().ok());
+
+  if headers.contains_key(header::FORWARDED) {
+    return values(&header::FORWARDED)
+      .flat_map(|value| split_unquoted(value, ','))
+      .map(parse_forwarded_element)
+      .collect();
+  }
+
+  let mut hops: Vec<Hop> = values(&X_FORWARDED_FOR)
+    .flat_map(|value| value.split(','))
+    .filter(|node| !node.trim().is_empty())
+    .map(|node| Hop { node: Some(parse_node(node)), proto: None })
+    .collect();
+
+  let protos: Vec<Option<Scheme>> =
+    values(&X_FORWARDED_PROTO).flat_map(|value| value.split(',')).map(|proto| Scheme::parse(proto.trim())).collect();
+
+  // Each proxy appending to both headers keeps them aligned, otherwise only the last protocol is reliable
+  match (protos.len() == hops.len(), protos.last()) {
+    (true, _) => hops.iter_mut().zip(protos).for_each(|(hop, proto)| hop.proto = proto),
+    (false, Some(&proto)) => match hops.last_mut() {
+      Some(hop) => hop.proto = proto,
+      None => hops.push(Hop { node: None, proto }),
+    },
+    (false, None) => {}
+  }
+
+  hops
+}
+
+/// Parses an element of the `Forwarded` header, e.g. `for=192.0.2.60;proto=https;by=203.0.113.43`.
+fn parse_forwarded_element(element: &str) -> Hop {
+  let mut hop = Hop::default();
+
+  for pair in split_unquoted(element, ';') {
+    let Some((key, value)) = pair.split_once('=') else {
+      continue;
+    };
+
+    let value = unquote(value.trim());
+
+    match key.trim() {
+      key if key.eq_ignore_ascii_case("for") => hop.node = Some(parse_node(value)),
+      key if key.eq_ignore_ascii_case("proto") => hop.proto = Scheme::parse(value),
+      _ => {}
+    }
+  }
+
+  hop
+}
+
+/// Parses a node, optionally with a port – `192.0.2.43`, `192.0.2.43:47011`, `[2001:db8::17]:4711`, or `2001:db8::17`.
+fn parse_node(node: &str) -> Node {
+  let node = unquote(node.trim());
+
+  if let Ok(ip) = node.parse::<IpAddr>() {
+    return Node::Ip(ip);
+  }
+
+  if let Ok(address) = node.parse::<SocketAddr>() {
+    return Node::Ip(address.ip());
+  }
+
+  // IPv6 address in brackets without a port
+  match node.strip_prefix('[').and_then(|node| node.strip_suffix(']')).map(str::parse) {
+    Some(Ok(ip)) => Node::Ip(ip),
+    _ => Node::Hidden,
+  }
+}
+
+/// Splits the value on the separator, ignoring the separators in quoted strings.
+fn split_unquoted(value: &str, separator: char) -> impl Iterator<Item = &str> {
+  let mut in_quotes = false;
+  let mut escaped = false;
+
+  value
+    .split(move |c: char| {
+      match c {
+        _ if escaped => escaped = false,
+        '\\' if in_quotes => escaped = true,
+        '"' => in_quotes = !in_quotes,
+        _ => {}
+      }
+
+      c == separator && !in_quotes
+    })
+    .map(str::trim)
+    .filter(|part| !part.is_empty())
+}
+
+fn unquote(value: &str) -> &str {
+  value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value)
+}
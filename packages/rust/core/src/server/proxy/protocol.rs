@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// The signature every PROXY protocol v2 header starts with.
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+const V2_HEADER_LEN: usize = 16;
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// The longest possible v1 header, including the line break.
+const V1_MAX_LEN: usize = 107;
+
+/// The type of the TLV carrying the information about the TLS connection between the client and the proxy.
+const PP2_TYPE_SSL: u8 = 0x20;
+const PP2_CLIENT_SSL: u8 = 0x01;
+
+/// The information about the client reported by a proxy using the PROXY protocol.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct ProxyHeader {
+  /// The address of the client connected to the proxy. `None` for health checks of the proxy itself
+  /// (`LOCAL`, `UNKNOWN`) and for address families other than TCP over IPv4 or IPv6.
+  pub source: Option<SocketAddr>,
+  /// Whether the client connected to the proxy using TLS, only reported by the v2 protocol.
+  pub tls: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ProxyProtocolError {
+  /// The connection doesn't start with a PROXY protocol header.
+  Missing,
+  Invalid(&'static str),
+}
+
+impl Display for ProxyProtocolError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ProxyProtocolError::Missing => write!(f, "connection doesn't start with a PROXY protocol header"),
+      ProxyProtocolError::Invalid(reason) => write!(f, "invalid PROXY protocol header: {reason}"),
+    }
+  }
+}
+
+impl std::error::Error for ProxyProtocolError {}
+
+/// Parses the PROXY protocol header (v1 or v2) at the start of the buffer.
+///
+/// Returns the header along with its length, or `None` if the buffer doesn't hold the whole header yet.
+pub fn parse(buf: &[u8]) -> Result<Option<(ProxyHeader, usize)>, ProxyProtocolError> {
+  if starts_with_partial(buf, V2_SIGNATURE) {
+    return parse_v2(buf);
+  }
+
+  if starts_with_partial(buf, V1_PREFIX) {
+    return parse_v1(buf);
+  }
+
+  Err(ProxyProtocolError::Missing)
+}
+
+/// `PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\n`
+fn parse_v1(buf: &[u8]) -> Result<Option<(ProxyHeader, usize)>, ProxyProtocolError> {
+  let Some(end) = buf.windows(2).position(|window| window == b"\r\n") else {
+    return match buf.len() < V1_MAX_LEN {
+      true => Ok(None),
+      false => Err(ProxyProtocolError::Invalid("v1 header is too long")),
+    };
+  };
+
+  let line = std::str::from_utf8(&buf[V1_PREFIX.len()..end]).map_err(|_| ProxyProtocolError::Invalid("not ASCII"))?;
+  let mut parts = line.split(' ');
+
+  let source = match parts.next() {
+    Some("TCP4" | "TCP6") => {
+      let (Some(source), Some(_), Some(port), Some(_), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+      else {
+        return Err(ProxyProtocolError::Invalid("expected source and destination addresses and ports"));
+      };
+
+      let source = source.parse::<IpAddr>().map_err(|_| ProxyProtocolError::Invalid("invalid source address"))?;
+      let port = port.parse::<u16>().map_err(|_| ProxyProtocolError::Invalid("invalid source port"))?;
+
+      Some(SocketAddr::new(source, port))
+    }
+    Some("UNKNOWN") => None,
+    _ => return Err(ProxyProtocolError::Invalid("unsupported v1 protocol")),
+  };
+
+  Ok(Some((ProxyHeader { source, tls: false }, end + 2)))
+}
+
+/// The binary header – signature, version and command, address family, length, addresses and TLVs.
+fn parse_v2(buf: &[u8]) -> Result<Option<(ProxyHeader, usize)>, ProxyProtocolError> {
+  if buf.len() < V2_HEADER_LEN {
+    return Ok(None);
+  }
+
+  let (version, command) = (buf[12] >> 4, buf[12] & 0x0f);
+  let family = buf[13];
+  let len = V2_HEADER_LEN + u16::from_be_bytes([buf[14], buf[15]]) as usize;
+
+  if version != 2 {
+    return Err(ProxyProtocolError::Invalid("unsupported version"));
+  }
+
+  if buf.len() < len {
+    return Ok(None);
+  }
+
+  let payload = &buf[V2_HEADER_LEN..len];
+
+  let (source, tlvs) = match (command, family) {
+    // LOCAL, sent by the proxy on its own behalf, e.g. health checks
+    (0x0, _) => (None, &[][..]),
+    // PROXY over TCP/IPv4
+    (0x1, 0x11) if payload.len() >= 12 => {
+      let ip = Ipv4Addr::from(<[u8; 4]>::try_from(&payload[..4]).unwrap_or_default());
+      let port = u16::from_be_bytes([payload[8], payload[9]]);
+      (Some(SocketAddr::new(IpAddr::V4(ip), port)), &payload[12..])
+    }
+    // PROXY over TCP/IPv6
+    (0x1, 0x21) if payload.len() >= 36 => {
+      let ip = Ipv6Addr::from(<[u8; 16]>::try_from(&payload[..16]).unwrap_or_default());
+      let port = u16::from_be_bytes([payload[32], payload[33]]);
+      (Some(SocketAddr::new(IpAddr::V6(ip), port)), &payload[36..])
+    }
+    (0x1, 0x11 | 0x21) => return Err(ProxyProtocolError::Invalid("addresses are truncated")),
+    // Other address families (UDP, Unix sockets, unspecified) carry no client IP address
+    (0x1, _) => (None, &[][..]),
+    _ => return Err(ProxyProtocolError::Invalid("unsupported command")),
+  };
+
+  Ok(Some((ProxyHeader { source, tls: is_tls(tlvs) }, len)))
+}
+
+/// Whether the TLVs report that the client connected to the proxy using TLS.
+fn is_tls(mut tlvs: &[u8]) -> bool {
+  while let [kind, len_hi, len_lo, rest @ ..] = tlvs {
+    let len = u16::from_be_bytes([*len_hi, *len_lo]) as usize;
+
+    let Some(value) = rest.get(..len) else {
+      return false;
+    };
+
+    if *kind == PP2_TYPE_SSL {
+      return value.first().is_some_and(|client| client & PP2_CLIENT_SSL != 0);
+    }
+
+    tlvs = &rest[len..];
+  }
+
+  false
+}
+
+/// Whether the buffer starts with the prefix, or with a part of it if the buffer is shorter.
+fn starts_with_partial(buf: &[u8], prefix: &[u8]) -> bool {
+  let len = buf.len().min(prefix.len());
+  buf[..len] == prefix[..len]
+}
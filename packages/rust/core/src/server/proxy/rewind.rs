@@ -0,0 +1,60 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A stream returning the bytes already read from it before continuing with the stream itself.
+///
+/// Reading the PROXY protocol header can read past the header, the bytes belonging to the request are put back this way.
+pub struct Rewind<T> {
+  prefix: Bytes,
+  inner: T,
+}
+
+impl<T> Rewind<T> {
+  pub fn new(prefix: Bytes, inner: T) -> Self {
+    Self { prefix, inner }
+  }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Rewind<T> {
+  fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+    if self.prefix.has_remaining() {
+      let len = self.prefix.len().min(buf.remaining());
+      buf.put_slice(&self.prefix[..len]);
+      self.prefix.advance(len);
+
+      return Poll::Ready(Ok(()));
+    }
+
+    Pin::new(&mut self.inner).poll_read(cx, buf)
+  }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Rewind<T> {
+  fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+    Pin::new(&mut self.inner).poll_write(cx, buf)
+  }
+
+  fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.inner).poll_flush(cx)
+  }
+
+  fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.inner).poll_shutdown(cx)
+  }
+
+  fn poll_write_vectored(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    bufs: &[io::IoSlice<'_>],
+  ) -> Poll<io::Result<usize>> {
+    Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+  }
+
+  fn is_write_vectored(&self) -> bool {
+    self.inner.is_write_vectored()
+  }
+}
@@ -0,0 +1,197 @@
+use std::net::{IpAddr, SocketAddr};
+
+use hyper::http::{HeaderMap, HeaderValue};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::protocol::{ProxyHeader, ProxyProtocolError, parse};
+use super::{Hop, Node, Rewind, TrustedProxies, parse_hops, read_header, resolve_with};
+use crate::server::listener::ConnectionInfo;
+use crate::server::request::peer::{PeerInfo, Scheme};
+
+fn ip(ip: &str) -> IpAddr {
+  ip.parse().unwrap()
+}
+
+fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+  let mut headers = HeaderMap::new();
+
+  for &(name, value) in pairs {
+    headers.append(name, HeaderValue::from_static(value));
+  }
+
+  headers
+}
+
+fn connection(peer_addr: &str) -> ConnectionInfo {
+  ConnectionInfo { peer_addr: Some(peer_addr.parse().unwrap()), proxy: None }
+}
+
+#[test]
+fn test_trusted_proxies() {
+  let trusted = TrustedProxies::parse(&["10.0.0.0/8", "192.168.1.1", "fd00::/8", "unix"]).unwrap();
+
+  assert!(trusted.contains(Some(ip("10.20.30.40"))));
+  assert!(trusted.contains(Some(ip("192.168.1.1"))));
+  assert!(!trusted.contains(Some(ip("192.168.1.2"))));
+  assert!(trusted.contains(Some(ip("fd12::1"))));
+  assert!(!trusted.contains(Some(ip("2001:db8::1"))));
+
+  // IPv4 clients of a listener bound to an IPv6 address are reported as IPv4-mapped IPv6 addresses
+  assert!(trusted.contains(Some(ip("::ffff:10.0.0.1"))));
+  assert!(trusted.contains(None));
+
+  assert!(!TrustedProxies::parse(&["10.0.0.1"]).unwrap().contains(None));
+  assert!(TrustedProxies::parse(&["0.0.0.0/0"]).unwrap().contains(Some(ip("203.0.113.1"))));
+
+  for invalid in ["10.0.0.0/33", "localhost", "10.0.0.0/", "::/129"] {
+    assert!(TrustedProxies::parse(&[invalid]).is_err(), "{invalid}");
+  }
+}
+
+#[test]
+fn test_parse_forwarded() {
+  let hops = parse_hops(&headers(&[
+    ("forwarded", "for=192.0.2.43;proto=https, for=\"[2001:db8:cafe::17]:4711\""),
+    ("forwarded", "for=unknown;by=10.0.0.1, proto=http"),
+  ]));
+
+  assert_eq!(
+    hops,
+    [
+      Hop { node: Some(Node::Ip(ip("192.0.2.43"))), proto: Some(Scheme::Https) },
+      Hop { node: Some(Node::Ip(ip("2001:db8:cafe::17"))), proto: None },
+      Hop { node: Some(Node::Hidden), proto: None },
+      Hop { node: None, proto: Some(Scheme::Http) },
+    ]
+  );
+
+  // Takes precedence over the X-Forwarded-* headers
+  let hops = parse_hops(&headers(&[("forwarded", "For=\"_gazonk\""), ("x-forwarded-for", "192.0.2.43")]));
+  assert_eq!(hops, [Hop { node: Some(Node::Hidden), proto: None }]);
+}
+
+#[test]
+fn test_parse_x_forwarded() {
+  let hops = parse_hops(&headers(&[
+    ("x-forwarded-for", "203.0.113.7, 198.51.100.1:8080"),
+    ("x-forwarded-for", "[2001:db8::1]"),
+    ("x-forwarded-proto", "https, http, HTTP"),
+  ]));
+
+  assert_eq!(
+    hops,
+    [
+      Hop { node: Some(Node::Ip(ip("203.0.113.7"))), proto: Some(Scheme::Https) },
+      Hop { node: Some(Node::Ip(ip("198.51.100.1"))), proto: Some(Scheme::Http) },
+      Hop { node: Some(Node::Ip(ip("2001:db8::1"))), proto: Some(Scheme::Http) },
+    ]
+  );
+
+  // Not aligned, only the protocol reported by the nearest proxy applies
+  let hops = parse_hops(&headers(&[("x-forwarded-for", "203.0.113.7, 10.0.0.2"), ("x-forwarded-proto", "https")]));
+  assert_eq!(hops[0].proto, None);
+  assert_eq!(hops[1].proto, Some(Scheme::Https));
+
+  let hops = parse_hops(&headers(&[("x-forwarded-proto", "https")]));
+  assert_eq!(hops, [Hop { node: None, proto: Some(Scheme::Https) }]);
+}
+
+#[test]
+fn test_resolve() {
+  let trusted = TrustedProxies::parse(&["10.0.0.0/8"]).unwrap();
+  let resolve = |connection: &ConnectionInfo, pairs| resolve_with(&trusted, connection, &headers(pairs));
+
+  // Headers from an untrusted peer are ignored
+  let direct =
+    resolve(&connection("203.0.113.7:5000"), &[("x-forwarded-for", "1.1.1.1"), ("x-forwarded-proto", "https")]);
+  assert_eq!(
+    direct,
+    PeerInfo {
+      client_ip: Some(ip("203.0.113.7")),
+      scheme: Scheme::Http,
+      remote_addr: Some("203.0.113.7:5000".parse().unwrap())
+    }
+  );
+
+  // The client can't spoof the addresses preceding the first untrusted one
+  let proxied = resolve(
+    &connection("10.0.0.1:5000"),
+    &[("x-forwarded-for", "1.1.1.1, 203.0.113.7, 10.0.0.2"), ("x-forwarded-proto", "https")],
+  );
+
+  assert_eq!(proxied.client_ip, Some(ip("203.0.113.7")));
+  assert_eq!(proxied.scheme, Scheme::Https);
+  assert!(proxied.is_secure());
+
+  // A proxy hiding the client
+  let hidden = resolve(&connection("10.0.0.1:5000"), &[("forwarded", "for=_hidden;proto=https")]);
+  assert_eq!((hidden.client_ip, hidden.scheme), (None, Scheme::Https));
+
+  // The source reported using the PROXY protocol is the client, TLS terminated by the proxy makes it `https`
+  let header = ProxyHeader { source: Some("198.51.100.9:40000".parse().unwrap()), tls: true };
+  let proxy_protocol = ConnectionInfo { proxy: Some(header), ..connection("10.0.0.1:5000") };
+
+  let proxied = resolve(&proxy_protocol, &[("x-forwarded-for", "1.1.1.1")]);
+  assert_eq!((proxied.client_ip, proxied.scheme), (Some(ip("198.51.100.9")), Scheme::Https));
+}
+
+#[test]
+fn test_parse_v1() {
+  let header = b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\nGET / HTTP/1.1\r\n";
+  let expected = ProxyHeader { source: Some("192.0.2.1:56324".parse().unwrap()), tls: false };
+  assert_eq!(parse(header), Ok(Some((expected, 45))));
+
+  let header = b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\n";
+  let source: SocketAddr = "[2001:db8::1]:56324".parse().unwrap();
+  assert_eq!(parse(header).unwrap().unwrap().0.source, Some(source));
+
+  assert_eq!(parse(b"PROXY UNKNOWN\r\n"), Ok(Some((ProxyHeader::default(), 15))));
+  assert_eq!(parse(b"PROXY TCP4 192.0.2.1"), Ok(None));
+  assert_eq!(parse(b"PRO"), Ok(None));
+
+  assert!(matches!(parse(b"PROXY TCP4 192.0.2.1 56324\r\n"), Err(ProxyProtocolError::Invalid(_))));
+  assert_eq!(parse(b"GET / HTTP/1.1\r\n"), Err(ProxyProtocolError::Missing));
+}
+
+#[test]
+fn test_parse_v2() {
+  let mut header = b"\r\n\r\n\0\r\nQUIT\n\x21\x11".to_vec();
+  header.extend_from_slice(&20u16.to_be_bytes());
+  header.extend_from_slice(&[192, 0, 2, 1, 198, 51, 100, 1]);
+  header.extend_from_slice(&56324u16.to_be_bytes());
+  header.extend_from_slice(&443u16.to_be_bytes());
+  // PP2_TYPE_SSL with the PP2_CLIENT_SSL flag and a zero verify result
+  header.extend_from_slice(&[0x20, 0x00, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00]);
+
+  let expected = ProxyHeader { source: Some("192.0.2.1:56324".parse().unwrap()), tls: true };
+  assert_eq!(parse(&header), Ok(Some((expected, 36))));
+  assert_eq!(parse(&header[..30]), Ok(None));
+
+  // LOCAL, e.g. a health check of the proxy
+  let local = b"\r\n\r\n\0\r\nQUIT\n\x20\x00\x00\x00";
+  assert_eq!(parse(local), Ok(Some((ProxyHeader::default(), 16))));
+
+  let version_1 = b"\r\n\r\n\0\r\nQUIT\n\x11\x00\x00\x00";
+  assert!(matches!(parse(version_1), Err(ProxyProtocolError::Invalid(_))));
+}
+
+#[tokio::test]
+async fn test_read_header() {
+  let (mut proxy, mut server) = tokio::io::duplex(64);
+
+  let sending = tokio::spawn(async move {
+    // Split across writes, so that the header arrives in pieces
+    proxy.write_all(b"PROXY TCP4 192.0.2.1 198.51.100.1 ").await.unwrap();
+    proxy.write_all(b"56324 443\r\nGET / HTTP/1.1\r\n\r\n").await.unwrap();
+  });
+
+  let (header, rest) = read_header(&mut server).await.unwrap();
+  assert_eq!(header.source, Some("192.0.2.1:56324".parse().unwrap()));
+
+  sending.await.unwrap();
+
+  // The bytes read past the header are read first
+  let mut request = String::new();
+  Rewind::new(rest, server).read_to_string(&mut request).await.unwrap();
+  assert_eq!(request, "GET / HTTP/1.1\r\n\r\n");
+}
@@ -0,0 +1,77 @@
+use std::net::IpAddr;
+
+const UNIX: &str = "unix";
+
+/// The parsed `ProxyConfig::trusted_proxies`.
+#[derive(Debug, Default)]
+pub struct TrustedProxies {
+  ranges: Vec<IpRange>,
+  unix: bool,
+}
+
+/// A CIDR range, a single address being a range with the full prefix length.
+#[derive(Debug, Copy, Clone)]
+struct IpRange {
+  address: IpAddr,
+  prefix_len: u8,
+}
+
+impl TrustedProxies {
+  pub fn parse(entries: &[&str]) -> Result<Self, String> {
+    let mut trusted = TrustedProxies::default();
+
+    for &entry in entries {
+      match entry {
+        UNIX => trusted.unix = true,
+        _ => trusted.ranges.push(IpRange::parse(entry)?),
+      }
+    }
+
+    Ok(trusted)
+  }
+
+  /// Whether the peer is a trusted proxy. `None` stands for a peer connected over a Unix domain socket.
+  pub fn contains(&self, peer: Option<IpAddr>) -> bool {
+    match peer {
+      Some(address) => self.ranges.iter().any(|range| range.contains(address.to_canonical())),
+      None => self.unix,
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ranges.is_empty() && !self.unix
+  }
+}
+
+impl IpRange {
+  fn parse(entry: &str) -> Result<Self, String> {
+    let (address, prefix_len) = entry.split_once('/').map_or((entry, None), |(a, p)| (a, Some(p)));
+
+    let address: IpAddr = address.parse().map_err(|e| format!("invalid trusted proxy '{entry}': {e}"))?;
+    let max_len = if address.is_ipv4() { 32 } else { 128 };
+
+    let prefix_len = match prefix_len {
+      Some(prefix_len) => match prefix_len.parse::<u8>() {
+        Ok(prefix_len) if prefix_len <= max_len => prefix_len,
+        _ => return Err(format!("invalid trusted proxy '{entry}': prefix length must be at most {max_len}")),
+      },
+      None => max_len,
+    };
+
+    Ok(IpRange { address: address.to_canonical(), prefix_len })
+  }
+
+  fn contains(&self, address: IpAddr) -> bool {
+    match (self.address, address) {
+      (IpAddr::V4(range), IpAddr::V4(address)) => {
+        let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+        u32::from(range) & mask == u32::from(address) & mask
+      }
+      (IpAddr::V6(range), IpAddr::V6(address)) => {
+        let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+        u128::from(range) & mask == u128::from(address) & mask
+      }
+      _ => false,
+    }
+  }
+}
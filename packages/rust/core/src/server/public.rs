@@ -0,0 +1,289 @@
+#[cfg(test)]
+mod test;
+
+mod range;
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use hyper::http::{self, HeaderMap, Method, StatusCode, header};
+
+use crate::config::get_app_config;
+use crate::log;
+use crate::server::conditional::{self, ETag};
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+use crate::util::hex::hex_pair_to_decimal;
+use crate::util::time::parse_http_date;
+
+pub use range::{ByteRange, parse_range};
+
+/// A file from the `public/` directory, indexed at build time.
+pub struct PublicFile {
+  /// The URL path of the file, e.g. `/favicon.ico`.
+  pub path: &'static str,
+  pub content_type: &'static str,
+  /// The file embedded into the binary, `None` if it's read from the public directory at runtime.
+  pub embedded: Option<EmbeddedFile>,
+}
+
+/// The contents of a public file embedded into the binary, along with its validators resolved at build time.
+pub struct EmbeddedFile {
+  pub contents: &'static [u8],
+  /// The opaque value of the strong entity tag computed from the contents.
+  pub etag: &'static str,
+  /// The modification time of the file, in seconds since the Unix epoch.
+  pub modified: u64,
+}
+
+/// The index of the `public/` directory, generated at build time.
+pub struct PublicIndex {
+  /// The files, sorted by their path.
+  pub files: &'static [PublicFile],
+}
+
+static INDEX: OnceLock<&'static PublicIndex> = OnceLock::new();
+
+/// The directory the files that aren't embedded are read from, see `StaticFilesConfig::dir`.
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Registers the index of the `public/` directory and resolves the directory the files that aren't embedded
+/// are read from. Called from the generated `main` function, once the app config is registered.
+pub fn register_public_files(index: &'static PublicIndex) {
+  if INDEX.set(index).is_err() {
+    log::warn("public_files_already_registered", "public files already registered", &[]);
+    return;
+  }
+
+  let config = &get_app_config().static_files;
+
+  if !config.enabled || index.files.iter().all(|file| file.embedded.is_some()) {
+    return;
+  }
+
+  let dir = std::path::absolute(config.dir).unwrap_or_else(|_| PathBuf::from(config.dir));
+
+  if !dir.is_dir() {
+    log::warn(
+      "public_dir_not_found",
+      "the public directory doesn't exist, its files will be answered with 404 Not Found",
+      &[("dir", (&dir.display()).into())],
+    );
+  }
+
+  let _ = DIR.set(dir);
+}
+
+/// Finds the public file with the given (percent-encoded) request path.
+pub fn find(path: &str) -> Option<&'static PublicFile> {
+  let index = INDEX.get()?;
+
+  if index.files.is_empty() || !get_app_config().static_files.enabled {
+    return None;
+  }
+
+  let decoded;
+  let path = match path.contains('%') {
+    true => {
+      decoded = percent_decode(path)?;
+      decoded.as_str()
+    }
+    false => path,
+  };
+
+  index.files.binary_search_by(|file| file.path.cmp(path)).ok().map(|i| &index.files[i])
+}
+
+/// The file being served, with its length and validators. Those of the files that aren't embedded
+/// are taken from the file system when the request is served, so that they match the bytes sent.
+struct Representation {
+  len: u64,
+  etag: ETag,
+  last_modified: SystemTime,
+  source: Source,
+}
+
+enum Source {
+  Embedded(&'static [u8]),
+  Disk(std::fs::File),
+}
+
+impl Representation {
+  fn embedded(file: &EmbeddedFile) -> Self {
+    Representation {
+      len: file.contents.len() as u64,
+      etag: ETag::strong(file.etag),
+      last_modified: UNIX_EPOCH + Duration::from_secs(file.modified),
+      source: Source::Embedded(file.contents),
+    }
+  }
+
+  /// Opens the file at the URL path in the directory, off the async runtime.
+  async fn open(dir: &Path, url_path: &'static str) -> io::Result<Self> {
+    let path = dir.join(url_path.trim_start_matches('/'));
+
+    let open = move || {
+      let source = std::fs::File::open(path)?;
+      let metadata = source.metadata()?;
+
+      if !metadata.is_file() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "not a file"));
+      }
+
+      let last_modified = metadata.modified()?;
+      let modified = last_modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+      // Without hashing the contents on every request, the version is identified by the modification time and length
+      let etag = ETag::strong(format!("{:x}.{:x}-{:x}", modified.as_secs(), modified.subsec_nanos(), metadata.len()));
+
+      Ok(Representation { len: metadata.len(), etag, last_modified, source: Source::Disk(source) })
+    };
+
+    tokio::task::spawn_blocking(open).await.unwrap_or_else(|e| Err(io::Error::other(e)))
+  }
+
+  /// Reads `len` bytes of the file starting at `first`, off the async runtime if it isn't embedded.
+  async fn read(self, first: u64, len: u64) -> io::Result<Bytes> {
+    let mut source = match self.source {
+      Source::Embedded(contents) => return Ok(Bytes::from_static(&contents[first as usize..(first + len) as usize])),
+      Source::Disk(source) => source,
+    };
+
+    let read = move || {
+      source.seek(SeekFrom::Start(first))?;
+
+      let mut contents = vec![0; len as usize];
+      source.read_exact(&mut contents)?;
+
+      Ok(Bytes::from(contents))
+    };
+
+    tokio::task::spawn_blocking(read).await.unwrap_or_else(|e| Err(io::Error::other(e)))
+  }
+}
+
+/// Responds with the file, honoring conditional and range requests. `HEAD` requests get the same
+/// headers as `GET` requests, without the body. Other methods are answered with `405 Method Not Allowed`.
+pub async fn respond(file: &'static PublicFile, method: &Method, headers: &HeaderMap) -> http::Result<Response> {
+  if method != Method::GET && method != Method::HEAD {
+    return hyper::Response::builder()
+      .status(StatusCode::METHOD_NOT_ALLOWED)
+      .header(header::ALLOW, "GET, HEAD")
+      .body(ResponseBody::new());
+  }
+
+  let representation = match &file.embedded {
+    Some(embedded) => Representation::embedded(embedded),
+    // The directory is resolved before any request can get here
+    None => match Representation::open(DIR.get().map_or(Path::new(""), PathBuf::as_path), file.path).await {
+      Ok(representation) => representation,
+      // Removed since the build
+      Err(e) if e.kind() == io::ErrorKind::NotFound => {
+        return text_response(StatusCode::NOT_FOUND, "Not Found");
+      }
+      Err(e) => return read_failed(file, &e),
+    },
+  };
+
+  let etag = &representation.etag;
+  let last_modified = Some(representation.last_modified);
+
+  if conditional::is_not_modified(method, headers, Some(etag), last_modified) {
+    return conditional::not_modified(Some(etag), last_modified);
+  }
+
+  let file_len = representation.len;
+
+  let range = headers
+    .get(header::RANGE)
+    .filter(|_| if_range_matches(headers, etag, last_modified))
+    .and_then(|range| parse_range(range.as_bytes(), file_len));
+
+  let mut builder = conditional::with_validators(hyper::Response::builder(), Some(etag), last_modified)
+    .header(header::CONTENT_TYPE, file.content_type)
+    .header(header::CACHE_CONTROL, get_app_config().static_files.cache_control)
+    .header(header::ACCEPT_RANGES, "bytes");
+
+  let (first, len) = match range {
+    None => (0, file_len),
+    Some(ByteRange::Satisfiable { first, last }) => {
+      builder = builder
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_RANGE, format!("bytes {first}-{last}/{file_len}"));
+
+      (first, last - first + 1)
+    }
+    Some(ByteRange::Unsatisfiable) => {
+      return builder
+        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+        .header(header::CONTENT_RANGE, format!("bytes */{file_len}"))
+        .body(ResponseBody::new());
+    }
+  };
+
+  builder = builder.header(header::CONTENT_LENGTH, len);
+
+  if method == Method::HEAD {
+    return builder.body(ResponseBody::new());
+  }
+
+  let contents = match representation.read(first, len).await {
+    Ok(contents) => contents,
+    Err(e) => return read_failed(file, &e),
+  };
+
+  let mut body = ResponseBody::new();
+  body.push(contents);
+
+  builder.body(body)
+}
+
+fn read_failed(file: &PublicFile, e: &io::Error) -> http::Result<Response> {
+  log::error(
+    "public_file_read_failed",
+    "failed to read a public file",
+    &[("path", file.path.into()), ("error", e.into())],
+  );
+
+  text_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+}
+
+fn text_response(status: StatusCode, text: &'static str) -> http::Result<Response> {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(text.as_bytes()));
+
+  hyper::Response::builder().status(status).header(header::CONTENT_TYPE, "text/plain").body(body)
+}
+
+/// Whether the `If-Range` precondition holds, i.e. the range can be served. A missing header holds trivially.
+/// Entity tags are compared using the strong comparison, as a range of a different representation is useless.
+fn if_range_matches(headers: &HeaderMap, etag: &ETag, last_modified: Option<SystemTime>) -> bool {
+  let Some(if_range) = headers.get(header::IF_RANGE).and_then(|value| value.to_str().ok()) else {
+    return true;
+  };
+
+  match ETag::parse(if_range) {
+    Some(tag) => !tag.is_weak() && tag.tag() == etag.tag(),
+    None => parse_http_date(if_range).is_some_and(|date| Some(date) == last_modified),
+  }
+}
+
+/// Decodes percent-encoded octets of the path. Returns `None` if the path isn't valid UTF-8 after decoding.
+fn percent_decode(path: &str) -> Option<String> {
+  let mut decoded = Vec::with_capacity(path.len());
+  let mut bytes = path.bytes();
+
+  while let Some(byte) = bytes.next() {
+    if byte != b'%' {
+      decoded.push(byte);
+      continue;
+    }
+
+    decoded.push(hex_pair_to_decimal(bytes.next()?, bytes.next()?)?);
+  }
+
+  String::from_utf8(decoded).ok()
+}
@@ -0,0 +1,52 @@
+/// A range of bytes requested with the `Range` header, resolved against the length of the representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+  /// The first and the last byte of the range, both inclusive.
+  Satisfiable { first: u64, last: u64 },
+  /// The range doesn't overlap the representation, answered with `416 Range Not Satisfiable`.
+  Unsatisfiable,
+}
+
+/// Parses the value of a `Range` header for a representation of the given length.
+///
+/// Returns `None` when the header should be ignored and the whole representation sent instead.
+/// That's the case for malformed values, units other than bytes, and multiple ranges, which
+/// we don't support (a server is free to ignore them).
+pub fn parse_range(value: &[u8], len: u64) -> Option<ByteRange> {
+  let value = std::str::from_utf8(value).ok()?.trim();
+
+  let unit = value.get(..6)?;
+  let spec = value.get(6..)?.trim();
+
+  if !unit.eq_ignore_ascii_case("bytes=") || spec.contains(',') {
+    return None;
+  }
+
+  let (first, last) = spec.split_once('-')?;
+  let (first, last) = (first.trim(), last.trim());
+
+  // Suffix range, i.e. the last N bytes
+  if first.is_empty() {
+    let suffix: u64 = last.parse().ok()?;
+
+    return Some(match suffix == 0 || len == 0 {
+      true => ByteRange::Unsatisfiable,
+      false => ByteRange::Satisfiable { first: len.saturating_sub(suffix), last: len - 1 },
+    });
+  }
+
+  let first: u64 = first.parse().ok()?;
+  let last: u64 = match last.is_empty() {
+    true => u64::MAX,
+    false => last.parse().ok()?,
+  };
+
+  if last < first {
+    return None;
+  }
+
+  Some(match first >= len {
+    true => ByteRange::Unsatisfiable,
+    false => ByteRange::Satisfiable { first, last: last.min(len - 1) },
+  })
+}
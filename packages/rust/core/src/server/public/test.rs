@@ -0,0 +1,61 @@
+use super::{ByteRange, Representation, parse_range, percent_decode};
+
+#[test]
+fn test_parse_range() {
+  let range = |first, last| Some(ByteRange::Satisfiable { first, last });
+
+  assert_eq!(parse_range(b"bytes=0-99", 1000), range(0, 99));
+  assert_eq!(parse_range(b"bytes=900-", 1000), range(900, 999));
+  assert_eq!(parse_range(b"bytes=900-5000", 1000), range(900, 999));
+  assert_eq!(parse_range(b"bytes=-100", 1000), range(900, 999));
+  assert_eq!(parse_range(b"bytes=-5000", 1000), range(0, 999));
+  assert_eq!(parse_range(b"Bytes= 10 - 20 ", 1000), range(10, 20));
+
+  assert_eq!(parse_range(b"bytes=1000-", 1000), Some(ByteRange::Unsatisfiable));
+  assert_eq!(parse_range(b"bytes=-0", 1000), Some(ByteRange::Unsatisfiable));
+  assert_eq!(parse_range(b"bytes=0-", 0), Some(ByteRange::Unsatisfiable));
+
+  // Ignored, the whole file is sent
+  assert_eq!(parse_range(b"bytes=20-10", 1000), None);
+  assert_eq!(parse_range(b"bytes=0-1,5-6", 1000), None);
+  assert_eq!(parse_range(b"items=0-1", 1000), None);
+  assert_eq!(parse_range(b"bytes=a-b", 1000), None);
+  assert_eq!(parse_range(b"bytes=", 1000), None);
+}
+
+#[test]
+fn test_percent_decode() {
+  assert_eq!(percent_decode("/my%20file.txt").as_deref(), Some("/my file.txt"));
+  assert_eq!(percent_decode("/%C5%BEluv.txt").as_deref(), Some("/žluv.txt"));
+  assert_eq!(percent_decode("/broken%2"), None);
+  assert_eq!(percent_decode("/invalid%FF"), None);
+}
+
+#[tokio::test]
+async fn test_files_read_at_runtime() {
+  let dir = std::env::temp_dir().join(format!("ruxy-public-test-{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  std::fs::write(dir.join("robots.txt"), "Disallow: /admin").unwrap();
+
+  let first = Representation::open(&dir, "/robots.txt").await.unwrap();
+  assert_eq!(first.len, 16);
+  assert!(!first.etag.is_weak());
+
+  let first_etag = first.etag.clone();
+  assert_eq!(first.read(0, 8).await.unwrap(), "Disallow");
+
+  // Edited without a rebuild, the file is served with its current length and validators
+  std::fs::write(dir.join("robots.txt"), "Allow: /").unwrap();
+
+  let edited = Representation::open(&dir, "/robots.txt").await.unwrap();
+  assert_eq!(edited.len, 8);
+  assert_ne!(edited.etag, first_etag);
+  assert_eq!(edited.read(0, 8).await.unwrap(), "Allow: /");
+
+  // Removed since the build
+  std::fs::remove_file(dir.join("robots.txt")).unwrap();
+  let removed = Representation::open(&dir, "/robots.txt").await.err().unwrap();
+  assert_eq!(removed.kind(), std::io::ErrorKind::NotFound);
+
+  std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod test;
+
+mod limit;
+mod memory_store;
+mod store;
+
+use std::sync::LazyLock;
+
+use bytes::Bytes;
+use hyper::http::{self, StatusCode, header};
+
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+use crate::server::tserver::HyperRequest;
+
+pub use limit::{RateLimit, RateLimitAlgorithm, RateLimitKey};
+pub use memory_store::MemoryStore;
+pub use store::{RateLimitDecision, RateLimitStore};
+
+static MEMORY_STORE: LazyLock<MemoryStore> = LazyLock::new(MemoryStore::new);
+
+/// Counts the request against the limit of the subtree with the given ID. Returns the
+/// `429 Too Many Requests` response if the client is over the limit.
+pub async fn check(limit: &RateLimit, subtree_id: &str, request: &HyperRequest) -> Option<http::Result<Response>> {
+  let client_key = limit.key.extract(request)?;
+  let key = format!("{subtree_id}\0{client_key}");
+
+  let store = limit.store.unwrap_or(&*MEMORY_STORE);
+
+  match store.hit(&key, &limit.algorithm).await {
+    RateLimitDecision::Allowed => None,
+    RateLimitDecision::Limited { retry_after } => Some(too_many_requests(retry_after.as_secs_f64().ceil() as u64)),
+  }
+}
+
+fn too_many_requests(retry_after_secs: u64) -> http::Result<Response> {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(b"Too Many Requests"));
+
+  hyper::Response::builder()
+    .status(StatusCode::TOO_MANY_REQUESTS)
+    .header(header::RETRY_AFTER, retry_after_secs.max(1))
+    .header(header::CONTENT_TYPE, "text/plain")
+    .body(body)
+}
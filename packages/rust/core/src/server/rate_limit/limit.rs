@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use hyper::http::{Extensions, HeaderMap, HeaderName};
+
+use crate::server::rate_limit::RateLimitStore;
+use crate::server::request::peer::PeerInfo;
+use crate::server::tserver::HyperRequest;
+
+/// A rate limit, returned from the `rate_limit` function of a segment's `rate_limit.rs` file.
+///
+/// It applies to the segment and all its descendants, which share a single budget per client.
+pub struct RateLimit {
+  pub algorithm: RateLimitAlgorithm,
+  /// How the clients are told apart.
+  pub key: RateLimitKey,
+  /// Where the state of the limit is kept. The in-memory store of this process if `None`.
+  pub store: Option<&'static dyn RateLimitStore>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RateLimitAlgorithm {
+  /// Allows bursts of up to `capacity` requests, after which one request is allowed every `refill_every`.
+  TokenBucket { capacity: u32, refill_every: Duration },
+  /// Allows up to `limit` requests in any `window`. The count is approximated from the counts
+  /// of the current and the previous fixed window, weighted by their overlap with the sliding one.
+  SlidingWindow { limit: u32, window: Duration },
+}
+
+#[derive(Clone, Copy)]
+pub enum RateLimitKey {
+  /// The IP address of the client, as reported by the trusted proxies if there are any.
+  ClientIp,
+  /// The value of a request header, e.g. an API key. Requests without the header aren't limited.
+  Header(&'static str),
+  /// A key computed from the request, e.g. a user ID taken from a session cookie.
+  /// Requests for which the function returns `None` aren't limited.
+  Function(fn(&HyperRequest) -> Option<String>),
+}
+
+impl RateLimitKey {
+  /// Extracts the key of the client from the request. `None` if the request isn't limited.
+  pub fn extract(&self, request: &HyperRequest) -> Option<String> {
+    match self {
+      RateLimitKey::Function(function) => function(request),
+      _ => self.extract_from_parts(request.headers(), request.extensions()),
+    }
+  }
+
+  /// Extracts the key from the headers and extensions of the request. `None` for `Function` keys.
+  pub(crate) fn extract_from_parts(&self, headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+    match self {
+      RateLimitKey::ClientIp => Some(extensions.get::<PeerInfo>()?.client_ip?.to_string()),
+      RateLimitKey::Header(name) => {
+        let name = HeaderName::from_bytes(name.as_bytes()).ok()?;
+        Some(headers.get(name)?.to_str().ok()?.to_string())
+      }
+      RateLimitKey::Function(_) => None,
+    }
+  }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::server::rate_limit::{RateLimitAlgorithm, RateLimitDecision, RateLimitStore};
+
+/// The minimum number of entries of a shard before expired entries are pruned.
+const MIN_PRUNE_LEN: usize = 1024;
+
+/// The in-memory store, keeping the state in the memory of this process.
+///
+/// The state is split into shards by the hash of the key, one shard per core, so that the
+/// worker threads rarely contend for the same lock.
+pub struct MemoryStore {
+  shards: Box<[Mutex<Shard>]>,
+  hasher: RandomState,
+}
+
+#[derive(Default)]
+struct Shard {
+  entries: HashMap<String, Entry>,
+  /// The number of entries at which the expired entries are pruned next.
+  prune_at: usize,
+}
+
+struct Entry {
+  state: State,
+  /// When the entry doesn't affect any decision anymore and can be pruned.
+  expires_at: Instant,
+}
+
+enum State {
+  Bucket { tokens: f64, updated: Instant },
+  Window { start: Instant, current: u32, previous: u32 },
+}
+
+impl MemoryStore {
+  pub fn new() -> Self {
+    let count = std::thread::available_parallelism().map_or(1, |count| count.get());
+    let shards = (0..count).map(|_| Mutex::new(Shard { prune_at: MIN_PRUNE_LEN, ..Shard::default() })).collect();
+
+    Self { shards, hasher: RandomState::new() }
+  }
+
+  /// Counts a request at the given time.
+  pub fn hit_at(&self, key: &str, algorithm: &RateLimitAlgorithm, now: Instant) -> RateLimitDecision {
+    let shard = &self.shards[self.hasher.hash_one(key) as usize % self.shards.len()];
+    let mut shard = shard.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if shard.entries.len() >= shard.prune_at {
+      shard.entries.retain(|_, entry| entry.expires_at > now);
+      shard.prune_at = (shard.entries.len() * 2).max(MIN_PRUNE_LEN);
+    }
+
+    let entry = match shard.entries.get_mut(key) {
+      Some(entry) => entry,
+      None => shard.entries.entry(key.to_string()).or_insert_with(|| Entry::new(algorithm, now)),
+    };
+
+    entry.hit(algorithm, now)
+  }
+}
+
+impl Default for MemoryStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl RateLimitStore for MemoryStore {
+  fn hit<'a>(
+    &'a self,
+    key: &'a str,
+    algorithm: &'a RateLimitAlgorithm,
+  ) -> Pin<Box<dyn Future<Output = RateLimitDecision> + Send + 'a>> {
+    let decision = self.hit_at(key, algorithm, Instant::now());
+    Box::pin(std::future::ready(decision))
+  }
+}
+
+impl Entry {
+  fn new(algorithm: &RateLimitAlgorithm, now: Instant) -> Self {
+    let state = match *algorithm {
+      RateLimitAlgorithm::TokenBucket { capacity, .. } => State::Bucket { tokens: capacity as f64, updated: now },
+      RateLimitAlgorithm::SlidingWindow { .. } => State::Window { start: now, current: 0, previous: 0 },
+    };
+
+    Self { state, expires_at: now }
+  }
+
+  fn hit(&mut self, algorithm: &RateLimitAlgorithm, now: Instant) -> RateLimitDecision {
+    match (&mut self.state, *algorithm) {
+      (State::Bucket { tokens, updated }, RateLimitAlgorithm::TokenBucket { capacity, refill_every }) => {
+        let refill_every = refill_every.as_secs_f64().max(f64::EPSILON);
+        let refilled = now.saturating_duration_since(*updated).as_secs_f64() / refill_every;
+
+        *tokens = (*tokens + refilled).min(capacity as f64);
+        *updated = now;
+
+        // The bucket is full again after this long, then the entry is the same as a new one
+        self.expires_at = now + Duration::from_secs_f64((capacity as f64 - *tokens + 1.0) * refill_every);
+
+        if *tokens < 1.0 {
+          return RateLimitDecision::Limited { retry_after: Duration::from_secs_f64((1.0 - *tokens) * refill_every) };
+        }
+
+        *tokens -= 1.0;
+        RateLimitDecision::Allowed
+      }
+      (State::Window { start, current, previous }, RateLimitAlgorithm::SlidingWindow { limit, window }) => {
+        let windows_passed = now.saturating_duration_since(*start).as_nanos() / window.as_nanos().max(1);
+
+        if windows_passed > 0 {
+          *previous = if windows_passed == 1 { *current } else { 0 };
+          *current = 0;
+          *start += window * windows_passed as u32;
+        }
+
+        let elapsed = now.saturating_duration_since(*start).as_secs_f64() / window.as_secs_f64().max(f64::EPSILON);
+        let previous_weight = 1.0 - elapsed;
+        let count = *previous as f64 * previous_weight + *current as f64;
+
+        self.expires_at = *start + window * 2;
+
+        if count + 1.0 > limit as f64 {
+          return RateLimitDecision::Limited {
+            retry_after: window_retry_after(*previous, *current, limit, elapsed, window),
+          };
+        }
+
+        *current += 1;
+        RateLimitDecision::Allowed
+      }
+      // The algorithm of the limit changed, starting over
+      _ => {
+        *self = Entry::new(algorithm, now);
+        self.hit(algorithm, now)
+      }
+    }
+  }
+}
+
+/// Estimates how long until the weighted count of the sliding window drops enough to allow a request.
+fn window_retry_after(previous: u32, current: u32, limit: u32, elapsed: f64, window: Duration) -> Duration {
+  let until_window_end = window.mul_f64(1.0 - elapsed);
+
+  // The current window alone is over the limit, the count only drops once it becomes the previous one
+  if current + 1 > limit || previous == 0 {
+    return until_window_end;
+  }
+
+  // previous * (1 - (elapsed + t)) + current + 1 <= limit
+  let t = 1.0 - elapsed - (limit - current - 1) as f64 / previous as f64;
+  window.mul_f64(t.clamp(0.0, 1.0 - elapsed))
+}
@@ -0,0 +1,28 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::server::rate_limit::RateLimitAlgorithm;
+
+/// The result of counting a request against a limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitDecision {
+  Allowed,
+  /// The request is over the limit, the client should retry after the duration.
+  Limited {
+    retry_after: Duration,
+  },
+}
+
+/// A store keeping the state of rate limits.
+///
+/// The in-memory store is used by default. Implement this trait to share the limits between
+/// multiple instances of the application, e.g. using Redis.
+pub trait RateLimitStore: Send + Sync {
+  /// Counts a request of the client identified by the key, returning whether it's allowed.
+  /// The key is unique for each client and rate-limited subtree.
+  fn hit<'a>(
+    &'a self,
+    key: &'a str,
+    algorithm: &'a RateLimitAlgorithm,
+  ) -> Pin<Box<dyn Future<Output = RateLimitDecision> + Send + 'a>>;
+}
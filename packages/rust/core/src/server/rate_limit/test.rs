@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use hyper::http::{Extensions, HeaderMap, HeaderValue};
+
+use crate::server::request::peer::PeerInfo;
+
+use super::{MemoryStore, RateLimitAlgorithm, RateLimitDecision, RateLimitKey};
+
+const SECOND: Duration = Duration::from_secs(1);
+
+#[test]
+fn test_token_bucket() {
+  let store = MemoryStore::new();
+  let algorithm = RateLimitAlgorithm::TokenBucket { capacity: 2, refill_every: SECOND };
+  let start = Instant::now();
+
+  assert_eq!(store.hit_at("a", &algorithm, start), RateLimitDecision::Allowed);
+  assert_eq!(store.hit_at("a", &algorithm, start), RateLimitDecision::Allowed);
+  assert_eq!(store.hit_at("a", &algorithm, start), RateLimitDecision::Limited { retry_after: SECOND });
+
+  // Other clients have their own buckets
+  assert_eq!(store.hit_at("b", &algorithm, start), RateLimitDecision::Allowed);
+
+  let half = start + SECOND / 2;
+  assert_eq!(store.hit_at("a", &algorithm, half), RateLimitDecision::Limited { retry_after: SECOND / 2 });
+  assert_eq!(store.hit_at("a", &algorithm, start + SECOND), RateLimitDecision::Allowed);
+
+  // Refilled up to the capacity only
+  let later = start + SECOND * 60;
+  assert_eq!(store.hit_at("a", &algorithm, later), RateLimitDecision::Allowed);
+  assert_eq!(store.hit_at("a", &algorithm, later), RateLimitDecision::Allowed);
+  assert!(matches!(store.hit_at("a", &algorithm, later), RateLimitDecision::Limited { .. }));
+}
+
+#[test]
+fn test_sliding_window() {
+  let store = MemoryStore::new();
+  let window = SECOND * 10;
+  let algorithm = RateLimitAlgorithm::SlidingWindow { limit: 4, window };
+  let start = Instant::now();
+
+  for _ in 0..4 {
+    assert_eq!(store.hit_at("a", &algorithm, start), RateLimitDecision::Allowed);
+  }
+
+  assert_eq!(store.hit_at("a", &algorithm, start), RateLimitDecision::Limited { retry_after: window });
+
+  // A quarter into the next window, the previous one still weighs 3 requests
+  let quarter = start + window + window / 4;
+  assert_eq!(store.hit_at("a", &algorithm, quarter), RateLimitDecision::Allowed);
+
+  let RateLimitDecision::Limited { retry_after } = store.hit_at("a", &algorithm, quarter) else {
+    panic!("expected the request to be limited");
+  };
+
+  // 4 * (1 - elapsed) + 1 + 1 <= 4 at elapsed = 0.5
+  assert_eq!(retry_after.as_millis(), 2500);
+
+  // After two whole windows, nothing is counted anymore
+  let later = start + window * 3;
+  assert_eq!(store.hit_at("a", &algorithm, later), RateLimitDecision::Allowed);
+}
+
+#[test]
+fn test_keys() {
+  let mut headers = HeaderMap::new();
+  headers.insert("x-api-key", HeaderValue::from_static("secret"));
+
+  let mut extensions = Extensions::new();
+  extensions.insert(PeerInfo { client_ip: Some("10.0.0.1".parse().unwrap()), ..PeerInfo::default() });
+
+  let extract = |key: RateLimitKey| key.extract_from_parts(&headers, &extensions);
+
+  assert_eq!(extract(RateLimitKey::ClientIp).as_deref(), Some("10.0.0.1"));
+  assert_eq!(extract(RateLimitKey::Header("x-api-key")).as_deref(), Some("secret"));
+  assert_eq!(extract(RateLimitKey::Header("authorization")), None);
+
+  // Connections on Unix domain sockets without a proxy have no client address
+  assert_eq!(RateLimitKey::ClientIp.extract_from_parts(&headers, &Extensions::new()), None);
+}
@@ -1,5 +1,12 @@
 use std::borrow::Cow;
 
+use hyper::StatusCode;
+use hyper::http::{HeaderValue, header};
+
+use crate::log;
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+
 pub fn redirect(destination: impl Into<Cow<'static, str>>) -> Redirect {
   Redirect { destination: destination.into(), ..Default::default() }
 }
@@ -33,3 +40,23 @@ impl Redirect {
     Redirect { status_code: 308, ..self }
   }
 }
+
+impl From<Redirect> for Response {
+  fn from(redirect: Redirect) -> Self {
+    let mut response = Response::new(ResponseBody::new());
+
+    *response.status_mut() = StatusCode::from_u16(redirect.status_code).unwrap_or(StatusCode::TEMPORARY_REDIRECT);
+
+    match HeaderValue::from_str(&redirect.destination) {
+      Ok(location) => {
+        response.headers_mut().insert(header::LOCATION, location);
+      }
+      Err(_) => {
+        log::error("invalid_redirect", "redirect destination is not a valid header value", &[]);
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+      }
+    }
+
+    response
+  }
+}
@@ -1,6 +1,8 @@
 pub mod cookies;
 pub mod headers;
 pub mod path_params;
+pub mod peer;
+pub mod request_id;
 
 use super::request::path_params::{PathParameters, UntypedPathParams};
 
@@ -8,4 +10,8 @@ pub struct Request<PathParams: PathParameters = UntypedPathParams> {
   pub headers: headers::Headers,
   pub cookies: cookies::Cookies,
   pub path_params: PathParams,
+  /// The ID of this request, taken from the incoming request header or generated.
+  pub request_id: request_id::RequestId,
+  /// The client address and scheme, resolved using the trusted proxies.
+  pub peer: peer::PeerInfo,
 }
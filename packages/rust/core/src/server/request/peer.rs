@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, SocketAddr};
+
+/// The client of a request, resolved from the connection and the trusted proxies in front of the application.
+///
+/// Without any trusted proxies, the client is the directly connected peer and the scheme is always `http`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct PeerInfo {
+  /// The address of the client. `None` if unknown, e.g. for a connection accepted on a Unix domain
+  /// socket without a proxy reporting the client, or when a proxy reports an obfuscated identifier.
+  pub client_ip: Option<IpAddr>,
+  /// The scheme of the request as sent by the client, `https` if it reached a trusted proxy over TLS.
+  pub scheme: Scheme,
+  /// The address of the directly connected peer, which is the nearest proxy if there's one.
+  pub remote_addr: Option<SocketAddr>,
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Scheme {
+  #[default]
+  Http,
+  Https,
+}
+
+impl PeerInfo {
+  /// Returns the peer info of the request, as resolved when the request was received.
+  pub fn of<B>(request: &hyper::Request<B>) -> PeerInfo {
+    request.extensions().get::<PeerInfo>().copied().unwrap_or_default()
+  }
+
+  /// Whether the client sent the request over TLS.
+  pub fn is_secure(&self) -> bool {
+    self.scheme == Scheme::Https
+  }
+}
+
+impl Scheme {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Scheme::Http => "http",
+      Scheme::Https => "https",
+    }
+  }
+
+  /// Parses the scheme, ignoring the case. Only `http` and `https` are recognized.
+  pub fn parse(value: &str) -> Option<Scheme> {
+    match value {
+      _ if value.eq_ignore_ascii_case("http") => Some(Scheme::Http),
+      _ if value.eq_ignore_ascii_case("https") => Some(Scheme::Https),
+      _ => None,
+    }
+  }
+}
+
+impl Display for Scheme {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
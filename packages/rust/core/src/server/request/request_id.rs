@@ -0,0 +1,175 @@
+use std::cell::Cell;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{BuildHasher, RandomState};
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use hyper::http::{HeaderName, HeaderValue};
+
+use crate::config::get_app_config;
+
+/// The maximum length of a request ID. Longer incoming IDs are replaced with generated ones.
+pub const MAX_LEN: usize = 128;
+
+tokio::task_local! {
+  static CURRENT: RequestId;
+}
+
+thread_local! {
+  static RNG_STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(std::thread::current().id()));
+}
+
+static HEADER_NAME: OnceLock<HeaderName> = OnceLock::new();
+
+/// An identifier of a single request, either taken from the incoming request header
+/// (`X-Request-Id` by default) or generated.
+///
+/// The ID is stored inline, so it can be copied around without allocating.
+#[derive(Copy, Clone)]
+pub struct RequestId {
+  len: u8,
+  bytes: [u8; MAX_LEN],
+}
+
+impl RequestId {
+  /// Generates a new random ID, formatted as 32 lowercase hexadecimal digits.
+  ///
+  /// The IDs are unique, but not cryptographically secure – don't use them as secrets.
+  pub fn generate() -> Self {
+    let mut bytes = [0; MAX_LEN];
+
+    for chunk in bytes[..32].chunks_exact_mut(16) {
+      let value = next_random();
+
+      for (position, byte) in chunk.iter_mut().enumerate() {
+        *byte = b"0123456789abcdef"[((value >> (position * 4)) & 0xf) as usize];
+      }
+    }
+
+    Self { len: 32, bytes }
+  }
+
+  /// Creates the ID from the value of an incoming header.
+  ///
+  /// Returns `None` if the value is empty, longer than `MAX_LEN`, or contains anything
+  /// else than visible ASCII characters, so that it can be safely logged and echoed back.
+  pub fn from_header(value: &[u8]) -> Option<Self> {
+    if value.is_empty() || value.len() > MAX_LEN || !value.iter().all(|b| b.is_ascii_graphic()) {
+      return None;
+    }
+
+    let mut bytes = [0; MAX_LEN];
+    bytes[..value.len()].copy_from_slice(value);
+
+    Some(Self { len: value.len() as u8, bytes })
+  }
+
+  pub fn as_str(&self) -> &str {
+    // The ID only ever contains visible ASCII characters, see `generate` and `from_header`.
+    std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+  }
+}
+
+impl Deref for RequestId {
+  type Target = str;
+
+  fn deref(&self) -> &Self::Target {
+    self.as_str()
+  }
+}
+
+impl Display for RequestId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl Debug for RequestId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    Debug::fmt(self.as_str(), f)
+  }
+}
+
+impl PartialEq for RequestId {
+  fn eq(&self, other: &Self) -> bool {
+    self.as_str() == other.as_str()
+  }
+}
+
+impl Eq for RequestId {}
+
+/// Returns the ID of the request being processed by the current task, if any.
+pub fn current() -> Option<RequestId> {
+  CURRENT.try_with(|id| *id).ok()
+}
+
+/// Runs the future with the provided request ID set as the current one.
+pub async fn scope<F: Future>(id: RequestId, future: F) -> F::Output {
+  CURRENT.scope(id, future).await
+}
+
+/// Takes the request ID from the configured header if allowed and valid, or generates a new one.
+pub fn resolve<B>(req: &hyper::Request<B>) -> RequestId {
+  if !get_app_config().request_id.trust_incoming {
+    return RequestId::generate();
+  }
+
+  let incoming = req.headers().get(header_name()).and_then(|value| RequestId::from_header(value.as_bytes()));
+  incoming.unwrap_or_else(RequestId::generate)
+}
+
+/// Sets the request ID header on the response, if configured so.
+pub fn echo<B>(id: &RequestId, response: &mut hyper::Response<B>) {
+  if !get_app_config().request_id.echo {
+    return;
+  }
+
+  // The ID only ever contains visible ASCII characters, which are always valid in header values.
+  if let Ok(value) = HeaderValue::from_str(id) {
+    response.headers_mut().insert(header_name().clone(), value);
+  }
+}
+
+/// Returns the parsed name of the configured request ID header.
+///
+/// ### Panics
+/// This function panics if the configured header name is not a valid HTTP header name.
+fn header_name() -> &'static HeaderName {
+  HEADER_NAME.get_or_init(|| {
+    let header = get_app_config().request_id.header;
+
+    HeaderName::from_bytes(header.as_bytes())
+      .unwrap_or_else(|_| panic!("AppConfig::request_id.header must be a valid header name, value: {header}"))
+  })
+}
+
+/// Returns the next value of the thread-local splitmix64 generator.
+fn next_random() -> u64 {
+  RNG_STATE.with(|state| {
+    let next = state.get().wrapping_add(0x9e3779b97f4a7c15);
+    state.set(next);
+
+    let mut z = next;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  })
+}
+
+#[test]
+fn validates_and_generates_ids() {
+  assert_eq!(RequestId::from_header(b"abc-123").unwrap().as_str(), "abc-123");
+  assert_eq!(RequestId::from_header(&[b'a'; MAX_LEN]).unwrap().len(), MAX_LEN);
+
+  assert!(RequestId::from_header(b"").is_none());
+  assert!(RequestId::from_header(b"has space").is_none());
+  assert!(RequestId::from_header(b"line\nbreak").is_none());
+  assert!(RequestId::from_header(&[b'a'; MAX_LEN + 1]).is_none());
+
+  let first = RequestId::generate();
+  let second = RequestId::generate();
+
+  assert_eq!(first.len(), 32);
+  assert!(first.bytes().all(|b| b.is_ascii_hexdigit()));
+  assert_ne!(first, second);
+}
@@ -1,3 +1,7 @@
 pub mod buffer;
 pub mod chunk;
-pub mod body;
\ No newline at end of file
+pub mod body;
+pub mod precompressed;
+
+/// A response produced by the application.
+pub type Response = hyper::Response<body::ResponseBody>;
@@ -1,23 +1,115 @@
+#[cfg(test)]
+mod test;
+
+mod data;
+mod sender;
+
 use bytes::Bytes;
+use futures_util::Stream;
 use hyper::body::{Frame, SizeHint};
 use std::fmt::{Display, Formatter};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+use super::buffer::ResponseBuffer;
+use super::chunk::{FlatBytes, ResponseChunk};
+use super::precompressed::Precompressed;
+
+pub use data::ResponseData;
+pub use sender::{BodySender, Disconnected};
+
+/// The number of chunks a body holds inline, before spilling to the heap. Bodies with
+/// a known number of chunks reserve the heap for the rest upfront, see `with_capacity`.
+pub const INLINE_CHUNKS: usize = 4;
 
 pub struct ResponseBody {
-  bytes: Vec<Bytes>,
-  tail_cursor: usize,
+  buffer: ResponseBuffer<INLINE_CHUNKS>,
   total_bytes: usize,
+  /// Set when the body consists solely of a precompressed static body.
+  precompressed: Option<&'static Precompressed>,
+  /// Chunks produced over time, sent after the buffered ones.
+  stream: Option<BodyStream>,
+}
+
+enum BodyStream {
+  /// Ends once all the senders are dropped.
+  Channel(mpsc::Receiver<Bytes>),
+  /// Polled only when the connection is ready to send more, so a slow client slows the producer down.
+  Producer(Pin<Box<dyn Stream<Item = Bytes> + Send>>),
 }
 
 impl ResponseBody {
   pub fn new() -> Self {
-    Self { bytes: Vec::new(), tail_cursor: 0, total_bytes: 0 }
+    Self::with_capacity(0)
+  }
+
+  /// Creates a body for the known number of chunks, so that pushing them allocates at most once.
+  pub fn with_capacity(count: usize) -> Self {
+    Self { buffer: ResponseBuffer::with_capacity(count), total_bytes: 0, precompressed: None, stream: None }
+  }
+
+  /// Creates a body streaming the chunks sent using the returned sender. At most `capacity` chunks
+  /// are queued, further sends wait until the client reads the queued ones.
+  ///
+  /// Dropping the body, e.g. when the client disconnects, closes the sender.
+  pub fn channel(capacity: usize) -> (BodySender, Self) {
+    let (sender, receiver) = mpsc::channel(capacity.max(1));
+    let body = Self { stream: Some(BodyStream::Channel(receiver)), ..Self::new() };
+
+    (BodySender::new(sender), body)
+  }
+
+  /// Creates a body pulling its chunks from the async producer.
+  pub fn from_producer(producer: impl Stream<Item = Bytes> + Send + 'static) -> Self {
+    let mut body = Self::new();
+    body.set_producer(producer);
+    body
+  }
+
+  /// Streams the chunks of the async producer after the chunks already pushed, which are sent right away.
+  /// This way the parts of a response known upfront don't wait for the ones that take time to produce.
+  ///
+  /// Replaces any producer or channel set before.
+  pub fn set_producer(&mut self, producer: impl Stream<Item = Bytes> + Send + 'static) {
+    self.stream = Some(BodyStream::Producer(Box::pin(producer)));
+    self.precompressed = None;
+  }
+
+  /// Whether the body is produced over time, so its size isn't known upfront.
+  pub fn is_streaming(&self) -> bool {
+    self.stream.is_some()
+  }
+
+  /// Creates a body from a static body compressed at build time, so that it
+  /// doesn't need to be compressed again when sent to a client accepting compression.
+  pub fn from_precompressed(precompressed: &'static Precompressed) -> Self {
+    let mut body = Self::new();
+    body.push(Bytes::from_static(precompressed.identity));
+    body.precompressed = Some(precompressed);
+    body
   }
 
   pub fn push(&mut self, bytes: Bytes) {
-    self.total_bytes += bytes.len();
-    self.bytes.push(bytes);
+    self.push_chunk(ResponseChunk::Bytes(bytes));
+  }
+
+  /// Pushes a chunk, which can hold any number of nested chunks.
+  pub fn push_chunk(&mut self, chunk: ResponseChunk) {
+    self.total_bytes += chunk.len();
+    self.buffer.push(chunk);
+    self.precompressed = None;
+  }
+
+  /// Iterates over the buffered chunks that haven't been sent yet, with the nested chunks flattened.
+  pub fn chunks(&self) -> impl Iterator<Item = &Bytes> + Clone {
+    let (inline, spilled) = self.buffer.unconsumed();
+    FlatBytes::new(inline.iter().chain(spilled.iter()))
+  }
+
+  /// Returns the precompressed variants of this body, if it consists solely of a precompressed static body.
+  pub fn precompressed(&self) -> Option<&'static Precompressed> {
+    self.precompressed
   }
 }
 
@@ -33,26 +125,53 @@ impl Display for ResponseBodyError {
 impl std::error::Error for ResponseBodyError {}
 
 impl hyper::body::Body for ResponseBody {
-  type Data = Bytes;
+  type Data = ResponseData;
   type Error = ResponseBodyError;
 
-  fn poll_frame(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
-    let index = self.tail_cursor;
+  fn poll_frame(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+    if self.buffer.is_consumed() {
+      let polled = match &mut self.stream {
+        Some(BodyStream::Channel(receiver)) => receiver.poll_recv(cx),
+        Some(BodyStream::Producer(producer)) => producer.as_mut().poll_next(cx),
+        None => return Poll::Ready(None),
+      };
+
+      return match polled {
+        Poll::Ready(Some(bytes)) => Poll::Ready(Some(Ok(Frame::data(ResponseData::from(bytes))))),
+        Poll::Ready(None) => {
+          self.stream = None;
+          Poll::Ready(None)
+        }
+        Poll::Pending => Poll::Pending,
+      };
+    }
+
+    // All the buffered chunks are sent as a single frame, written to the connection using vectored writes
+    let buffer = &mut self.buffer;
+    let mut data = ResponseData::with_capacity(buffer.unconsumed_size() as usize);
 
-    let Some(bytes) = self.bytes.get_mut(index) else {
-      return Poll::Ready(None);
-    };
+    while let Some(chunk) = buffer.consume() {
+      chunk.flatten_into(&mut data);
+    }
 
-    let bytes = std::mem::take(bytes);
-    self.tail_cursor += 1;
-    Poll::Ready(Some(Ok(Frame::data(bytes))))
+    Poll::Ready(Some(Ok(Frame::data(data))))
   }
 
   fn is_end_stream(&self) -> bool {
-    self.tail_cursor == self.bytes.len()
+    self.buffer.is_consumed() && self.stream.is_none()
   }
 
   fn size_hint(&self) -> SizeHint {
-    SizeHint::with_exact(self.total_bytes as u64)
+    match self.stream {
+      Some(_) => {
+        let mut hint = SizeHint::new();
+        hint.set_lower(self.total_bytes as u64);
+        hint
+      }
+      None => SizeHint::with_exact(self.total_bytes as u64),
+    }
   }
 }
@@ -0,0 +1,99 @@
+use std::collections::VecDeque;
+use std::io::IoSlice;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// The data of a single frame of a `ResponseBody`, consisting of any number of chunks.
+///
+/// The chunks aren't copied into a contiguous buffer, they're written to the connection
+/// using vectored writes instead.
+#[derive(Debug, Default)]
+pub struct ResponseData {
+  chunks: VecDeque<Bytes>,
+  remaining: usize,
+}
+
+impl ResponseData {
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self { chunks: VecDeque::with_capacity(capacity), remaining: 0 }
+  }
+
+  /// Returns the number of the remaining chunks.
+  pub fn chunk_count(&self) -> usize {
+    self.chunks.len()
+  }
+}
+
+impl From<Bytes> for ResponseData {
+  fn from(bytes: Bytes) -> Self {
+    let mut data = Self::with_capacity(1);
+    data.extend([bytes]);
+    data
+  }
+}
+
+impl Extend<Bytes> for ResponseData {
+  fn extend<T: IntoIterator<Item = Bytes>>(&mut self, iter: T) {
+    for bytes in iter {
+      self.remaining += bytes.len();
+      self.chunks.push_back(bytes);
+    }
+  }
+}
+
+impl Buf for ResponseData {
+  fn remaining(&self) -> usize {
+    self.remaining
+  }
+
+  fn chunk(&self) -> &[u8] {
+    self.chunks.front().map(Bytes::as_ref).unwrap_or_default()
+  }
+
+  fn advance(&mut self, mut cnt: usize) {
+    assert!(cnt <= self.remaining, "cannot advance past the remaining bytes");
+    self.remaining -= cnt;
+
+    while cnt > 0 {
+      let Some(front) = self.chunks.front_mut() else {
+        return;
+      };
+
+      if cnt < front.len() {
+        front.advance(cnt);
+        return;
+      }
+
+      cnt -= front.len();
+      self.chunks.pop_front();
+    }
+  }
+
+  fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+    let mut filled = 0;
+
+    for (slot, chunk) in dst.iter_mut().zip(self.chunks.iter().filter(|chunk| !chunk.is_empty())) {
+      *slot = IoSlice::new(chunk);
+      filled += 1;
+    }
+
+    filled
+  }
+
+  fn copy_to_bytes(&mut self, len: usize) -> Bytes {
+    // Avoids copying when the requested bytes are exactly the first chunk
+    match self.chunks.front() {
+      Some(front) if front.len() == len => {
+        self.remaining -= len;
+        self.chunks.pop_front().unwrap_or_default()
+      }
+      _ => {
+        assert!(len <= self.remaining, "cannot copy past the remaining bytes");
+
+        let mut out = BytesMut::with_capacity(len);
+        out.put((&mut *self).take(len));
+        out.freeze()
+      }
+    }
+  }
+}
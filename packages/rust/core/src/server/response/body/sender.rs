@@ -0,0 +1,43 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use tokio::sync::mpsc;
+
+/// The sending half of a streaming `ResponseBody`, see `ResponseBody::channel`.
+#[derive(Clone)]
+pub struct BodySender {
+  sender: mpsc::Sender<Bytes>,
+}
+
+/// The client disconnected, or the body was dropped for another reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl BodySender {
+  pub(super) fn new(sender: mpsc::Sender<Bytes>) -> Self {
+    Self { sender }
+  }
+
+  /// Sends the chunk, waiting while the queue of chunks not yet read by the client is full.
+  pub async fn send(&self, bytes: Bytes) -> Result<(), Disconnected> {
+    self.sender.send(bytes).await.map_err(|_| Disconnected)
+  }
+
+  /// Whether the body was dropped, so that nothing sent will ever reach the client.
+  pub fn is_closed(&self) -> bool {
+    self.sender.is_closed()
+  }
+
+  /// Resolves once the body is dropped, e.g. when the client disconnects.
+  pub async fn closed(&self) {
+    self.sender.closed().await
+  }
+}
+
+impl Display for Disconnected {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str("the client disconnected")
+  }
+}
+
+impl std::error::Error for Disconnected {}
@@ -0,0 +1,138 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::{Buf, Bytes};
+use futures_util::{StreamExt, stream};
+use hyper::body::Body;
+
+use super::{INLINE_CHUNKS, ResponseBody, ResponseData};
+use crate::server::response::chunk::ResponseChunk;
+
+async fn next_chunk(body: &mut ResponseBody) -> Option<Bytes> {
+  let frame = std::future::poll_fn(|cx| Pin::new(&mut *body).poll_frame(cx)).await?;
+  let mut data = frame.unwrap().into_data().unwrap();
+  Some(data.copy_to_bytes(data.remaining()))
+}
+
+#[tokio::test]
+async fn test_buffered() {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(b"hello "));
+  body.push(Bytes::from_static(b"world"));
+
+  assert!(!body.is_streaming());
+  assert_eq!(body.size_hint().exact(), Some(11));
+
+  // The buffered chunks are flushed together, as a single vectored write
+  assert_eq!(next_chunk(&mut body).await.unwrap(), "hello world");
+  assert_eq!(next_chunk(&mut body).await, None);
+  assert!(body.is_end_stream());
+}
+
+#[tokio::test]
+async fn test_spilled_nested() {
+  let mut body = ResponseBody::with_capacity(64);
+
+  for i in 0..48 {
+    body.push_chunk(ResponseChunk::from(vec![ResponseChunk::from("<i>"), ResponseChunk::from(i.to_string())]));
+  }
+
+  assert_eq!(body.chunks().count(), 96);
+
+  let expected = (0..48).map(|i| format!("<i>{i}")).collect::<String>();
+  assert_eq!(body.size_hint().exact(), Some(expected.len() as u64));
+  assert_eq!(next_chunk(&mut body).await.unwrap(), expected);
+  assert_eq!(next_chunk(&mut body).await, None);
+}
+
+#[tokio::test]
+async fn test_sized_buffer() {
+  const COUNT: usize = 1000;
+
+  let mut body = ResponseBody::with_capacity(COUNT);
+  let (_, spilled) = body.buffer.unconsumed();
+  let reserved = spilled.capacity();
+  assert!(reserved >= COUNT - INLINE_CHUNKS);
+
+  for i in 0..COUNT {
+    body.push(Bytes::from(format!("{}", i % 10)));
+  }
+
+  // The chunks past the inline buffer went to the heap reserved upfront, without growing it
+  let (inline, spilled) = body.buffer.unconsumed();
+  assert_eq!((inline.len(), spilled.len()), (INLINE_CHUNKS, COUNT - INLINE_CHUNKS));
+  assert_eq!(spilled.capacity(), reserved);
+
+  // The chunks are iterated without collecting them
+  assert_eq!(body.chunks().count(), COUNT);
+  assert_eq!(next_chunk(&mut body).await.unwrap().len(), COUNT);
+  assert!(body.is_end_stream());
+}
+
+#[test]
+fn test_data_vectored() {
+  let mut data = ResponseData::default();
+  data.extend([Bytes::from_static(b"hello"), Bytes::from_static(b", "), Bytes::from_static(b"world")]);
+
+  let mut slices = [std::io::IoSlice::new(&[]); 2];
+  assert_eq!(data.chunks_vectored(&mut slices), 2);
+  assert_eq!(&*slices[1], b", ");
+
+  // A partial write advances into the middle of a chunk
+  data.advance(6);
+  assert_eq!(data.chunk_count(), 2);
+  assert_eq!(data.chunk(), b" ");
+  assert_eq!(data.copy_to_bytes(data.remaining()), " world");
+  assert!(!data.has_remaining());
+}
+
+#[tokio::test]
+async fn test_producer() {
+  let produced = Arc::new(AtomicUsize::new(0));
+  let counter = produced.clone();
+
+  let producer = stream::iter(["loaded", "tail"]).map(move |chunk| {
+    counter.fetch_add(1, Ordering::Relaxed);
+    Bytes::from_static(chunk.as_bytes())
+  });
+
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(b"head"));
+  body.set_producer(producer);
+
+  // Only the buffered part is known upfront
+  assert!(body.is_streaming());
+  assert_eq!(body.size_hint().exact(), None);
+  assert_eq!(body.size_hint().lower(), 4);
+
+  // The buffered chunks go first, without polling the producer
+  assert_eq!(next_chunk(&mut body).await.unwrap(), "head");
+  assert_eq!(produced.load(Ordering::Relaxed), 0);
+
+  // The producer is pulled one chunk at a time, as the client reads
+  assert_eq!(next_chunk(&mut body).await.unwrap(), "loaded");
+  assert_eq!(produced.load(Ordering::Relaxed), 1);
+
+  assert_eq!(next_chunk(&mut body).await.unwrap(), "tail");
+  assert_eq!(next_chunk(&mut body).await, None);
+  assert!(body.is_end_stream());
+}
+
+#[tokio::test]
+async fn test_channel() {
+  let (sender, mut body) = ResponseBody::channel(1);
+
+  sender.send(Bytes::from_static(b"first")).await.unwrap();
+
+  // The queue is full until the client reads the first chunk
+  tokio::select! {
+    biased;
+    _ = sender.send(Bytes::from_static(b"second")) => panic!("expected the send to wait"),
+    _ = std::future::ready(()) => {}
+  }
+
+  assert_eq!(next_chunk(&mut body).await.unwrap(), "first");
+  drop(sender);
+  assert_eq!(next_chunk(&mut body).await, None);
+}
@@ -1,9 +1,13 @@
+#[cfg(test)]
+mod test;
+
+use std::collections::VecDeque;
 use std::mem::MaybeUninit;
 
 use super::chunk::ResponseChunk;
 
 /// Ruxy internal response body buffer.
-/// 
+///
 /// A stack-allocated, fixed-capacity array that supports partial initialization,
 /// up to the limit of 300 kilobytes, where it switches to heap allocation.
 ///
@@ -22,39 +26,59 @@ use super::chunk::ResponseChunk;
 /// avoid dynamic allocation and prefer precise control over memory initialization.
 ///
 /// IMPORTANT: `ResponseBuffer` will allocate on stack up to the limit of 300 kilobytes.
-/// If the maximum capacity exceeds this limit, `ResponseBuffer` will allocate on
-/// the heap to prevent stack overflows.
+/// Chunks pushed past the inline capacity spill to the heap, so the capacity should be
+/// computed using `inline_capacity`, which caps it at the limit to prevent stack overflows.
 pub struct ResponseBuffer<const CAP: usize> {
   items: [MaybeUninit<ResponseChunk>; CAP],
   head: usize,
   tail: usize,
+  /// Chunks pushed after the inline array filled up, consumed after the inline ones.
+  spilled: VecDeque<ResponseChunk>,
 }
 
+/// Returns the inline capacity of a `ResponseBuffer` holding `count` chunks, capped so that
+/// the buffer stays below the stack limit. The rest of the chunks spill to the heap.
+pub const fn inline_capacity(count: usize) -> usize {
+  if count < MAX_STACK_SIZE_COUNT { count } else { MAX_STACK_SIZE_COUNT }
+}
+
+// Thread stack limit is anywhere between 1 to 8 MBs depending on the platform, we'll take
+// the conservative approach and use less than 3rd of the smallest stack limit.
+const MAX_STACK_SIZE_BYTES: usize = 300_000;
+const MAX_STACK_SIZE_COUNT: usize = MAX_STACK_SIZE_BYTES / size_of::<ResponseChunk>();
+
 impl<const CAP: usize> ResponseBuffer<CAP> {
-  // Thread stack limit is anywhere between 1 to 8 MBs depending on the platform, we'll take
-  // the conservative approach and use less than 3rd of the smallest stack limit.
-  const MAX_STACK_SIZE_BYTES: usize = 330_000;
-  const MAX_STACK_SIZE_COUNT: usize = Self::MAX_STACK_SIZE_BYTES / size_of::<ResponseChunk>();  
-  const MAX_STACK_CHUNKS: bool = size_of::<ResponseChunk>().saturating_mul(CAP) > 300_000;
   const UNINIT: MaybeUninit<ResponseChunk> = MaybeUninit::uninit();
 
-  /// Constructs a new `PartialArray` with the given `CAP` capacity.
+  /// Constructs a new `ResponseBuffer` with the given `CAP` inline capacity.
   pub fn new() -> Self {
-    Self { items: [Self::UNINIT; CAP], head: 0, tail: 0 }
+    const { assert!(CAP <= MAX_STACK_SIZE_COUNT, "ResponseBuffer exceeds the stack limit, see `inline_capacity`") };
+
+    Self { items: [Self::UNINIT; CAP], head: 0, tail: 0, spilled: VecDeque::new() }
   }
 
-  /// Write an item to the head of the array.
-  /// This function will panic if the head is at the array's capacity.
+  /// Constructs a new `ResponseBuffer`, reserving the heap for the chunks past the inline capacity upfront.
+  pub fn with_capacity(count: usize) -> Self {
+    let mut buffer = Self::new();
+    buffer.spilled.reserve(count.saturating_sub(CAP));
+    buffer
+  }
+
+  /// Write an item to the head of the array, or to the heap once the array is full.
   pub fn push(&mut self, item: ResponseChunk) {
-    self.items[self.head] = MaybeUninit::new(item);
-    self.head += 1;
+    if self.head < CAP && self.spilled.is_empty() {
+      // SAFETY: we just checked that the head is below the array's capacity
+      unsafe { self.push_unchecked(item) };
+    } else {
+      self.spilled.push_back(item);
+    }
   }
 
   /// Write an item to the head of the array.
   /// This function will NOT check that the head is below the array's capacity.
   ///
   /// ### Safety
-  /// The caller must guarantee the head is below the array's capacity.
+  /// The caller must guarantee the head is below the array's capacity and nothing spilled to the heap yet.
   /// Pushing an item to an array which has its head reached capacity is Undefined Behavior.
   pub unsafe fn push_unchecked(&mut self, item: ResponseChunk) {
     // SAFETY: the caller must uphold the documented safety contract.
@@ -62,12 +86,12 @@ impl<const CAP: usize> ResponseBuffer<CAP> {
     self.head += 1;
   }
 
-  /// Consumes an item from the array and moves its tail cursor.
-  /// This function will return None if the tail is not below the head.
+  /// Consumes an item from the array and moves its tail cursor, continuing with the spilled items.
+  /// This function will return None once everything is consumed.
   pub fn consume(&mut self) -> Option<ResponseChunk> {
     // Check we're operating inside safe bounds
     if self.tail >= self.head {
-      return None;
+      return self.spilled.pop_front();
     }
 
     // SAFETY: we just checked that the tail is below the head
@@ -94,27 +118,30 @@ impl<const CAP: usize> ResponseBuffer<CAP> {
   }
 
   pub fn is_consumed(&self) -> bool {
-    self.tail == self.head
+    self.tail == self.head && self.spilled.is_empty()
   }
 
   pub fn unconsumed_size(&self) -> u64 {
-    (self.head - self.tail) as u64
+    (self.head - self.tail + self.spilled.len()) as u64
   }
-}
 
-impl<const CAP: usize> Drop for ResponseBuffer<CAP> {
-  fn drop(&mut self) {
-    for item in &mut self.items[self.tail..self.head] {
-      // SAFETY: we guarantee the area between `self.tail` and `self.head` is initialized.
-      unsafe { item.assume_init_drop() }
-    }
+  /// Whether any chunks spilled to the heap.
+  pub fn is_spilled(&self) -> bool {
+    !self.spilled.is_empty()
   }
-}
 
-impl<const CAP: usize> std::ops::Deref for ResponseBuffer<CAP> {
-  type Target = [ResponseChunk];
+  /// Iterates over the unconsumed items, the inline ones first.
+  pub fn iter(&self) -> impl Iterator<Item = &ResponseChunk> {
+    self.inline().iter().chain(self.spilled.iter())
+  }
 
-  fn deref(&self) -> &Self::Target {
+  /// Returns the unconsumed inline items and the spilled ones.
+  pub fn unconsumed(&self) -> (&[ResponseChunk], &VecDeque<ResponseChunk>) {
+    (self.inline(), &self.spilled)
+  }
+
+  /// Returns the slice of the unconsumed inline items.
+  fn inline(&self) -> &[ResponseChunk] {
     let filled = &self.items[self.tail..self.head];
 
     // SAFETY: 1. we guarantee the area between `self.tail` and `self.head` is initialized.
@@ -123,13 +150,17 @@ impl<const CAP: usize> std::ops::Deref for ResponseBuffer<CAP> {
   }
 }
 
-impl<const CAP: usize> std::ops::DerefMut for ResponseBuffer<CAP> {
-  /// Dereference to the slice of filled elements (potentially less than `N`).
-  fn deref_mut(&mut self) -> &mut Self::Target {
-    let filled = &mut self.items[self.tail..self.head];
+impl<const CAP: usize> Default for ResponseBuffer<CAP> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
 
-    // SAFETY: 1. we guarantee the area between `self.tail` and `self.head` is initialized.
-    //         2. `MaybeUninit` is `#[repr(transparent)]`, transmute is safe.
-    unsafe { std::mem::transmute(filled) }
+impl<const CAP: usize> Drop for ResponseBuffer<CAP> {
+  fn drop(&mut self) {
+    for item in &mut self.items[self.tail..self.head] {
+      // SAFETY: we guarantee the area between `self.tail` and `self.head` is initialized.
+      unsafe { item.assume_init_drop() }
+    }
   }
 }
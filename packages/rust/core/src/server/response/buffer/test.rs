@@ -0,0 +1,68 @@
+use bytes::Bytes;
+
+use super::{MAX_STACK_SIZE_COUNT, ResponseBuffer, inline_capacity};
+use crate::server::response::chunk::ResponseChunk;
+
+fn text(chunk: ResponseChunk) -> String {
+  let mut out = Vec::new();
+  chunk.for_each_bytes(&mut |bytes| out.extend_from_slice(bytes));
+  String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_inline_capacity() {
+  assert_eq!(inline_capacity(16), 16);
+  assert_eq!(inline_capacity(usize::MAX), MAX_STACK_SIZE_COUNT);
+  assert!(size_of::<ResponseBuffer<{ inline_capacity(usize::MAX) }>>() < 310_000);
+}
+
+#[test]
+fn test_spill() {
+  let mut buffer = ResponseBuffer::<2>::with_capacity(4);
+
+  for chunk in ["a", "b", "c", "d"] {
+    buffer.push(ResponseChunk::from(chunk));
+  }
+
+  assert!(buffer.is_spilled());
+  assert_eq!(buffer.unconsumed_size(), 4);
+  assert_eq!(buffer.iter().map(ResponseChunk::len).sum::<usize>(), 4);
+
+  // The inline chunks are consumed first, the spilled ones after them
+  let consumed = std::iter::from_fn(|| buffer.consume()).map(text).collect::<Vec<_>>();
+  assert_eq!(consumed, ["a", "b", "c", "d"]);
+  assert!(buffer.is_consumed());
+}
+
+#[test]
+fn test_drop_partially_consumed() {
+  let shared = Bytes::from(b"shared".to_vec());
+  let mut buffer = ResponseBuffer::<4>::new();
+
+  for _ in 0..3 {
+    buffer.push(ResponseChunk::from(shared.clone()));
+  }
+
+  drop(buffer.consume());
+  drop(buffer);
+
+  // All the remaining chunks were dropped, releasing their references
+  assert!(shared.is_unique());
+}
+
+#[test]
+fn test_flatten_nested() {
+  let chunk = ResponseChunk::from(vec![
+    ResponseChunk::from("<ul>"),
+    ResponseChunk::from(vec![ResponseChunk::from("<li>"), ResponseChunk::from(""), ResponseChunk::from("</li>")]),
+    ResponseChunk::from(String::from("</ul>")),
+  ]);
+
+  assert_eq!(chunk.len(), 18);
+
+  let mut flattened = Vec::new();
+  chunk.flatten_into(&mut flattened);
+
+  // Empty chunks are skipped, so they don't take a slot of the vectored write
+  assert_eq!(flattened, ["<ul>", "<li>", "</li>", "</ul>"]);
+}
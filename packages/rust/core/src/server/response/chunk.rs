@@ -1,3 +1,5 @@
+use bytes::Bytes;
+
 /// This is enough to represent every value the user can return from handlers.
 /// `Vec` here doesn't mean a Vector returned by the user, but rather anything
 /// we don't know the static count of ahead.
@@ -9,3 +11,96 @@ pub enum ResponseChunk {
   Bytes(bytes::Bytes),
   Vec(Vec<ResponseChunk>),
 }
+
+impl ResponseChunk {
+  /// The total length of the chunk in bytes, including all the nested chunks.
+  pub fn len(&self) -> usize {
+    match self {
+      ResponseChunk::Bytes(bytes) => bytes.len(),
+      ResponseChunk::Vec(chunks) => chunks.iter().map(ResponseChunk::len).sum(),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Calls the function for each of the bytes, flattening the nested chunks in order.
+  pub fn for_each_bytes<'a>(&'a self, f: &mut impl FnMut(&'a Bytes)) {
+    match self {
+      ResponseChunk::Bytes(bytes) => f(bytes),
+      ResponseChunk::Vec(chunks) => chunks.iter().for_each(|chunk| chunk.for_each_bytes(f)),
+    }
+  }
+
+  /// Moves the bytes into the collection, flattening the nested chunks in order. Empty bytes are skipped.
+  pub fn flatten_into(self, out: &mut impl Extend<Bytes>) {
+    match self {
+      ResponseChunk::Bytes(bytes) if bytes.is_empty() => {}
+      ResponseChunk::Bytes(bytes) => out.extend([bytes]),
+      ResponseChunk::Vec(chunks) => chunks.into_iter().for_each(|chunk| chunk.flatten_into(out)),
+    }
+  }
+}
+
+/// Iterates over the bytes of the chunks, flattening the nested chunks in order.
+/// Allocates only when the chunks are nested.
+#[derive(Clone)]
+pub struct FlatBytes<'a, I> {
+  chunks: I,
+  nested: Vec<std::slice::Iter<'a, ResponseChunk>>,
+}
+
+impl<'a, I: Iterator<Item = &'a ResponseChunk>> FlatBytes<'a, I> {
+  pub fn new(chunks: I) -> Self {
+    Self { chunks, nested: Vec::new() }
+  }
+}
+
+impl<'a, I: Iterator<Item = &'a ResponseChunk>> Iterator for FlatBytes<'a, I> {
+  type Item = &'a Bytes;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let chunk = match self.nested.last_mut() {
+        Some(nested) => match nested.next() {
+          Some(chunk) => chunk,
+          None => {
+            self.nested.pop();
+            continue;
+          }
+        },
+        None => self.chunks.next()?,
+      };
+
+      match chunk {
+        ResponseChunk::Bytes(bytes) => return Some(bytes),
+        ResponseChunk::Vec(chunks) => self.nested.push(chunks.iter()),
+      }
+    }
+  }
+}
+
+impl From<Bytes> for ResponseChunk {
+  fn from(bytes: Bytes) -> Self {
+    ResponseChunk::Bytes(bytes)
+  }
+}
+
+impl From<&'static str> for ResponseChunk {
+  fn from(text: &'static str) -> Self {
+    ResponseChunk::Bytes(Bytes::from_static(text.as_bytes()))
+  }
+}
+
+impl From<String> for ResponseChunk {
+  fn from(text: String) -> Self {
+    ResponseChunk::Bytes(Bytes::from(text))
+  }
+}
+
+impl From<Vec<ResponseChunk>> for ResponseChunk {
+  fn from(chunks: Vec<ResponseChunk>) -> Self {
+    ResponseChunk::Vec(chunks)
+  }
+}
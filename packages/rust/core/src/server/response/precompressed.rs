@@ -0,0 +1,22 @@
+use crate::config::ContentEncoding;
+
+/// A static response body along with its variants compressed at build time.
+///
+/// Variants of encodings that were disabled at build time are empty.
+pub struct Precompressed {
+  pub identity: &'static [u8],
+  pub gzip: &'static [u8],
+  pub brotli: &'static [u8],
+  pub zstd: &'static [u8],
+}
+
+impl Precompressed {
+  /// Returns the variant compressed with the provided encoding, empty if not available.
+  pub fn variant(&self, encoding: ContentEncoding) -> &'static [u8] {
+    match encoding {
+      ContentEncoding::Gzip => self.gzip,
+      ContentEncoding::Brotli => self.brotli,
+      ContentEncoding::Zstd => self.zstd,
+    }
+  }
+}
@@ -0,0 +1,143 @@
+mod nonce;
+
+#[cfg(test)]
+mod test;
+
+use std::sync::OnceLock;
+
+use bytes::Bytes;
+use hyper::http::{HeaderMap, HeaderName, HeaderValue, header};
+
+use crate::config::SecurityHeadersConfig;
+use crate::log;
+use crate::server::response::Response;
+
+pub use nonce::CspNonce;
+
+const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+tokio::task_local! {
+  static CURRENT: Option<CspNonce>;
+}
+
+static SECURITY_HEADERS: OnceLock<SecurityHeaders> = OnceLock::new();
+
+/// Validates the configured security headers, exiting the process if any of them is invalid.
+pub fn init(config: &SecurityHeadersConfig) {
+  let security_headers = SecurityHeaders::parse(config).unwrap_or_else(|e| {
+    log::error("invalid_security_header", "invalid security header", &[("error", (&e).into())]);
+    std::process::exit(1);
+  });
+
+  if SECURITY_HEADERS.set(security_headers).is_err() {
+    log::warn("security_headers_already_initialized", "security headers already initialized", &[]);
+  }
+}
+
+fn security_headers() -> &'static SecurityHeaders {
+  SECURITY_HEADERS.get_or_init(SecurityHeaders::default)
+}
+
+/// Generates a nonce for a request, if the configured Content Security Policy uses one.
+pub fn generate_nonce() -> Option<CspNonce> {
+  matches!(security_headers().csp, Some(Csp::WithNonce(_))).then(CspNonce::generate)
+}
+
+/// Returns the nonce of the request currently being served, `None` if the Content Security Policy
+/// doesn't use nonces or if called outside of a request.
+///
+/// Use it to allow your own inline scripts, e.g. `<script nonce="{nonce}">`.
+pub fn current() -> Option<CspNonce> {
+  CURRENT.try_with(|nonce| *nonce).ok().flatten()
+}
+
+/// Runs the future with the nonce as the current one.
+pub fn scope<F: Future>(nonce: Option<CspNonce>, future: F) -> impl Future<Output = F::Output> {
+  CURRENT.scope(nonce, future)
+}
+
+/// Returns the opening script tag, carrying the nonce of the current request if there's one.
+/// Every script tag emitted by Ruxy is opened with this, e.g. the one passing the Props to the page.
+pub fn script_open_tag() -> Bytes {
+  match current() {
+    Some(nonce) => Bytes::from(format!("<script nonce=\"{nonce}\">")),
+    None => Bytes::from_static(b"<script>"),
+  }
+}
+
+/// Adds the configured security headers to the response, keeping the ones already set.
+pub fn apply(nonce: Option<&CspNonce>, response: &mut Response) {
+  insert_headers(security_headers(), nonce, response.headers_mut());
+}
+
+/// The configured security headers, validated when the server starts.
+#[derive(Default)]
+struct SecurityHeaders {
+  csp: Option<Csp>,
+  headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+enum Csp {
+  Static(HeaderValue),
+  /// The policy contains `{nonce}` placeholders, replaced for each request.
+  WithNonce(&'static str),
+}
+
+impl SecurityHeaders {
+  fn parse(config: &SecurityHeadersConfig) -> Result<Self, String> {
+    if !config.enabled {
+      return Ok(Self::default());
+    }
+
+    let csp = match config.content_security_policy {
+      Some(csp) if csp.contains(NONCE_PLACEHOLDER) => {
+        // The nonce only contains base64 characters, so any nonce is as valid as this one
+        let sample = csp.replace(NONCE_PLACEHOLDER, &CspNonce::generate());
+        parse_value(header::CONTENT_SECURITY_POLICY, &sample)?;
+
+        Some(Csp::WithNonce(csp))
+      }
+      Some(csp) => Some(Csp::Static(parse_value(header::CONTENT_SECURITY_POLICY, csp)?)),
+      None => None,
+    };
+
+    let headers = [
+      (header::STRICT_TRANSPORT_SECURITY, config.strict_transport_security),
+      (header::X_CONTENT_TYPE_OPTIONS, config.content_type_options.then_some("nosniff")),
+      (header::REFERRER_POLICY, config.referrer_policy),
+      (header::X_FRAME_OPTIONS, config.frame_options.map(|options| options.as_str())),
+    ];
+
+    let headers = headers
+      .into_iter()
+      .filter_map(|(name, value)| value.map(|value| parse_value(name.clone(), value).map(|value| (name, value))))
+      .collect::<Result<_, _>>()?;
+
+    Ok(Self { csp, headers })
+  }
+}
+
+fn parse_value(name: HeaderName, value: &str) -> Result<HeaderValue, String> {
+  HeaderValue::from_str(value).map_err(|_| format!("the value of {name} isn't a valid header value: {value:?}"))
+}
+
+fn insert_headers(security_headers: &SecurityHeaders, nonce: Option<&CspNonce>, headers: &mut HeaderMap) {
+  let csp = match (&security_headers.csp, nonce) {
+    (Some(Csp::Static(csp)), _) => Some(csp.clone()),
+    // Validated at startup, see `SecurityHeaders::parse`
+    (Some(Csp::WithNonce(csp)), Some(nonce)) => HeaderValue::try_from(csp.replace(NONCE_PLACEHOLDER, nonce)).ok(),
+    _ => None,
+  };
+
+  if let Some(csp) = csp {
+    insert_if_absent(headers, header::CONTENT_SECURITY_POLICY, csp);
+  }
+
+  for (name, value) in &security_headers.headers {
+    insert_if_absent(headers, name.clone(), value.clone());
+  }
+}
+
+fn insert_if_absent(headers: &mut HeaderMap, name: HeaderName, value: HeaderValue) {
+  headers.entry(name).or_insert(value);
+}
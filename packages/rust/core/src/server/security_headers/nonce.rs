@@ -0,0 +1,76 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::Deref;
+
+/// The length of a nonce – 16 random bytes encoded as unpadded base64.
+const LEN: usize = 22;
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A Content Security Policy nonce, generated for each request from the OS random number generator.
+///
+/// The nonce is stored inline, so it can be copied around without allocating.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct CspNonce {
+  bytes: [u8; LEN],
+}
+
+impl CspNonce {
+  /// Generates a new nonce.
+  ///
+  /// ### Panics
+  /// This function panics if the OS random number generator is unavailable.
+  pub fn generate() -> Self {
+    let mut random = [0; 16];
+    getrandom::fill(&mut random).expect("OS random number generator should be available");
+
+    let mut bytes = [0; LEN];
+    let mut position = 0;
+
+    for chunk in random.chunks(3) {
+      let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| group | (*byte as u32) << (16 - i * 8));
+
+      // A full chunk of 3 bytes is encoded as 4 characters, the last chunk of 1 byte as 2 characters
+      for i in 0..=chunk.len() {
+        bytes[position] = BASE64_ALPHABET[(group >> (18 - i * 6)) as usize & 0x3f];
+        position += 1;
+      }
+    }
+
+    Self { bytes }
+  }
+
+  pub fn as_str(&self) -> &str {
+    // The nonce only contains characters of the base64 alphabet
+    std::str::from_utf8(&self.bytes).unwrap_or_default()
+  }
+}
+
+impl Deref for CspNonce {
+  type Target = str;
+
+  fn deref(&self) -> &Self::Target {
+    self.as_str()
+  }
+}
+
+impl Display for CspNonce {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl Debug for CspNonce {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    Debug::fmt(self.as_str(), f)
+  }
+}
+
+#[test]
+fn generates_base64_nonces() {
+  let first = CspNonce::generate();
+  let second = CspNonce::generate();
+
+  assert_eq!(first.len(), LEN);
+  assert!(first.bytes().all(|b| BASE64_ALPHABET.contains(&b)));
+  assert_ne!(first, second);
+}
@@ -0,0 +1,46 @@
+use hyper::http::{HeaderMap, HeaderValue, header};
+
+use crate::config::{FrameOptions, SecurityHeadersConfig};
+
+use super::{CspNonce, SecurityHeaders, insert_headers};
+
+#[test]
+fn test_insert_configured_headers() {
+  let config = SecurityHeadersConfig {
+    content_security_policy: Some("script-src 'nonce-{nonce}'"),
+    strict_transport_security: Some("max-age=600"),
+    frame_options: Some(FrameOptions::Deny),
+    ..SecurityHeadersConfig::default()
+  };
+
+  let security_headers = SecurityHeaders::parse(&config).unwrap();
+
+  let nonce = CspNonce::generate();
+  let mut headers = HeaderMap::new();
+  headers.insert(header::REFERRER_POLICY, HeaderValue::from_static("no-referrer"));
+
+  insert_headers(&security_headers, Some(&nonce), &mut headers);
+
+  assert_eq!(headers[header::CONTENT_SECURITY_POLICY], format!("script-src 'nonce-{nonce}'").as_str());
+  assert_eq!(headers[header::STRICT_TRANSPORT_SECURITY], "max-age=600");
+  assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+  assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+  assert_eq!(headers[header::REFERRER_POLICY], "no-referrer");
+}
+
+#[test]
+fn test_invalid_headers() {
+  let csp = SecurityHeadersConfig { content_security_policy: Some("script-src 'self'\n"), ..Default::default() };
+  assert!(SecurityHeaders::parse(&csp).err().unwrap().contains("content-security-policy"));
+
+  let csp =
+    SecurityHeadersConfig { content_security_policy: Some("script-src 'nonce-{nonce}'\0"), ..Default::default() };
+  assert!(SecurityHeaders::parse(&csp).is_err());
+
+  let referrer = SecurityHeadersConfig { referrer_policy: Some("no-referrer\r\n"), ..Default::default() };
+  assert!(SecurityHeaders::parse(&referrer).err().unwrap().contains("referrer-policy"));
+
+  // Disabled headers aren't sent, so they aren't validated either
+  let disabled = SecurityHeadersConfig { enabled: false, ..referrer };
+  assert!(SecurityHeaders::parse(&disabled).unwrap().headers.is_empty());
+}
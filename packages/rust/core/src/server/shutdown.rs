@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+use crate::log;
+use crate::metrics;
+
+/// Set to `true` once graceful shutdown starts. Watch channels don't depend on any particular
+/// runtime, so the connections on all the worker threads can wait for it.
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::Sender::new(false));
+
+/// The number of tasks outliving their connection task, see `hold`.
+static HELD: AtomicU64 = AtomicU64::new(0);
+
+/// Keeps graceful shutdown waiting while alive.
+pub struct ShutdownHold(());
+
+/// Starts graceful shutdown – listeners stop accepting new connections, the readiness
+/// endpoint starts failing and open connections are closed once their requests are served.
+pub fn trigger() {
+  SHUTDOWN.send_replace(true);
+}
+
+/// Whether graceful shutdown has started.
+pub fn is_shutting_down() -> bool {
+  *SHUTDOWN.borrow()
+}
+
+/// Resolves once graceful shutdown starts.
+pub async fn wait() {
+  let mut receiver = SHUTDOWN.subscribe();
+
+  // The sender is static, so it's never dropped and this can't fail.
+  let _ = receiver.wait_for(|shutting_down| *shutting_down).await;
+}
+
+/// Resolves once the process is asked to terminate, by `SIGTERM` or `SIGINT` (Ctrl+C).
+pub async fn signal() {
+  #[cfg(unix)]
+  {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+      Ok(terminate) => terminate,
+      Err(e) => {
+        log::warn("signal_handler_failed", "failed to listen for SIGTERM", &[("error", (&e).into())]);
+        return std::future::pending().await;
+      }
+    };
+
+    tokio::select! {
+      _ = terminate.recv() => {},
+      _ = tokio::signal::ctrl_c() => {},
+    }
+  }
+
+  #[cfg(not(unix))]
+  if let Err(e) = tokio::signal::ctrl_c().await {
+    log::warn("signal_handler_failed", "failed to listen for Ctrl+C", &[("error", (&e).into())]);
+    std::future::pending().await
+  }
+}
+
+/// Makes graceful shutdown wait until the returned guard is dropped. Meant for tasks taking over
+/// a connection from its connection task, e.g. upgraded WebSockets, which are otherwise not counted.
+pub fn hold() -> ShutdownHold {
+  HELD.fetch_add(1, Ordering::Relaxed);
+  ShutdownHold(())
+}
+
+impl Drop for ShutdownHold {
+  fn drop(&mut self) {
+    HELD.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+/// Waits until all the open connections are closed, or until the timeout elapses.
+pub async fn drain(timeout: Duration) {
+  let open_connections = || metrics::active_tasks() + HELD.load(Ordering::Relaxed);
+
+  match wait_until_drained(timeout, open_connections).await {
+    true => log::info("shutdown_completed", "all connections closed", &[]),
+    false => log::warn(
+      "shutdown_timed_out",
+      "connections still open after the shutdown timeout, exiting anyway",
+      &[("open_connections", open_connections().into())],
+    ),
+  }
+}
+
+/// Polls the number of open connections until there are none, returning `false` if the timeout elapses first.
+async fn wait_until_drained(timeout: Duration, open_connections: impl Fn() -> u64) -> bool {
+  let deadline = Instant::now() + timeout;
+
+  while open_connections() > 0 {
+    if Instant::now() >= deadline {
+      return false;
+    }
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+  }
+
+  true
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use super::{HELD, hold, wait_until_drained};
+
+#[test]
+fn test_hold_is_counted() {
+  assert_eq!(HELD.load(Ordering::Relaxed), 0);
+
+  let first = hold();
+  let second = hold();
+  assert_eq!(HELD.load(Ordering::Relaxed), 2);
+
+  drop(first);
+  drop(second);
+  assert_eq!(HELD.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test]
+async fn test_wait_until_drained() {
+  assert!(wait_until_drained(Duration::from_secs(5), || 0).await);
+
+  // Connections closing one by one
+  let open = Arc::new(AtomicU64::new(3));
+  let closing = open.clone();
+
+  tokio::spawn(async move {
+    while closing.load(Ordering::Relaxed) > 0 {
+      tokio::time::sleep(Duration::from_millis(20)).await;
+      closing.fetch_sub(1, Ordering::Relaxed);
+    }
+  });
+
+  let started = Instant::now();
+  assert!(wait_until_drained(Duration::from_secs(5), || open.load(Ordering::Relaxed)).await);
+  assert!(started.elapsed() < Duration::from_secs(1));
+
+  // A connection that never closes
+  let started = Instant::now();
+  assert!(!wait_until_drained(Duration::from_millis(100), || 1).await);
+  assert!(started.elapsed() >= Duration::from_millis(100));
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod test;
+
+mod event;
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use hyper::http::{HeaderValue, header};
+
+use crate::server::response::Response;
+use crate::server::response::body::{BodySender, Disconnected, ResponseBody};
+use crate::server::shutdown;
+
+pub use event::Event;
+
+/// The number of events queued for a slow client before `SseSender::send` starts waiting.
+const QUEUE_CAPACITY: usize = 16;
+
+/// A Server-Sent Events response, streaming events produced by a task over time.
+///
+/// ```ignore
+/// pub async fn handler(request: &ruxy::HandlerRequest) -> ruxy::Response {
+///   let mut next_id = ruxy::sse::last_event_id(request).and_then(|id| id.parse().ok()).unwrap_or(0) + 1;
+///
+///   ruxy::Sse::new().spawn(move |events| async move {
+///     while let Some(notification) = next_notification().await {
+///       let event = ruxy::Event::new().id(next_id.to_string()).event("notification").data(notification);
+///
+///       if events.send(event).await.is_err() {
+///         break; // The client disconnected
+///       }
+///
+///       next_id += 1;
+///     }
+///   })
+/// }
+/// ```
+pub struct Sse {
+  keep_alive: Option<Duration>,
+}
+
+/// Sends the events of a Server-Sent Events response.
+pub struct SseSender {
+  body: BodySender,
+}
+
+impl Sse {
+  /// Creates the response, sending a keep-alive comment every 15 seconds.
+  pub fn new() -> Self {
+    Self { keep_alive: Some(Duration::from_secs(15)) }
+  }
+
+  /// Sets how often a comment is sent, so that proxies don't close the idle connection. `None` disables it.
+  pub fn keep_alive(mut self, interval: Option<Duration>) -> Self {
+    self.keep_alive = interval;
+    self
+  }
+
+  /// Spawns the producer of the events on the current worker thread and returns the response streaming them.
+  ///
+  /// The stream ends when the producer returns. The producer is cancelled when the client
+  /// disconnects or graceful shutdown starts.
+  pub fn spawn<F, Fut>(self, producer: F) -> Response
+  where
+    F: FnOnce(SseSender) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    let (sender, body) = ResponseBody::channel(QUEUE_CAPACITY);
+    let keep_alive_sender = sender.clone();
+
+    let producer = producer(SseSender { body: sender });
+
+    tokio::task::spawn(async move {
+      tokio::select! {
+        _ = producer => {},
+        _ = keep_alive(&keep_alive_sender, self.keep_alive) => {},
+        _ = shutdown::wait() => {},
+      }
+    });
+
+    let mut response = Response::new(body);
+
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    // Disables response buffering in nginx, which would hold the events back
+    headers.insert("x-accel-buffering", HeaderValue::from_static("no"));
+
+    response
+  }
+}
+
+impl Default for Sse {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl SseSender {
+  /// Sends the event, waiting while too many events are queued for a slow client.
+  pub async fn send(&self, event: Event) -> Result<(), Disconnected> {
+    self.body.send(event.to_bytes()).await
+  }
+
+  /// Whether the client disconnected.
+  pub fn is_closed(&self) -> bool {
+    self.body.is_closed()
+  }
+
+  /// Resolves once the client disconnects.
+  pub async fn closed(&self) {
+    self.body.closed().await
+  }
+}
+
+/// Returns the ID of the last event the client received, sent when it reconnects after losing the connection.
+pub fn last_event_id<B>(request: &hyper::Request<B>) -> Option<&str> {
+  request.headers().get("last-event-id")?.to_str().ok()
+}
+
+/// Sends a comment every interval, until the client disconnects.
+async fn keep_alive(sender: &BodySender, interval: Option<Duration>) {
+  let Some(interval) = interval else {
+    return sender.closed().await;
+  };
+
+  let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+
+  loop {
+    interval.tick().await;
+
+    if sender.send(Bytes::from_static(b":\n\n")).await.is_err() {
+      return;
+    }
+  }
+}
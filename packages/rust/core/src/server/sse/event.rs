@@ -0,0 +1,88 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use bytes::Bytes;
+
+/// A single Server-Sent Event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+  id: Option<String>,
+  event: Option<String>,
+  data: Option<String>,
+  retry: Option<Duration>,
+  comment: Option<String>,
+}
+
+impl Event {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// A comment, ignored by the client.
+  pub fn comment(text: impl Into<String>) -> Self {
+    Self { comment: Some(text.into()), ..Self::default() }
+  }
+
+  /// Sets the ID, which the client sends back in the `Last-Event-ID` header when it reconnects.
+  /// Line breaks are removed, as they would end the field.
+  pub fn id(mut self, id: impl Into<String>) -> Self {
+    self.id = Some(remove_line_breaks(id.into()));
+    self
+  }
+
+  /// Sets the type of the event, `message` if not set. Line breaks are removed, as they would end the field.
+  pub fn event(mut self, event: impl Into<String>) -> Self {
+    self.event = Some(remove_line_breaks(event.into()));
+    self
+  }
+
+  /// Sets the data of the event. Multi-line data is sent as multiple `data` fields.
+  pub fn data(mut self, data: impl Into<String>) -> Self {
+    self.data = Some(data.into());
+    self
+  }
+
+  /// Sets how long the client waits before reconnecting after losing the connection.
+  pub fn retry(mut self, retry: Duration) -> Self {
+    self.retry = Some(retry);
+    self
+  }
+
+  /// Serializes the event in the `text/event-stream` format.
+  pub fn to_bytes(&self) -> Bytes {
+    let mut out = String::new();
+
+    if let Some(comment) = &self.comment {
+      comment.lines().for_each(|line| _ = writeln!(out, ":{line}"));
+    }
+
+    if let Some(id) = &self.id {
+      _ = writeln!(out, "id:{id}");
+    }
+
+    if let Some(event) = &self.event {
+      _ = writeln!(out, "event:{event}");
+    }
+
+    if let Some(retry) = self.retry {
+      _ = writeln!(out, "retry:{}", retry.as_millis());
+    }
+
+    if let Some(data) = &self.data {
+      // `lines` doesn't yield an empty trailing line, which still has to be sent
+      data.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)).for_each(|line| {
+        _ = writeln!(out, "data:{line}");
+      });
+    }
+
+    out.push('\n');
+    Bytes::from(out)
+  }
+}
+
+fn remove_line_breaks(value: String) -> String {
+  match value.contains(['\r', '\n']) {
+    true => value.replace(['\r', '\n'], ""),
+    false => value,
+  }
+}
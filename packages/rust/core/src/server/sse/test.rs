@@ -0,0 +1,60 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+use bytes::Buf;
+use hyper::body::Body;
+
+use super::{Event, Sse};
+use crate::server::response::Response;
+
+#[test]
+fn test_event_serialization() {
+  let event = Event::new().id("7").event("notification").data("hello").retry(Duration::from_secs(3));
+  assert_eq!(event.to_bytes(), "id:7\nevent:notification\nretry:3000\ndata:hello\n\n");
+
+  assert_eq!(Event::new().data("a\nb\r\nc").to_bytes(), "data:a\ndata:b\ndata:c\n\n");
+  assert_eq!(Event::new().data("").to_bytes(), "data:\n\n");
+  assert_eq!(Event::comment("ping").to_bytes(), ":ping\n\n");
+
+  // Line breaks would end the field and let the value inject other fields
+  assert_eq!(Event::new().id("1\ndata:x").event("a\r\nb").to_bytes(), "id:1data:x\nevent:ab\n\n");
+}
+
+#[tokio::test]
+async fn test_streaming() {
+  let response = Sse::new().keep_alive(None).spawn(|events| async move {
+    events.send(Event::new().data("first")).await.unwrap();
+    events.send(Event::new().data("second")).await.unwrap();
+  });
+
+  assert_eq!(response.headers()["content-type"], "text/event-stream");
+  assert_eq!(response.body().size_hint().exact(), None);
+
+  assert_eq!(read_body(response).await, ["data:first\n\n", "data:second\n\n"]);
+}
+
+#[tokio::test]
+async fn test_disconnect() {
+  let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+
+  let response = Sse::new().keep_alive(None).spawn(|_events| async move {
+    let _sender = sender;
+    std::future::pending::<()>().await;
+  });
+
+  // The producer is cancelled once the body is dropped, dropping the sender without sending anything
+  drop(response);
+  assert!(receiver.await.is_err());
+}
+
+async fn read_body(response: Response) -> Vec<String> {
+  let mut body = response.into_body();
+  let mut chunks = Vec::new();
+
+  while let Some(frame) = std::future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+    let mut data = frame.unwrap().into_data().unwrap();
+    chunks.push(String::from_utf8(data.copy_to_bytes(data.remaining()).to_vec()).unwrap());
+  }
+
+  chunks
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test;
+
+use std::any::TypeId;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::future::pending;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use bytes::Bytes;
+use hyper::http::{self, StatusCode, header};
+
+use crate::config::get_app_config;
+use crate::log;
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+use crate::server::tserver::HandlerResult;
+
+/// Timeouts of the segment defining them and all its descendants, returned from the `timeout`
+/// function of a `timeout.rs` file. The default values are the global ones from `TimeoutConfig`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RouteTimeout {
+  /// How long the route may take to produce its response, including middleware and loaders.
+  pub request: Option<Duration>,
+  /// How long each loader of the route may take.
+  pub loader: Option<Duration>,
+}
+
+impl Default for RouteTimeout {
+  fn default() -> Self {
+    let config = &get_app_config().timeout;
+    RouteTimeout { request: config.request, loader: config.loader }
+  }
+}
+
+/// The error passed to the nearest error loader when a loader doesn't finish before its deadline.
+/// The loader that timed out is reported by `Caught::thrown_by`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LoaderTimeout {
+  /// The deadline the loader missed.
+  pub after: Duration,
+}
+
+impl Display for LoaderTimeout {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "loader timed out after {}ms", self.after.as_millis())
+  }
+}
+
+impl Error for LoaderTimeout {}
+
+/// Runs the loader with the deadline, or without any if it's `None`. The loader is cancelled
+/// (dropped) as soon as the deadline passes.
+pub async fn deadline<F: Future>(limit: Option<Duration>, loader: F) -> Result<F::Output, LoaderTimeout> {
+  let Some(after) = limit else {
+    return Ok(loader.await);
+  };
+
+  tokio::time::timeout(after, loader).await.map_err(|_| LoaderTimeout { after })
+}
+
+/// Runs the responder of the route with the request timeout, or without any if it's `None`.
+/// Once the timeout passes, the responder is cancelled and the request is answered with
+/// `503 Service Unavailable`, logging the `request_timed_out` event.
+pub async fn request<F>(limit: Option<Duration>, route_id: &'static str, responder: F) -> HandlerResult
+where
+  F: Future<Output = HandlerResult>,
+{
+  let Some(after) = limit else {
+    return responder.await;
+  };
+
+  match tokio::time::timeout(after, responder).await {
+    Ok(result) => result,
+    Err(_) => {
+      log::warn(
+        "request_timed_out",
+        "route didn't respond before the request timeout",
+        &[("route_id", route_id.into()), ("timeout_ms", (&after.as_millis()).into())],
+      );
+
+      HandlerResult { response: service_unavailable(), route_id: Some(route_id) }
+    }
+  }
+}
+
+/// Completes once the limit passes without the connection receiving any request, never completes otherwise.
+///
+/// Covers what happens on a connection before its first request is read – the detection of the
+/// protocol, and the HTTP/2 preface and settings – which no per-request timeout applies to.
+pub async fn first_request(limit: Option<Duration>, received: &AtomicBool) {
+  let Some(after) = limit else {
+    return pending().await;
+  };
+
+  tokio::time::sleep(after).await;
+
+  if received.load(Ordering::Relaxed) {
+    pending().await
+  }
+}
+
+/// The status of the response to an error returned from a loader, `504 Gateway Timeout`
+/// for a `LoaderTimeout` and `500 Internal Server Error` for anything else.
+pub fn error_status<E: 'static>(_error: &E) -> StatusCode {
+  match TypeId::of::<E>() == TypeId::of::<LoaderTimeout>() {
+    true => StatusCode::GATEWAY_TIMEOUT,
+    false => StatusCode::INTERNAL_SERVER_ERROR,
+  }
+}
+
+fn service_unavailable() -> http::Result<Response> {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(b"Service Unavailable"));
+
+  http::Response::builder()
+    .status(StatusCode::SERVICE_UNAVAILABLE)
+    .header(header::CONTENT_TYPE, "text/plain")
+    .body(body)
+}
@@ -0,0 +1,62 @@
+use std::future::pending;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use hyper::StatusCode;
+
+use crate::server::response::body::ResponseBody;
+use crate::server::tserver::HandlerResult;
+
+use super::{LoaderTimeout, deadline, error_status, first_request, request};
+
+const DEADLINE: Duration = Duration::from_millis(10);
+
+#[tokio::test]
+async fn test_deadline() {
+  assert_eq!(deadline(Some(DEADLINE), async { 42 }).await, Ok(42));
+  assert_eq!(deadline(None, async { 42 }).await, Ok(42));
+
+  let timed_out = deadline(Some(DEADLINE), pending::<()>()).await;
+  assert_eq!(timed_out, Err(LoaderTimeout { after: DEADLINE }));
+}
+
+#[tokio::test]
+async fn test_request_timeout() {
+  let result = request(Some(DEADLINE), "slow_route", pending::<HandlerResult>()).await;
+
+  assert_eq!(result.route_id, Some("slow_route"));
+  assert_eq!(result.response.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+
+  let responder = async {
+    let response = hyper::Response::builder().status(StatusCode::OK).body(ResponseBody::new());
+    HandlerResult { response, route_id: Some("fast_route") }
+  };
+
+  let result = request(Some(DEADLINE), "fast_route", responder).await;
+  assert_eq!(result.response.unwrap().status(), StatusCode::OK);
+}
+
+#[test]
+fn test_error_status() {
+  assert_eq!(error_status(&LoaderTimeout { after: DEADLINE }), StatusCode::GATEWAY_TIMEOUT);
+  assert_eq!(error_status(&std::io::Error::other("upstream failed")), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn test_loader_timeout_display() {
+  assert_eq!(LoaderTimeout { after: Duration::from_millis(1500) }.to_string(), "loader timed out after 1500ms");
+}
+
+#[tokio::test]
+async fn test_first_request() {
+  // Connections without any request are closed once the limit passes
+  let idle = AtomicBool::new(false);
+  assert!(tokio::time::timeout(DEADLINE * 5, first_request(Some(DEADLINE), &idle)).await.is_ok());
+
+  // Once a request is received, the per-request timeouts take over
+  let served = AtomicBool::new(true);
+  assert!(tokio::time::timeout(DEADLINE * 5, first_request(Some(DEADLINE), &served)).await.is_err());
+
+  let unlimited = AtomicBool::new(false);
+  assert!(tokio::time::timeout(DEADLINE * 5, first_request(None, &unlimited)).await.is_err());
+}
@@ -1,16 +1,38 @@
+#[cfg(test)]
+mod test;
+
 use std::future::Future;
-use std::net::SocketAddr;
 use std::process::Termination;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
+use bytes::Bytes;
+use hyper::body::Body;
 use hyper::http::HeaderValue;
 use hyper::service::service_fn;
-use hyper::{Response, StatusCode, http};
-use hyper_util::rt::TokioIo;
+use hyper::{Method, Response, StatusCode, http};
+use hyper_util::rt::{TokioIo, TokioTimer};
 use hyper_util::server::conn;
-use tokio::net::{TcpListener, TcpStream};
+use tokio::io::{AsyncRead, AsyncWrite};
 
-use crate::config::get_app_config;
+use crate::config::{StaticFilesPriority, TimeoutConfig, get_app_config};
+use crate::log;
+use crate::metrics;
+use crate::runtime::rt::{self, Dispatcher};
+use crate::server::access_log::{self, PendingAccessLogRecord};
+use crate::server::compression;
+use crate::server::health;
+use crate::server::listener::{self, Connection, ConnectionInfo, ListenAddress, Listener};
+use crate::server::load_shedding;
+use crate::server::page::error::ThrownBy;
+use crate::server::proxy;
+use crate::server::public;
+use crate::server::request::request_id::{self, RequestId};
+use crate::server::security_headers;
+use crate::server::shutdown;
+use crate::server::timeout;
 
 use crate::server::response::body::ResponseBody;
 
@@ -21,53 +43,126 @@ pub type HyperRequest = hyper::Request<hyper::body::Incoming>;
 /// expanded macro to call into the runtime and vice versa.
 #[allow(async_fn_in_trait)]
 pub trait Server: Send + 'static {
+  /// How many accepted connections can wait for a worker thread. Connections accepted while
+  /// the queue is full are answered with `503 Service Unavailable` and closed, see `LoadSheddingConfig`.
+  //
   // This default is pretty arbitrary, we'll adjust it in time when we have more
   // data about real production usage and the performance of the server. Anyway,
   // this can be easily overridden by the user.
   const REQUEST_QUEUE_SIZE: usize = 10;
 
   fn start() {
-    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
-
-    rt.block_on(async {
-      let app_config = get_app_config();
-      
-      for (index, address) in app_config.addresses.iter().enumerate() {
-        // Addresses are validated in the parsing step, so we can just unwrap here.
-        let address = SocketAddr::from_str(address).unwrap();
-
-        // We don't want to start listening in here, we want to spawn a new task for that:
-        // https://docs.rs/tokio/latest/tokio/runtime/struct.Runtime.html#non-worker-future
-        let handle = tokio::task::spawn(Self::listen(address));
-
-        if index == app_config.addresses.len() - 1 {
-          // TODO: Logs
-          handle.await.expect("error starting the server");
-        }
-      }
+    let app_config = get_app_config();
+
+    access_log::init(&app_config.access_log);
+    metrics::init(&app_config.metrics);
+    proxy::init(&app_config.proxy);
+    security_headers::init(&app_config.security_headers);
+
+    let addresses = listener::listen_addresses(app_config.addresses).unwrap_or_else(|e| {
+      log::error("invalid_listen_address", "invalid listen address", &[("error", (&e).into())]);
+      std::process::exit(1);
     });
-  }
 
-  fn listen(address: SocketAddr) -> impl Future<Output = ()> + Send {
-    async move {
-      let listener = match TcpListener::bind(address).await {
-        Ok(listener) => listener,
-        Err(e) => {
-          // TODO: Logs
-          eprintln!("[ERR] failed to bind to '{address}': {e}");
-          std::process::exit(1);
+    let addresses = addresses.into_iter().map(|address| {
+      ListenAddress::from_str(&address).unwrap_or_else(|e| {
+        log::error("invalid_listen_address", "invalid listen address", &[("error", (&e).into())]);
+        std::process::exit(1);
+      })
+    });
+
+    let addresses: Vec<ListenAddress> = addresses.collect();
+
+    rt::create(
+      Self::REQUEST_QUEUE_SIZE,
+      |dispatcher| async move {
+        let mut handles = Vec::with_capacity(addresses.len());
+
+        for address in addresses {
+          let listener = match Listener::bind(&address).await {
+            Ok(listener) => listener,
+            Err(e) => {
+              log::error(
+                "listener_bind_failed",
+                "failed to bind listener",
+                &[("address", (&address).into()), ("error", (&e).into())],
+              );
+
+              std::process::exit(1);
+            }
+          };
+
+          log::info("listener_bound", "listening for connections", &[("address", (&address).into())]);
+
+          handles.push(tokio::task::spawn(Self::listen(listener, dispatcher.clone())));
         }
-      };
 
+        health::set_listeners_bound();
+
+        let abort_handles: Vec<_> = handles.iter().map(|handle| handle.abort_handle()).collect();
+
+        let listeners = async {
+          for handle in handles {
+            if let Err(e) = handle.await {
+              log::error("listener_failed", "listener stopped unexpectedly", &[("error", (&e).into())]);
+              std::process::exit(1);
+            }
+          }
+        };
+
+        tokio::select! {
+          _ = listeners => {},
+          _ = shutdown::signal() => {},
+        }
+
+        health::set_shutting_down();
+
+        // Load balancers stop sending new requests once the readiness fails, until then they're still served.
+        // Another signal skips the delay.
+        let shutdown_delay = app_config.health.shutdown_delay;
+
+        if !shutdown_delay.is_zero() {
+          log::info(
+            "shutdown_delayed",
+            "failing readiness before shutting down",
+            &[("delay_ms", (&shutdown_delay.as_millis()).into())],
+          );
+
+          tokio::select! {
+            _ = tokio::time::sleep(shutdown_delay) => {},
+            _ = shutdown::signal() => {},
+          }
+        }
+
+        log::info("shutdown_started", "shutting down gracefully", &[]);
+
+        shutdown::trigger();
+
+        for handle in abort_handles {
+          handle.abort();
+        }
+
+        shutdown::drain(app_config.shutdown_timeout).await;
+      },
+      Self::serve_connection,
+    );
+  }
+
+  fn listen<H, F>(listener: Listener, dispatcher: Dispatcher<H>) -> impl Future<Output = ()> + Send
+  where
+    H: Fn(Connection) -> F + Send + Sync,
+    F: Future<Output = ()> + Send + 'static,
+  {
+    async move {
       loop {
         match listener.accept().await {
-          Ok((stream, _addr)) => {
-            // TODO: Metrics
-            Self::process_tcp_stream(stream);
+          Ok(connection) => {
+            metrics::record_connection_accepted();
+            dispatcher.dispatch(connection).await;
           }
           Err(e) => {
-            // TODO: Logs, metrics
-            eprintln!("[ERR] failed to establish connection: {e}");
+            metrics::record_accept_error();
+            log::error("connection_accept_failed", "failed to establish connection", &[("error", (&e).into())]);
             continue;
           }
         }
@@ -75,21 +170,207 @@ pub trait Server: Send + 'static {
     }
   }
 
-  fn process_tcp_stream(stream: TcpStream) {
-    let io = TokioIo::new(stream);
+  fn serve_connection(connection: Connection) -> impl Future<Output = ()> + Send {
+    let info = connection.info();
 
-    tokio::task::spawn(async move {
-      let builder = conn::auto::Builder::new(crate::runtime::AsyncExecutor);
+    async move {
+      if !get_app_config().proxy.proxy_protocol {
+        return Self::serve_io(connection, info).await;
+      }
 
-      if let Err(err) = builder.serve_connection(io, service_fn(Self::serve)).await {
-        // TODO: Logs
-        println!("[ERR] error serving connection: {err:?}");
+      match proxy::accept(connection, info).await {
+        Ok((connection, info)) => Self::serve_io(connection, info).await,
+        Err(e) => log::warn(
+          "proxy_protocol_failed",
+          "closing connection without a valid PROXY protocol header",
+          &[("error", (&e).into())],
+        ),
       }
-    });
+    }
+  }
+
+  /// Serves the HTTP requests received on the connection until it's closed.
+  fn serve_io<I>(io: I, info: ConnectionInfo) -> impl Future<Output = ()> + Send
+  where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+  {
+    let io = TokioIo::new(io);
+
+    async move {
+      let TimeoutConfig { header_read, http2_keep_alive, .. } = get_app_config().timeout;
+      let mut builder = conn::auto::Builder::new(crate::runtime::AsyncExecutor);
+
+      // Clients sending the headers too slowly would hold the connection open, see `TimeoutConfig`.
+      // HTTP/2 clients can be pinged instead, so that connections of unresponsive ones get closed.
+      builder.http1().timer(TokioTimer::new()).header_read_timeout(header_read);
+      builder.http2().timer(TokioTimer::new()).keep_alive_interval(http2_keep_alive);
+
+      if let Some(http2_keep_alive) = http2_keep_alive {
+        builder.http2().keep_alive_timeout(http2_keep_alive);
+      }
+
+      let received = Arc::new(AtomicBool::new(false));
+      let first_request = timeout::first_request(header_read, &received);
+      let receiving = received.clone();
+
+      let service = service_fn(move |req| {
+        receiving.store(true, Ordering::Relaxed);
+        Self::serve(req, info)
+      });
+
+      let connection = builder.serve_connection_with_upgrades(io, service);
+      tokio::pin!(connection);
+
+      let result = tokio::select! {
+        result = connection.as_mut() => result,
+        _ = first_request => {
+          log::debug("header_read_timed_out", "closing connection without any request", &[]);
+          return;
+        }
+        _ = shutdown::wait() => {
+          // Lets the in-flight requests finish, idle connections are closed right away.
+          connection.as_mut().graceful_shutdown();
+          connection.await
+        }
+      };
+
+      if let Err(err) = result {
+        log::warn("connection_error", "error serving connection", &[("error", (&err).into())]);
+      }
+    }
+  }
+
+  fn serve(
+    req: HyperRequest,
+    connection: ConnectionInfo,
+  ) -> impl Future<Output = http::Result<Response<ResponseBody>>> + Send {
+    let request_id = request_id::resolve(&req);
+    let nonce = security_headers::generate_nonce();
+
+    // Everything logged while serving the request is tagged with its ID.
+    let serving = Self::serve_request(req, connection, request_id);
+    let serving = request_id::scope(request_id, security_headers::scope(nonce, serving));
+
+    async move {
+      let mut response = serving.await;
+
+      if let Ok(response) = &mut response {
+        request_id::echo(&request_id, response);
+        security_headers::apply(nonce.as_ref(), response);
+      }
+
+      response
+    }
+  }
+
+  fn serve_request(
+    mut req: HyperRequest,
+    connection: ConnectionInfo,
+    request_id: RequestId,
+  ) -> impl Future<Output = http::Result<Response<ResponseBody>>> + Send {
+    async move {
+      if let Some(response) = Self::health_response(&req).await {
+        return response;
+      }
+
+      let metrics_enabled = metrics::enabled();
+
+      if metrics_enabled && Self::is_metrics_request(&req) {
+        return Self::metrics_response();
+      }
+
+      let peer = proxy::resolve(&connection, req.headers());
+
+      let pending =
+        access_log::get().map(|access_log| (access_log, PendingAccessLogRecord::new(&req, &peer, request_id)));
+      let start = metrics_enabled.then(Instant::now);
+
+      // Makes the client address available to the handler, e.g. for rate limiting
+      req.extensions_mut().insert(connection);
+      req.extensions_mut().insert(peer);
+
+      // Health and metrics endpoints above stay available under load, so that the overload can be observed
+      let admitted = load_shedding::admit_request();
+
+      let is_head = req.method() == Method::HEAD;
+      let compression = &get_app_config().compression;
+      let accept_encoding = compression.enabled.then(|| req.headers().get(http::header::ACCEPT_ENCODING).cloned());
+
+      let mut result = match (&admitted, public::find(req.uri().path())) {
+        (None, _) => HandlerResult { response: load_shedding::overloaded(), route_id: None },
+        (Some(_), Some(file)) => match get_app_config().static_files.priority {
+          StaticFilesPriority::BeforeRoutes => {
+            HandlerResult { response: public::respond(file, req.method(), req.headers()).await, route_id: None }
+          }
+          StaticFilesPriority::AfterRoutes => {
+            let (method, headers) = (req.method().clone(), req.headers().clone());
+            let result = Self::handler(req).await;
+
+            // Only the global 404 has no route ID
+            let is_unmatched = result.route_id.is_none()
+              && result.response.as_ref().is_ok_and(|response| response.status() == StatusCode::NOT_FOUND);
+
+            match is_unmatched {
+              true => HandlerResult { response: public::respond(file, &method, &headers).await, route_id: None },
+              false => result,
+            }
+          }
+        },
+        (Some(_), None) => Self::handler(req).await,
+      };
+
+      if let Ok(response) = &mut result.response {
+        compression::apply(compression, accept_encoding.flatten().as_ref(), response);
+
+        if is_head {
+          strip_body(response);
+        }
+      }
+
+      if let Some(start) = start {
+        metrics::record_request(result.route_id, &result.response, start.elapsed());
+      }
+
+      if let Some((access_log, pending)) = pending {
+        access_log.record(pending.finish(result.route_id, &result.response));
+      }
+
+      result.response
+    }
+  }
+
+  /// Produces the response of a health endpoint if the request targets one. These are served outside of the router.
+  fn health_response(req: &HyperRequest) -> impl Future<Output = Option<http::Result<Response<ResponseBody>>>> + Send {
+    let config = &get_app_config().health;
+    let path = req.uri().path();
+
+    let is_liveness = config.enabled && path == config.liveness_path;
+    let is_readiness = config.enabled && path == config.readiness_path;
+
+    async move {
+      match (is_liveness, is_readiness) {
+        (true, _) => Some(health::liveness_response()),
+        (_, true) => Some(health::readiness_response().await),
+        _ => None,
+      }
+    }
   }
 
-  fn serve(req: HyperRequest) -> impl Future<Output = http::Result<Response<ResponseBody>>> + Send {
-    async { Self::handler(req).await.response }
+  /// Whether the request targets the metrics endpoint, which is served outside of the router.
+  fn is_metrics_request(req: &HyperRequest) -> bool {
+    let method = req.method();
+    req.uri().path() == get_app_config().metrics.path && (method == Method::GET || method == Method::HEAD)
+  }
+
+  /// Produces a response with all the collected metrics in the Prometheus text format.
+  fn metrics_response() -> http::Result<Response<ResponseBody>> {
+    let mut body = ResponseBody::new();
+    body.push(Bytes::from(metrics::render()));
+
+    http::Response::builder()
+      .status(StatusCode::OK)
+      .header(http::header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")
+      .body(body)
   }
 
   /// Implemented by the `app!` macro
@@ -98,6 +379,86 @@ pub trait Server: Send + 'static {
   /// Implemented by the `app!` macro
   fn main() -> impl Termination;
 
+  /// Produces a generic error response for an error returned from a loader which wasn't handled
+  /// by any error loader, logging the `uncaught_loader_error` event. The `status` is usually
+  /// from `timeout::error_status`.
+  fn uncaught_error<E>(status: StatusCode, _error: &E, thrown_by: ThrownBy) -> HandlerResult {
+    log::error(
+      "uncaught_loader_error",
+      "uncaught error returned from a loader",
+      &[
+        ("route_id", thrown_by.route_id.into()),
+        ("loader_kind", (&thrown_by.loader_kind).into()),
+        ("error_type", std::any::type_name::<E>().into()),
+      ],
+    );
+
+    let mut body = ResponseBody::new();
+    body.push(Bytes::from_static(status.canonical_reason().unwrap_or_default().as_bytes()));
+
+    HandlerResult {
+      response: http::Response::builder().status(status).header(http::header::CONTENT_TYPE, "text/plain").body(body),
+      route_id: Some(thrown_by.route_id),
+    }
+  }
+
+  /// Produces the response of the error page whose error loader handled an error returned
+  /// from a loader of the route.
+  fn caught_error(status: StatusCode, error_page_id: &'static str, route_id: &'static str) -> HandlerResult {
+    // TODO: Render the error page with the Props returned by its error loader
+    let chunks = [
+      "<!DOCTYPE html>",
+      "<html>",
+      "<head>",
+      "<meta charset=\"utf-8\" />",
+      "</head>",
+      "<body>",
+      "<div>Matched error page:</div>",
+      "<div style=\"color: red;\">",
+      error_page_id,
+      "</div>",
+      "</body>",
+      "</html>",
+    ];
+
+    let mut body = ResponseBody::with_capacity(chunks.len());
+
+    for chunk in chunks {
+      body.push(Bytes::from_static(chunk.as_bytes()));
+    }
+
+    HandlerResult {
+      response: http::Response::builder().status(status).header(http::header::CONTENT_TYPE, "text/html").body(body),
+      route_id: Some(route_id),
+    }
+  }
+
+  /// Answers an `OPTIONS` request to a route, listing the methods it supports in the `Allow` header.
+  fn allowed_methods(allow: &'static str, route_id: &'static str) -> HandlerResult {
+    HandlerResult {
+      response: http::Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(http::header::ALLOW, allow)
+        .body(ResponseBody::new()),
+      route_id: Some(route_id),
+    }
+  }
+
+  /// Produces a "Method Not Allowed" response for a method the route doesn't support.
+  fn method_not_allowed(allow: &'static str, route_id: &'static str) -> HandlerResult {
+    let mut body = ResponseBody::new();
+    body.push(Bytes::from_static(b"Method Not Allowed"));
+
+    HandlerResult {
+      response: http::Response::builder()
+        .status(StatusCode::METHOD_NOT_ALLOWED)
+        .header(http::header::ALLOW, allow)
+        .header(http::header::CONTENT_TYPE, "text/plain")
+        .body(body),
+      route_id: Some(route_id),
+    }
+  }
+
   /// Produces a response that redirects the user to the provided `path`.
   #[inline]
   fn redirect_to_path(path: &str) -> HandlerResult {
@@ -108,10 +469,14 @@ pub trait Server: Send + 'static {
         .status(StatusCode::PERMANENT_REDIRECT)
         .header(http::header::LOCATION, path)
         .body(ResponseBody::new()),
+      route_id: None,
     }
   }
 
   /// Produces a response that redirects the user to the provided `path` with added trailing slash.
+  ///
+  /// Like `redirect_to_path`, the location is relative, so the client keeps the scheme and host
+  /// it used, e.g. `https` terminated by a proxy in front of the application.
   #[inline]
   fn redirect_to_added_slash(path: &str) -> HandlerResult {
     let mut location = Vec::with_capacity(path.len() + 1);
@@ -126,10 +491,25 @@ pub trait Server: Send + 'static {
         .status(StatusCode::PERMANENT_REDIRECT)
         .header(http::header::LOCATION, location)
         .body(ResponseBody::new()),
+      route_id: None,
     }
   }
 }
 
 pub struct HandlerResult {
   pub response: http::Result<Response<ResponseBody>>,
+  /// The ID of the route that produced the response, `None` if no route was matched.
+  pub route_id: Option<&'static str>,
+}
+
+/// Removes the body of a response to a `HEAD` request, keeping the `Content-Length` it would have had for `GET`.
+fn strip_body(response: &mut Response<ResponseBody>) {
+  if let Some(len) = response.body().size_hint().exact()
+    && len > 0
+    && !response.headers().contains_key(http::header::CONTENT_LENGTH)
+  {
+    response.headers_mut().insert(http::header::CONTENT_LENGTH, HeaderValue::from(len));
+  }
+
+  *response.body_mut() = ResponseBody::new();
 }
@@ -0,0 +1,64 @@
+use std::future::Future;
+use std::process::Termination;
+
+use bytes::Bytes;
+use hyper::body::Body;
+use hyper::{StatusCode, http};
+
+use crate::server::response::body::ResponseBody;
+
+use super::{HandlerResult, HyperRequest, Server, strip_body};
+
+struct TestServer;
+
+impl Server for TestServer {
+  #[allow(clippy::manual_async_fn)]
+  fn handler(_req: HyperRequest) -> impl Future<Output = HandlerResult> + Send {
+    async { unreachable!("the test server doesn't handle requests") }
+  }
+
+  fn main() -> impl Termination {}
+}
+
+#[test]
+fn test_allowed_methods() {
+  let response = TestServer::allowed_methods("GET, HEAD, OPTIONS", "about").response.unwrap();
+
+  assert_eq!(response.status(), StatusCode::NO_CONTENT);
+  assert_eq!(response.headers()[http::header::ALLOW], "GET, HEAD, OPTIONS");
+  assert_eq!(response.body().size_hint().exact(), Some(0));
+}
+
+#[test]
+fn test_method_not_allowed() {
+  let response = TestServer::method_not_allowed("GET, HEAD, OPTIONS", "about").response.unwrap();
+
+  assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+  assert_eq!(response.headers()[http::header::ALLOW], "GET, HEAD, OPTIONS");
+}
+
+#[test]
+fn test_head_strips_body() {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(b"<html>"));
+  body.push(Bytes::from_static(b"</html>"));
+
+  let mut response = http::Response::builder().body(body).unwrap();
+  strip_body(&mut response);
+
+  // The same length as the GET response would have, but no body
+  assert_eq!(response.headers()[http::header::CONTENT_LENGTH], "13");
+  assert_eq!(response.body().size_hint().exact(), Some(0));
+
+  // The length set by the responder is kept
+  let mut response = http::Response::builder().header(http::header::CONTENT_LENGTH, "42").body(body_of(b"hi")).unwrap();
+  strip_body(&mut response);
+
+  assert_eq!(response.headers()[http::header::CONTENT_LENGTH], "42");
+}
+
+fn body_of(bytes: &'static [u8]) -> ResponseBody {
+  let mut body = ResponseBody::new();
+  body.push(Bytes::from_static(bytes));
+  body
+}
@@ -0,0 +1,10 @@
+#[cfg(test)]
+mod test;
+
+mod message;
+mod socket;
+mod upgrade;
+
+pub use message::{CloseFrame, Message};
+pub use socket::{WebSocket, WebSocketError};
+pub use upgrade::{UpgradeError, WebSocketUpgrade};
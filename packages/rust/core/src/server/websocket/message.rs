@@ -0,0 +1,86 @@
+use bytes::Bytes;
+use tokio_tungstenite::tungstenite::Message as RawMessage;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame as RawCloseFrame, frame::coding::CloseCode};
+
+/// A message sent or received over a WebSocket.
+///
+/// Pings are answered with pongs automatically, they're only passed to the application
+/// so that it can observe them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+  Text(String),
+  Binary(Bytes),
+  Ping(Bytes),
+  Pong(Bytes),
+  /// The closing handshake. Received when the client closes the connection, sending it closes
+  /// the connection from the server side.
+  Close(Option<CloseFrame>),
+}
+
+/// The status code and reason of closing a WebSocket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseFrame {
+  /// The status code, e.g. `1000` for a normal closure or `1001` when the server is going away.
+  pub code: u16,
+  pub reason: String,
+}
+
+impl CloseFrame {
+  pub const NORMAL: u16 = 1000;
+  pub const GOING_AWAY: u16 = 1001;
+  pub const POLICY_VIOLATION: u16 = 1008;
+  pub const TOO_BIG: u16 = 1009;
+}
+
+impl Message {
+  /// Converts the message received from the protocol implementation. `None` for raw frames,
+  /// which are never produced while reading.
+  pub(crate) fn from_raw(message: RawMessage) -> Option<Self> {
+    Some(match message {
+      RawMessage::Text(text) => Message::Text(text.as_str().to_string()),
+      RawMessage::Binary(data) => Message::Binary(data),
+      RawMessage::Ping(data) => Message::Ping(data),
+      RawMessage::Pong(data) => Message::Pong(data),
+      RawMessage::Close(frame) => {
+        Message::Close(frame.map(|frame| CloseFrame { code: frame.code.into(), reason: frame.reason.to_string() }))
+      }
+      RawMessage::Frame(_) => return None,
+    })
+  }
+
+  pub(crate) fn into_raw(self) -> RawMessage {
+    match self {
+      Message::Text(text) => RawMessage::Text(text.into()),
+      Message::Binary(data) => RawMessage::Binary(data),
+      Message::Ping(data) => RawMessage::Ping(data),
+      Message::Pong(data) => RawMessage::Pong(data),
+      Message::Close(frame) => RawMessage::Close(
+        frame.map(|frame| RawCloseFrame { code: CloseCode::from(frame.code), reason: frame.reason.into() }),
+      ),
+    }
+  }
+}
+
+impl From<String> for Message {
+  fn from(text: String) -> Self {
+    Message::Text(text)
+  }
+}
+
+impl From<&str> for Message {
+  fn from(text: &str) -> Self {
+    Message::Text(text.to_string())
+  }
+}
+
+impl From<Bytes> for Message {
+  fn from(data: Bytes) -> Self {
+    Message::Binary(data)
+  }
+}
+
+impl From<Vec<u8>> for Message {
+  fn from(data: Vec<u8>) -> Self {
+    Message::Binary(Bytes::from(data))
+  }
+}
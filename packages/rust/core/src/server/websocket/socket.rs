@@ -0,0 +1,99 @@
+use std::fmt::{Display, Formatter};
+
+use futures_util::{SinkExt, StreamExt};
+use hyper::upgrade::Upgraded;
+use hyper_util::rt::TokioIo;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Error as RawError;
+
+use crate::server::shutdown;
+use crate::server::websocket::{CloseFrame, Message};
+
+/// An upgraded WebSocket connection, exchanging whole messages with the client.
+///
+/// The socket runs as a task on the same worker thread that served the upgrade request.
+pub struct WebSocket {
+  stream: WebSocketStream<TokioIo<Upgraded>>,
+  protocol: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum WebSocketError {
+  /// The connection is already closed, no more messages can be sent or received.
+  Closed,
+  /// A frame or a message exceeded the configured size limits.
+  TooBig,
+  /// The client violated the protocol, e.g. by sending a text message that isn't valid UTF-8.
+  Protocol(String),
+  Io(std::io::Error),
+}
+
+impl WebSocket {
+  pub(crate) fn new(stream: WebSocketStream<TokioIo<Upgraded>>, protocol: Option<String>) -> Self {
+    Self { stream, protocol }
+  }
+
+  /// The subprotocol agreed on during the upgrade, if any.
+  pub fn protocol(&self) -> Option<&str> {
+    self.protocol.as_deref()
+  }
+
+  /// Receives the next message. `None` once the connection is closed.
+  ///
+  /// When graceful shutdown starts, the connection is closed with `1001 Going Away` and `None` is returned.
+  pub async fn recv(&mut self) -> Option<Result<Message, WebSocketError>> {
+    loop {
+      let next = tokio::select! {
+        next = self.stream.next() => next?,
+        _ = shutdown::wait() => {
+          let frame = CloseFrame { code: CloseFrame::GOING_AWAY, reason: "server shutting down".to_string() };
+          let _ = self.close(frame).await;
+          return None;
+        }
+      };
+
+      match next {
+        Ok(message) => match Message::from_raw(message) {
+          Some(message) => return Some(Ok(message)),
+          None => continue,
+        },
+        Err(RawError::ConnectionClosed | RawError::AlreadyClosed) => return None,
+        Err(e) => return Some(Err(e.into())),
+      }
+    }
+  }
+
+  /// Sends the message, waiting until it's written to the connection.
+  pub async fn send(&mut self, message: impl Into<Message>) -> Result<(), WebSocketError> {
+    self.stream.send(message.into().into_raw()).await.map_err(WebSocketError::from)
+  }
+
+  /// Starts the closing handshake. Messages still arriving from the client can be received until `recv` returns `None`.
+  pub async fn close(&mut self, frame: CloseFrame) -> Result<(), WebSocketError> {
+    self.send(Message::Close(Some(frame))).await
+  }
+}
+
+impl From<RawError> for WebSocketError {
+  fn from(error: RawError) -> Self {
+    match error {
+      RawError::ConnectionClosed | RawError::AlreadyClosed => WebSocketError::Closed,
+      RawError::Capacity(_) => WebSocketError::TooBig,
+      RawError::Io(e) => WebSocketError::Io(e),
+      e => WebSocketError::Protocol(e.to_string()),
+    }
+  }
+}
+
+impl Display for WebSocketError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      WebSocketError::Closed => f.write_str("the connection is closed"),
+      WebSocketError::TooBig => f.write_str("the message exceeds the size limit"),
+      WebSocketError::Protocol(e) => write!(f, "protocol error: {e}"),
+      WebSocketError::Io(e) => write!(f, "I/O error: {e}"),
+    }
+  }
+}
+
+impl std::error::Error for WebSocketError {}
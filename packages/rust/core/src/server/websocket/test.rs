@@ -0,0 +1,75 @@
+use bytes::Bytes;
+use hyper::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+
+use super::upgrade::{negotiate_protocol, validate};
+use super::{CloseFrame, Message, UpgradeError};
+
+fn handshake_headers() -> HeaderMap {
+  let mut headers = HeaderMap::new();
+  headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+  headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive, Upgrade"));
+  headers.insert(header::SEC_WEBSOCKET_VERSION, HeaderValue::from_static("13"));
+  headers.insert(header::SEC_WEBSOCKET_KEY, HeaderValue::from_static("dGhlIHNhbXBsZSBub25jZQ=="));
+  headers
+}
+
+#[test]
+fn test_validate() {
+  // The example from RFC 6455
+  assert_eq!(validate(&Method::GET, &handshake_headers()).unwrap(), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+  assert_eq!(validate(&Method::POST, &handshake_headers()), Err(UpgradeError::MethodNotGet));
+  assert_eq!(validate(&Method::GET, &HeaderMap::new()), Err(UpgradeError::NotUpgradeRequest));
+
+  let mut headers = handshake_headers();
+  headers.insert(header::SEC_WEBSOCKET_VERSION, HeaderValue::from_static("8"));
+  assert_eq!(validate(&Method::GET, &headers), Err(UpgradeError::UnsupportedVersion));
+
+  let mut headers = handshake_headers();
+  headers.insert(header::SEC_WEBSOCKET_KEY, HeaderValue::from_static("short"));
+  assert_eq!(validate(&Method::GET, &headers), Err(UpgradeError::InvalidKey));
+
+  headers.remove(header::SEC_WEBSOCKET_KEY);
+  assert_eq!(validate(&Method::GET, &headers), Err(UpgradeError::InvalidKey));
+}
+
+#[test]
+fn test_negotiate_protocol() {
+  let requested = vec!["chat.v2".to_string(), "chat.v1".to_string()];
+
+  // The client's preference wins
+  assert_eq!(negotiate_protocol(&requested, &["chat.v1", "chat.v2"]), Some("chat.v2"));
+  assert_eq!(negotiate_protocol(&requested, &["chat.v1"]), Some("chat.v1"));
+  assert_eq!(negotiate_protocol(&requested, &["other"]), None);
+  assert_eq!(negotiate_protocol(&[], &["chat.v1"]), None);
+}
+
+#[test]
+fn test_error_responses() {
+  let response = UpgradeError::UnsupportedVersion.into_response();
+  assert_eq!(response.status(), StatusCode::UPGRADE_REQUIRED);
+  assert_eq!(response.headers()[header::SEC_WEBSOCKET_VERSION], "13");
+
+  let response = UpgradeError::NotUpgradeRequest.into_response();
+  assert_eq!(response.status(), StatusCode::UPGRADE_REQUIRED);
+  assert_eq!(response.headers()[header::UPGRADE], "websocket");
+
+  assert_eq!(UpgradeError::MethodNotGet.into_response().status(), StatusCode::METHOD_NOT_ALLOWED);
+  assert_eq!(UpgradeError::InvalidKey.into_response().status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_message_conversion() {
+  let messages = [
+    Message::Text("hello".to_string()),
+    Message::Binary(Bytes::from_static(b"\x00\x01")),
+    Message::Ping(Bytes::from_static(b"ping")),
+    Message::Pong(Bytes::new()),
+    Message::Close(Some(CloseFrame { code: CloseFrame::GOING_AWAY, reason: "bye".to_string() })),
+    Message::Close(None),
+  ];
+
+  for message in messages {
+    assert_eq!(Message::from_raw(message.clone().into_raw()), Some(message));
+  }
+}
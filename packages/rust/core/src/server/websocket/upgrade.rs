@@ -0,0 +1,235 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use hyper::http::{self, HeaderMap, HeaderValue, Method, StatusCode, header};
+use hyper::upgrade::OnUpgrade;
+use hyper_util::rt::TokioIo;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{Role, WebSocketConfig as ProtocolConfig};
+
+use crate::config::get_app_config;
+use crate::log;
+use crate::server::middleware::into_response;
+use crate::server::response::Response;
+use crate::server::response::body::ResponseBody;
+use crate::server::shutdown;
+use crate::server::websocket::WebSocket;
+
+/// A request to upgrade the connection to a WebSocket, accepted by a handler.
+///
+/// ```ignore
+/// pub async fn handler(request: &ruxy::HandlerRequest) -> ruxy::Response {
+///   let upgrade = match ruxy::WebSocketUpgrade::from_request(request) {
+///     Ok(upgrade) => upgrade,
+///     Err(e) => return e.into_response(),
+///   };
+///
+///   upgrade.protocols(&["dashboard.v1"]).on_upgrade(|mut socket| async move {
+///     while let Some(Ok(message)) = socket.recv().await {
+///       let _ = socket.send(message).await;
+///     }
+///   })
+/// }
+/// ```
+pub struct WebSocketUpgrade {
+  on_upgrade: OnUpgrade,
+  accept_key: String,
+  requested_protocols: Vec<String>,
+  protocol: Option<String>,
+  max_frame_size: usize,
+  max_message_size: usize,
+}
+
+/// Why a request couldn't be upgraded to a WebSocket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeError {
+  /// The request doesn't ask for a WebSocket upgrade at all.
+  NotUpgradeRequest,
+  MethodNotGet,
+  /// The client speaks another version of the protocol than 13.
+  UnsupportedVersion,
+  /// The `Sec-WebSocket-Key` header is missing or malformed.
+  InvalidKey,
+  /// The connection can't be taken over, e.g. because the request was made over HTTP/2.
+  NotUpgradable,
+}
+
+impl WebSocketUpgrade {
+  /// Validates the upgrade request. The limits default to the ones in `AppConfig::websocket`.
+  pub fn from_request<B>(request: &hyper::Request<B>) -> Result<Self, UpgradeError> {
+    let accept_key = validate(request.method(), request.headers())?;
+    let on_upgrade = request.extensions().get::<OnUpgrade>().cloned().ok_or(UpgradeError::NotUpgradable)?;
+    let config = &get_app_config().websocket;
+
+    Ok(Self {
+      on_upgrade,
+      accept_key,
+      requested_protocols: requested_protocols(request.headers()),
+      protocol: None,
+      max_frame_size: config.max_frame_size,
+      max_message_size: config.max_message_size,
+    })
+  }
+
+  /// Whether the request asks for a WebSocket upgrade, so that a handler can serve regular requests too.
+  pub fn is_upgrade_request<B>(request: &hyper::Request<B>) -> bool {
+    has_token(request.headers(), header::UPGRADE, "websocket")
+  }
+
+  /// The subprotocols requested by the client, in the order of its preference.
+  pub fn requested_protocols(&self) -> impl Iterator<Item = &str> {
+    self.requested_protocols.iter().map(String::as_str)
+  }
+
+  /// Selects the subprotocol most preferred by the client among the supported ones. If none
+  /// matches, the upgrade proceeds without a subprotocol and the client decides whether to continue.
+  pub fn protocols(mut self, supported: &[&str]) -> Self {
+    self.protocol = negotiate_protocol(&self.requested_protocols, supported).map(str::to_string);
+    self
+  }
+
+  /// Sets the maximum size of a single incoming frame in bytes.
+  pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+    self.max_frame_size = max_frame_size;
+    self
+  }
+
+  /// Sets the maximum size of an incoming message in bytes.
+  pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+    self.max_message_size = max_message_size;
+    self
+  }
+
+  /// Accepts the upgrade, returning the `101 Switching Protocols` response to be returned from the handler.
+  ///
+  /// Once the response is sent, the callback is spawned on the current worker thread with the upgraded socket.
+  pub fn on_upgrade<F, Fut>(self, callback: F) -> Response
+  where
+    F: FnOnce(WebSocket) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    let mut response = hyper::Response::builder()
+      .status(StatusCode::SWITCHING_PROTOCOLS)
+      .header(header::UPGRADE, "websocket")
+      .header(header::CONNECTION, "upgrade")
+      .header(header::SEC_WEBSOCKET_ACCEPT, &self.accept_key);
+
+    if let Some(protocol) = &self.protocol {
+      response = response.header(header::SEC_WEBSOCKET_PROTOCOL, protocol);
+    }
+
+    let config =
+      ProtocolConfig::default().max_frame_size(Some(self.max_frame_size)).max_message_size(Some(self.max_message_size));
+
+    let (on_upgrade, protocol) = (self.on_upgrade, self.protocol);
+    let hold = shutdown::hold();
+
+    tokio::task::spawn(async move {
+      // The socket closes itself once shutdown starts, see `WebSocket::recv`
+      let _hold = hold;
+
+      match on_upgrade.await {
+        Ok(upgraded) => {
+          let stream = WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, Some(config)).await;
+          callback(WebSocket::new(stream, protocol)).await;
+        }
+        Err(e) => log::warn("websocket_upgrade_failed", "failed to upgrade the connection", &[("error", (&e).into())]),
+      }
+    });
+
+    into_response(response.body(ResponseBody::new()))
+  }
+}
+
+impl UpgradeError {
+  /// The response rejecting the upgrade.
+  pub fn into_response(self) -> Response {
+    into_response(self.response())
+  }
+
+  fn response(self) -> http::Result<Response> {
+    let mut response = hyper::Response::builder().header(header::CONTENT_TYPE, "text/plain");
+
+    response = match self {
+      UpgradeError::NotUpgradeRequest => response
+        .status(StatusCode::UPGRADE_REQUIRED)
+        .header(header::UPGRADE, "websocket")
+        .header(header::CONNECTION, "upgrade"),
+      UpgradeError::MethodNotGet => response.status(StatusCode::METHOD_NOT_ALLOWED).header(header::ALLOW, "GET"),
+      UpgradeError::UnsupportedVersion => {
+        response.status(StatusCode::UPGRADE_REQUIRED).header(header::SEC_WEBSOCKET_VERSION, "13")
+      }
+      UpgradeError::InvalidKey | UpgradeError::NotUpgradable => response.status(StatusCode::BAD_REQUEST),
+    };
+
+    let mut body = ResponseBody::new();
+    body.push(Bytes::from(self.to_string()));
+
+    response.body(body)
+  }
+}
+
+impl From<UpgradeError> for Response {
+  fn from(error: UpgradeError) -> Self {
+    error.into_response()
+  }
+}
+
+impl Display for UpgradeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(match self {
+      UpgradeError::NotUpgradeRequest => "Expected a WebSocket upgrade request",
+      UpgradeError::MethodNotGet => "WebSocket upgrades must use the GET method",
+      UpgradeError::UnsupportedVersion => "Unsupported WebSocket version",
+      UpgradeError::InvalidKey => "Missing or invalid Sec-WebSocket-Key",
+      UpgradeError::NotUpgradable => "The connection can't be upgraded",
+    })
+  }
+}
+
+impl std::error::Error for UpgradeError {}
+
+/// Validates the handshake headers (RFC 6455, section 4.2.1), returning the `Sec-WebSocket-Accept` value.
+pub(crate) fn validate(method: &Method, headers: &HeaderMap) -> Result<String, UpgradeError> {
+  if !has_token(headers, header::UPGRADE, "websocket") || !has_token(headers, header::CONNECTION, "upgrade") {
+    return Err(UpgradeError::NotUpgradeRequest);
+  }
+
+  if method != Method::GET {
+    return Err(UpgradeError::MethodNotGet);
+  }
+
+  if headers.get(header::SEC_WEBSOCKET_VERSION).map(HeaderValue::as_bytes) != Some(b"13") {
+    return Err(UpgradeError::UnsupportedVersion);
+  }
+
+  // The key is 16 random bytes encoded in base64
+  let key = headers.get(header::SEC_WEBSOCKET_KEY).map(HeaderValue::as_bytes).unwrap_or_default();
+
+  if key.len() != 24 || !key.ends_with(b"==") {
+    return Err(UpgradeError::InvalidKey);
+  }
+
+  Ok(derive_accept_key(key))
+}
+
+/// Picks the first protocol requested by the client that the server supports.
+pub(crate) fn negotiate_protocol<'a>(requested: &[String], supported: &[&'a str]) -> Option<&'a str> {
+  requested.iter().find_map(|requested| supported.iter().copied().find(|supported| *supported == requested))
+}
+
+fn requested_protocols(headers: &HeaderMap) -> Vec<String> {
+  let values = headers.get_all(header::SEC_WEBSOCKET_PROTOCOL).iter().filter_map(|value| value.to_str().ok());
+  let protocols = values.flat_map(|value| value.split(',')).map(str::trim).filter(|protocol| !protocol.is_empty());
+
+  protocols.map(str::to_string).collect()
+}
+
+/// Whether the comma-separated header contains the token, ignoring case.
+fn has_token(headers: &HeaderMap, name: header::HeaderName, token: &str) -> bool {
+  headers
+    .get_all(name)
+    .iter()
+    .any(|value| value.to_str().is_ok_and(|value| value.split(',').any(|v| v.trim().eq_ignore_ascii_case(token))))
+}
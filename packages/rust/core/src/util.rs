@@ -3,4 +3,6 @@ pub mod dollar_encoding;
 pub mod fs;
 pub mod hex;
 pub mod is_build_script;
+pub mod json;
 pub mod radix_trie;
+pub mod time;
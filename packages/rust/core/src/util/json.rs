@@ -0,0 +1,21 @@
+use std::fmt::Write;
+
+/// Writes the provided string to `out` as a quoted and escaped JSON string.
+pub fn write_json_string(out: &mut String, value: &str) {
+  out.push('"');
+
+  for ch in value.chars() {
+    match ch {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      '\r' => out.push_str("\\r"),
+      '\t' => out.push_str("\\t"),
+      // Writing to String can't fail
+      ch if ch.is_control() => _ = write!(out, "\\u{:04x}", ch as u32),
+      ch => out.push(ch),
+    }
+  }
+
+  out.push('"');
+}
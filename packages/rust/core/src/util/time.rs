@@ -0,0 +1,147 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A calendar date and time in UTC, broken down to its components.
+pub struct UtcDateTime {
+  pub year: i64,
+  pub month: u32,
+  pub day: u32,
+  pub hour: u32,
+  pub minute: u32,
+  pub second: u32,
+  pub millisecond: u32,
+}
+
+impl UtcDateTime {
+  pub fn now() -> Self {
+    Self::from_system_time(SystemTime::now())
+  }
+
+  pub fn from_system_time(time: SystemTime) -> Self {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs() as i64;
+
+    let days = secs.div_euclid(86_400);
+    let secs_of_day = secs.rem_euclid(86_400) as u32;
+
+    let (year, month, day) = civil_from_days(days);
+
+    Self {
+      year,
+      month,
+      day,
+      hour: secs_of_day / 3600,
+      minute: secs_of_day % 3600 / 60,
+      second: secs_of_day % 60,
+      millisecond: since_epoch.subsec_millis(),
+    }
+  }
+
+  /// Formats the date as RFC 3339 with millisecond precision, e.g. `2025-06-01T12:30:45.123Z`.
+  pub fn to_rfc3339(&self) -> String {
+    format!(
+      "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+      self.year, self.month, self.day, self.hour, self.minute, self.second, self.millisecond
+    )
+  }
+
+  /// Formats the date as used by the Common Log Format, e.g. `01/Jun/2025:12:30:45 +0000`.
+  pub fn to_common_log_format(&self) -> String {
+    let month = MONTH_ABBREVIATIONS[(self.month - 1) as usize];
+
+    format!("{:02}/{month}/{:04}:{:02}:{:02}:{:02} +0000", self.day, self.year, self.hour, self.minute, self.second)
+  }
+
+  /// Formats the date as an HTTP date (IMF-fixdate), e.g. `Sun, 01 Jun 2025 12:30:45 GMT`.
+  pub fn to_http_date(&self) -> String {
+    let month = MONTH_ABBREVIATIONS[(self.month - 1) as usize];
+    let weekday = WEEKDAY_ABBREVIATIONS[days_from_civil(self.year, self.month, self.day).rem_euclid(7) as usize];
+
+    format!(
+      "{weekday}, {:02} {month} {:04} {:02}:{:02}:{:02} GMT",
+      self.day, self.year, self.hour, self.minute, self.second
+    )
+  }
+}
+
+/// Parses an HTTP date in the IMF-fixdate format, e.g. `Sun, 01 Jun 2025 12:30:45 GMT`.
+///
+/// The obsolete RFC 850 and asctime formats aren't supported, such dates yield `None`.
+pub fn parse_http_date(value: &str) -> Option<SystemTime> {
+  let (_weekday, rest) = value.split_once(", ")?;
+  let mut parts = rest.split(' ');
+
+  let day: u32 = parts.next().filter(|d| d.len() == 2)?.parse().ok()?;
+  let month = parts.next()?;
+  let month = MONTH_ABBREVIATIONS.iter().position(|m| *m == month)? as u32 + 1;
+  let year: i64 = parts.next().filter(|y| y.len() == 4)?.parse().ok()?;
+
+  let mut time = parts.next().filter(|t| t.len() == 8)?.split(':').map(|c| c.parse::<u32>().ok());
+  let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+  if parts.next() != Some("GMT") || parts.next().is_some() || day == 0 || day > 31 || hour > 23 || minute > 59 {
+    return None;
+  }
+
+  // Leap seconds are allowed by the format, but not representable
+  if second > 60 {
+    return None;
+  }
+
+  let secs = days_from_civil(year, month, day) * 86_400 + (hour * 3600 + minute * 60 + second) as i64;
+
+  Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+const MONTH_ABBREVIATIONS: [&str; 12] =
+  ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Starting with Thursday, as the Unix epoch was on a Thursday.
+const WEEKDAY_ABBREVIATIONS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+
+/// Converts the number of days since the Unix epoch to a (year, month, day) triple
+/// in the proleptic Gregorian calendar (http://howardhinnant.github.io/date_algorithms.html).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+  (year, month, day)
+}
+
+/// Converts a (year, month, day) triple in the proleptic Gregorian calendar to the number
+/// of days since the Unix epoch. The inverse of `civil_from_days`.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let yoe = year.rem_euclid(400);
+  let mp = if month > 2 { month - 3 } else { month + 9 } as i64;
+  let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+  era * 146_097 + doe - 719_468
+}
+
+#[test]
+fn converts_to_civil_date() {
+  let format = |secs: u64| UtcDateTime::from_system_time(UNIX_EPOCH + Duration::from_millis(secs)).to_rfc3339();
+
+  assert_eq!(format(0), "1970-01-01T00:00:00.000Z");
+  assert_eq!(format(951_782_400_500), "2000-02-29T00:00:00.500Z");
+  assert_eq!(format(1_767_225_599_999), "2025-12-31T23:59:59.999Z");
+
+  let time = UNIX_EPOCH + Duration::from_secs(1_748_781_045);
+  assert_eq!(UtcDateTime::from_system_time(time).to_common_log_format(), "01/Jun/2025:12:30:45 +0000");
+  assert_eq!(UtcDateTime::from_system_time(time).to_http_date(), "Sun, 01 Jun 2025 12:30:45 GMT");
+
+  assert_eq!(parse_http_date("Sun, 01 Jun 2025 12:30:45 GMT"), Some(time));
+  assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(UNIX_EPOCH));
+  assert_eq!(parse_http_date("Sunday, 01-Jun-25 12:30:45 GMT"), None);
+  assert_eq!(parse_http_date("Sun, 01 Jun 2025 12:30:45 UTC"), None);
+  assert_eq!(parse_http_date("Sun, 01 Foo 2025 12:30:45 GMT"), None);
+}
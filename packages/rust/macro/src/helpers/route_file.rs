@@ -20,6 +20,10 @@ pub enum RouteFileType {
   ErrorPage,
   ErrorHandler,
   Layout,
+  Middleware,
+  Cors,
+  RateLimit,
+  Timeout,
   Matcher,
   Unknown,
 }
@@ -50,6 +54,10 @@ impl RouteFileType {
       "error_page.rs" => RouteFileType::ErrorPage,
       "error_handler.rs" => RouteFileType::ErrorHandler,
       "layout.rs" => RouteFileType::Layout,
+      "middleware.rs" => RouteFileType::Middleware,
+      "cors.rs" => RouteFileType::Cors,
+      "rate_limit.rs" => RouteFileType::RateLimit,
+      "timeout.rs" => RouteFileType::Timeout,
       "match.rs" => RouteFileType::Matcher,
       _ => RouteFileType::Unknown,
     }
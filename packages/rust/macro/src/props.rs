@@ -1,11 +1,25 @@
+use ::ruxy_core::util::json::write_json_string;
+use proc_macro2::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 
 pub fn derive_props(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
   let input = syn::parse_macro_input!(input as syn::DeriveInput);
   let ident = input.ident;
 
+  let write_json = match &input.data {
+    syn::Data::Struct(data) => gen_write_json(&data.fields),
+    _ => {
+      return syn::Error::new_spanned(&ident, "Props can only be derived for structs").to_compile_error().into();
+    }
+  };
+
   let result = quote! {
-    impl ::ruxy::Props for #ident {}
+    impl ::ruxy::Props for #ident {
+      fn __internal_write_json(&self, out: &mut ::std::string::String) {
+        #write_json
+      }
+    }
     
     // TODO: It would be nice if we could provide these implementations for all props,
     //       not just the macro-derived ones. For now it's better than nothing, though.
@@ -25,3 +39,44 @@ pub fn derive_props(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
   
   result.into()
 }
+
+/// Serializes structs with named fields as JSON objects, tuple structs as arrays and unit structs as `null`.
+fn gen_write_json(fields: &syn::Fields) -> TokenStream {
+  let (open, close) = match fields {
+    syn::Fields::Named(_) => ('{', '}'),
+    syn::Fields::Unnamed(_) => ('[', ']'),
+    syn::Fields::Unit => return quote! { out.push_str("null"); },
+  };
+
+  let items = fields.iter().enumerate().map(|(i, field)| {
+    // The separators and keys are precomputed, so that the expansion only pushes string literals
+    let mut prefix = String::new();
+
+    if i > 0 {
+      prefix.push(',');
+    }
+
+    let accessor = match &field.ident {
+      Some(ident) => {
+        write_json_string(&mut prefix, &ident.unraw().to_string());
+        prefix.push(':');
+        quote! { #ident }
+      }
+      None => {
+        let index = syn::Index::from(i);
+        quote! { #index }
+      }
+    };
+
+    quote! {
+      out.push_str(#prefix);
+      ::ruxy::Props::__internal_write_json(&self.#accessor, out);
+    }
+  });
+
+  quote! {
+    out.push(#open);
+    #(#items)*
+    out.push(#close);
+  }
+}
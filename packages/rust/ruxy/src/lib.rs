@@ -4,6 +4,8 @@ pub use ruxy_macro::{Props, build, generator, loader, main};
 pub use ruxy_core::server::request::Request;
 pub use ruxy_core::server::request::cookies::Cookies;
 pub use ruxy_core::server::request::headers::Headers;
+pub use ruxy_core::server::request::peer::{PeerInfo, Scheme};
+pub use ruxy_core::server::request::request_id::RequestId;
 
 // Page-related items
 pub use ruxy_core::server::page::error::{self, Caught};
@@ -11,23 +13,76 @@ pub use ruxy_core::server::page::{GeneratorOutput, Loadable, LoaderOutput, Props
 
 // Items usable in both Page and Handler
 pub use ruxy_core::server::redirect;
+pub use ruxy_core::server::response::Response;
+pub use ruxy_core::server::response::body::{BodySender, Disconnected, ResponseBody};
+pub use ruxy_core::server::response::chunk::ResponseChunk;
+pub use ruxy_core::server::conditional::ETag;
+pub use ruxy_core::server::security_headers::{CspNonce, current as csp_nonce};
+
+// Rate limiting
+pub use ruxy_core::server::rate_limit::{
+  MemoryStore, RateLimit, RateLimitAlgorithm, RateLimitDecision, RateLimitKey, RateLimitStore,
+};
+
+// Timeouts
+pub use ruxy_core::server::timeout::{LoaderTimeout, RouteTimeout};
+
+// Handler-related items
+pub use ruxy_core::server::handler::HandlerRequest;
+pub use ruxy_core::server::sse::{self, Event, Sse, SseSender};
+pub use ruxy_core::server::websocket::{
+  CloseFrame, Message, UpgradeError, WebSocket, WebSocketError, WebSocketUpgrade,
+};
+
+// Middleware-related items
+pub use ruxy_core::server::middleware::{MiddlewareRequest, Next};
+
+// Logging, metrics and health
+pub use ruxy_core::log;
+pub use ruxy_core::metrics;
+pub use ruxy_core::server::health::register_readiness_check;
 
 // Config-related items
 pub use ruxy_core::config::AppConfig;
+pub use ruxy_core::config::{AccessLogConfig, AccessLogFormat, AccessLogOutput};
+pub use ruxy_core::config::{CompressionConfig, CompressionLevel, CompressionRule, ContentEncoding};
+pub use ruxy_core::config::{CorsConfig, CorsOrigin};
+pub use ruxy_core::config::HealthConfig;
+pub use ruxy_core::config::LoadSheddingConfig;
+pub use ruxy_core::config::{LogConfig, LogFormat};
+pub use ruxy_core::config::MetricsConfig;
 pub use ruxy_core::config::PartytownConfig;
+pub use ruxy_core::config::ProxyConfig;
+pub use ruxy_core::config::RequestIdConfig;
+pub use ruxy_core::config::{FrameOptions, STRICT_CONTENT_SECURITY_POLICY, SecurityHeadersConfig};
+pub use ruxy_core::config::{StaticFilesConfig, StaticFilesPriority};
+pub use ruxy_core::config::TimeoutConfig;
 pub use ruxy_core::config::TrailingSlashConfig;
+pub use ruxy_core::config::WebSocketConfig;
 
 // Internal (called in macro expansions)
 #[doc(hidden)]
 pub mod __ruxy_macro_internal {
   // 3rd party (maybe re-export publicly?)
   pub use bytes::Bytes;
+  pub use hyper::StatusCode;
   pub use hyper::body::Frame;
 
   // Internals
   pub use ruxy_core::build::{build, BuildConfig};
-  pub use ruxy_core::config::register_app_config;
+  pub use ruxy_core::config::{CorsConfig, get_app_config, register_app_config};
+  pub use ruxy_core::metrics::register_routes;
+  pub use ruxy_core::server::conditional::{ETag, is_not_modified, not_modified, with_validators};
+  pub use ruxy_core::server::cors;
+  pub use ruxy_core::server::middleware::{Next, NextFn, into_response};
+  pub use ruxy_core::server::page::Loadable;
+  pub use ruxy_core::server::page::error::{Downcastable, ThrownBy, ThrownByLoaderKind};
+  pub use ruxy_core::server::public::{EmbeddedFile, PublicFile, PublicIndex, register_public_files};
+  pub use ruxy_core::server::rate_limit::{self, RateLimit};
   pub use ruxy_core::server::response::body::ResponseBody;
+  pub use ruxy_core::server::response::precompressed::Precompressed;
+  pub use ruxy_core::server::security_headers::script_open_tag;
+  pub use ruxy_core::server::timeout::{self, RouteTimeout};
   pub use ruxy_core::server::tserver::HandlerResult;
   pub use ruxy_core::server::tserver::HyperRequest;
   pub use ruxy_core::server::tserver::Server;
@@ -1,4 +1,16 @@
-// #[ruxy::handler]
-// pub async fn handle() -> ruxy::HandlerResult {
-//   // Create custom response here
-// }
+use ruxy::{HandlerRequest, Message, Response, WebSocketUpgrade};
+
+pub async fn handler(request: &HandlerRequest) -> Response {
+  let upgrade = match WebSocketUpgrade::from_request(request) {
+    Ok(upgrade) => upgrade,
+    Err(e) => return e.into(),
+  };
+
+  upgrade.protocols(&["echo"]).on_upgrade(|mut socket| async move {
+    while let Some(Ok(message)) = socket.recv().await {
+      if let Message::Text(_) | Message::Binary(_) = message {
+        let _ = socket.send(message).await;
+      }
+    }
+  })
+}
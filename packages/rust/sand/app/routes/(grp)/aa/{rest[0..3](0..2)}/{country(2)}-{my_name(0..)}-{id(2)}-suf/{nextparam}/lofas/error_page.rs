@@ -12,7 +12,7 @@ struct MyOtherErr {
   my_other_attr: String,
 }
 
-async fn error_loader(mut error: impl Caught, /* req. extractors... */) -> impl Loadable {
+pub async fn error_loader(mut error: impl Caught, /* req. extractors... */) -> impl Loadable {
   if let Some(err) = error.get_error::<MyErr>() {
     println!("Caught MyErr: {}", err.my_attr);
   }